@@ -0,0 +1,18 @@
+// Timing helper shared by the benchmarks
+use std::time::{Duration, Instant};
+
+// Median over 9 samples of the average time of f over enough runs to take
+// about 50ms
+pub fn time<T>(mut f: impl FnMut() -> T) -> Duration {
+    let mut samples: Vec<Duration> = (0..9).map(|_| {
+        let start = Instant::now();
+        let mut runs = 0;
+        while runs < 2 || start.elapsed() < Duration::from_millis(50) {
+            f();
+            runs += 1;
+        }
+        start.elapsed() / runs
+    }).collect();
+    samples.sort();
+    samples[samples.len() / 2]
+}
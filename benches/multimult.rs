@@ -0,0 +1,64 @@
+// Bos–Coster against Pippenger in MultiMult, on random pairs of P-256 and
+// Tom-256 of the sizes that exp verification produces.
+//      cargo bench --bench multimult
+mod common;
+
+use openssl::bn::{BigNum, BigNumContext};
+use openssl::ec::{EcGroupRef, EcPoint};
+
+use zkattest_rs::curves;
+use zkattest_rs::curves::multimult::MultiMult;
+use zkattest_rs::pedersen::generate_random;
+
+use common::time;
+
+const SIZES: [usize; 7] = [4, 16, 32, 64, 128, 256, 512];
+
+fn random_pairs(group: &EcGroupRef, n: usize) -> Vec<(EcPoint, BigNum)> {
+    let mut ctx = BigNumContext::new().unwrap();
+    let mut order_curve = BigNum::new().unwrap();
+    group.order(&mut order_curve, &mut ctx).unwrap();
+
+    (0..n).map(|_| {
+        let k = generate_random(&order_curve).unwrap();
+        let mut pt = EcPoint::new(group).unwrap();
+        pt.mul_generator2(group, &k, &mut ctx).unwrap();
+        (pt, generate_random(&order_curve).unwrap())
+    }).collect()
+}
+
+fn load<'a>(group: &'a EcGroupRef, pairs: &[(EcPoint, BigNum)]) -> MultiMult<'a> {
+    let mut multi = MultiMult::new(group);
+    for (pt, s) in pairs {
+        multi.insert(pt.as_ref().to_owned(group).unwrap(), s.as_ref().to_owned().unwrap()).unwrap();
+    }
+    multi
+}
+
+fn bench(name: &str, group: &EcGroupRef) {
+    let mut ctx = BigNumContext::new().unwrap();
+
+    println!("{name}");
+    println!("{:>6} {:>14} {:>14}", "pairs", "bos-coster", "pippenger");
+    for n in SIZES {
+        let pairs = random_pairs(group, n);
+
+        let expected = load(group, &pairs).evaluate_bos_coster().unwrap();
+        let got = load(group, &pairs).evaluate_pippenger().unwrap();
+        assert!(expected.eq(group, &got, &mut ctx).unwrap());
+
+        // evaluate_bos_coster consumes the pairs, so it is timed with the
+        // loading, which is then subtracted
+        let loading = time(|| { load(group, &pairs); EcPoint::new(group).unwrap() });
+        let bos_coster = time(|| load(group, &pairs).evaluate_bos_coster().unwrap()).saturating_sub(loading);
+        let multi = load(group, &pairs);
+        let pippenger = time(|| multi.evaluate_pippenger().unwrap());
+        println!("{n:>6} {bos_coster:>14.2?} {pippenger:>14.2?}");
+    }
+    println!();
+}
+
+fn main() {
+    bench("P-256", &curves::p256().unwrap());
+    bench("Tom-256", &curves::tom256().unwrap());
+}
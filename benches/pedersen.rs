@@ -0,0 +1,55 @@
+// Pedersen commitments with and without the precomputed tables of g and h,
+// on the NIST curves and their Tom companions, and secp256k1 and secq256k1.
+// On P-256 with_precomputation keeps OpenSSL's multiplication, so both columns match.
+//      cargo bench --bench pedersen
+mod common;
+
+use std::time::Instant;
+
+use openssl::bn::{BigNum, BigNumContext};
+use openssl::ec::{EcGroupRef, EcPoint};
+
+use zkattest_rs::curves;
+use zkattest_rs::pedersen::{generate_pedersen_params, generate_random, FIXED_BASE_WINDOW};
+
+use common::time;
+
+fn bench(name: &str, group: &EcGroupRef) {
+    let mut ctx = BigNumContext::new().unwrap();
+    let mut order_curve = BigNum::new().unwrap();
+    group.order(&mut order_curve, &mut ctx).unwrap();
+
+    let start = Instant::now();
+    let precomputed = generate_pedersen_params(group).unwrap().with_precomputation().unwrap();
+    let build = start.elapsed();
+    let plain = generate_pedersen_params(group).unwrap();
+
+    let x = generate_random(&order_curve).unwrap();
+    let r = generate_random(&order_curve).unwrap();
+    let expected = plain.commit_with_randomness(&x, &r).unwrap();
+    let got = precomputed.commit_with_randomness(&x, &r).unwrap();
+    assert!(expected.p.eq(group, &got.p, &mut ctx).unwrap());
+
+    let commit_plain = time(|| { plain.commit(&x).unwrap(); });
+    let commit_precomputed = time(|| { precomputed.commit(&x).unwrap(); });
+    let mul_plain = time(|| {
+        let mut p = EcPoint::new(group).unwrap();
+        p.mul2(group, &plain.h, &x, &mut ctx).unwrap();
+    });
+    let mul_precomputed = time(|| { precomputed.mul_h(&x).unwrap(); });
+
+    println!("{name} (tables of {FIXED_BASE_WINDOW}-bit windows built in {build:.2?})");
+    println!("{:>8} {:>12} {:>12}", "", "plain", "precomputed");
+    println!("{:>8} {:>12.2?} {:>12.2?}", "commit", commit_plain, commit_precomputed);
+    println!("{:>8} {:>12.2?} {:>12.2?}", "k h", mul_plain, mul_precomputed);
+    println!();
+}
+
+fn main() {
+    bench("P-256", &curves::p256().unwrap());
+    bench("Tom-256", &curves::tom256().unwrap());
+    bench("P-384", &curves::p384().unwrap());
+    bench("Tom-384", &curves::tom384().unwrap());
+    bench("secp256k1", &curves::secp256k1().unwrap());
+    bench("secq256k1", &curves::secq256k1().unwrap());
+}
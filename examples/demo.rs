@@ -0,0 +1,1406 @@
+#![allow(non_snake_case)]
+#![allow(deprecated)]
+// use std::error::Error;
+use openssl::ec::{EcGroup, EcKey, EcPoint, PointConversionForm};
+use openssl::ecdsa::EcdsaSig;
+use openssl::bn::{BigNum, BigNumContext};
+use openssl::hash::{hash, MessageDigest};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+use zkattest_rs::{curves, pedersen, equality, mult};
+use zkattest_rs::curves::CurvePair;
+use zkattest_rs::curves::multimult::{MultiMult, PIPPENGER_THRESHOLD};
+use zkattest_rs::{prove_point_add, verify_point_add, prov_exp, prov_exp_with_rng, verify_exp};
+use zkattest_rs::{EqualityBatchItem, PointAddBatchItem, ExpBatchItem, batch_verify_equality, batch_verify_point_add, batch_verify_exp};
+use zkattest_rs::exp::exp::{padded_bits, generate_indices, generate_indices_with_rng};
+use zkattest_rs::{SystemParameters, prove_signature, prove_signature_with_rng, prove_signature_deterministic, verify_signature};
+use zkattest_rs::{prove_signature_list, prove_signature_list_deterministic, verify_signature_list};
+use zkattest_rs::{HashFunction, Transcript};
+use zkattest_rs::{EqualityProof, MultProof, PointAddProof, SignatureProof, SignatureListProof, exp_to_bytes, exp_from_bytes};
+
+
+
+fn main() {
+    println!("Hello, world!");
+
+    // ========================== Testing units ==========================
+
+    // Create a new P256 curve object
+    let group = curves::p256().unwrap();
+
+    // Creat new T256 curve object
+    let tom_group = curves::tom256().unwrap();
+
+    assert!(curves::is_companion(&group, &tom_group).unwrap());
+
+    // A wrong cofactor puts the group order outside the Hasse bound
+    let bad_cofactor = curves::CurveParams { cofactor: 2, ..curves::TOM256 };
+    assert!(matches!(curves::from_params(&bad_cofactor), Err(zkattest_rs::ZkError::InvalidCurve(_))));
+    println!("from_params rejects a wrong cofactor: true");
+
+    // ========================== pedersen.rs ==========================
+    { // =========================== add ===============================
+        // Generate two points randomly
+        let point1 = EcPoint::new(&group).unwrap();
+        let point2 = EcPoint::new(&group).unwrap();
+
+        let bign43 = BigNum::from_dec_str("43").unwrap();
+        let bign2 = BigNum::from_dec_str("2").unwrap();
+
+        let c1 = pedersen::Commitment::new(&group, point1, bign43);
+        let c2 = pedersen::Commitment::new(&group, point2, bign2);
+        let c3 = c1.add(&c2).unwrap();
+
+        println!("The result of adding commitments is {} = 45?", c3.r);
+    }
+
+    { // =========================== sub ===============================
+        // Generate two points randomly
+        let point1 = EcPoint::new(&group).unwrap();
+        let point2 = EcPoint::new(&group).unwrap();
+
+        let bign43 = BigNum::from_dec_str("43").unwrap();
+        let bign2 = BigNum::from_dec_str("2").unwrap();
+
+        let c1 = pedersen::Commitment::new(&group, point1, bign43);
+        let c2 = pedersen::Commitment::new(&group, point2, bign2);
+        let c3 = c1.sub(&c2).unwrap();
+
+        println!("The result of adding commitments is {} = 41?", c3.r);
+    }
+
+    { // =========================== mul ===============================
+        // Generate one point randomly
+        let point1 = EcPoint::new(&group).unwrap();
+
+        let bign43 = BigNum::from_dec_str("43").unwrap();
+        let bign2 = BigNum::from_dec_str("2").unwrap();
+
+        let mut c1 = pedersen::Commitment::new(&group, point1, bign43);
+        let c3 = c1.mul(&bign2).unwrap();
+
+        println!("The result of adding commitments is {} = 86?", c3.r);
+    }
+
+
+    { // =========================== new ===============================
+        let g = EcPoint::new(&group).unwrap();
+        let h = EcPoint::new(&group).unwrap();
+        let pp = pedersen::PedersenParams::new(&group, g, h);
+        
+        let bign101 = BigNum::from_dec_str("101").unwrap();
+        pp.commit(&bign101).unwrap();
+    }
+
+
+    { // ================= generate_pedersen_params ====================
+        let pp = pedersen::generate_pedersen_params(&group).unwrap();
+        
+        let bign101 = BigNum::from_dec_str("101").unwrap();
+        pp.commit(&bign101).unwrap();
+    }
+
+    { // ================= commit_with_randomness ====================
+        let pp = pedersen::generate_pedersen_params(&group).unwrap();
+        let mut ctx = BigNumContext::new().unwrap();
+        let mut order_curve = BigNum::new().unwrap();
+        group.order(&mut order_curve, &mut ctx).unwrap();
+
+        // randomness is uniform in [1, n)
+        let com = pp.commit(&BigNum::from_u32(101).unwrap()).unwrap();
+        assert!(com.r.num_bits() > 0 && com.r < order_curve);
+
+        // values and blinding are reduced mod n
+        let r = BigNum::from_u32(7).unwrap();
+        let com_r = pp.commit_with_randomness(&BigNum::from_u32(101).unwrap(), &r).unwrap();
+        let mut value_plus_n = BigNum::new().unwrap();
+        value_plus_n.checked_add(&BigNum::from_u32(101).unwrap(), &order_curve).unwrap();
+        let mut r_plus_n = BigNum::new().unwrap();
+        r_plus_n.checked_add(&r, &order_curve).unwrap();
+        let com_oversized = pp.commit_with_randomness(&value_plus_n, &r_plus_n).unwrap();
+        assert!(com_r.p.eq(&group, &com_oversized.p, &mut ctx).unwrap());
+        assert!(com_oversized.r == r);
+        println!("commit_with_randomness reduces its inputs: {}", com_oversized.r == r);
+    }
+
+    { // ================= with_precomputation ====================
+        let mut ctx = BigNumContext::new().unwrap();
+        let mut order_curve = BigNum::new().unwrap();
+        tom_group.order(&mut order_curve, &mut ctx).unwrap();
+
+        let plain = pedersen::generate_pedersen_params(&tom_group).unwrap();
+        let precomputed = pedersen::generate_pedersen_params(&tom_group).unwrap().with_precomputation().unwrap();
+        assert!(precomputed.eq(&plain));
+
+        // k g and k h for k = 0, 1, -3, n - 1, n, n + 5 and a random k
+        let mut ks = vec![BigNum::new().unwrap(), BigNum::from_u32(1).unwrap(), BigNum::from_dec_str("-3").unwrap()];
+        for (add, sub) in [(0, 1), (0, 0), (5, 0)] {
+            let mut k = BigNum::new().unwrap();
+            k.checked_add(&order_curve, &BigNum::from_u32(add).unwrap()).unwrap();
+            let mut k_sub = BigNum::new().unwrap();
+            k_sub.checked_sub(&k, &BigNum::from_u32(sub).unwrap()).unwrap();
+            ks.push(k_sub);
+        }
+        ks.push(pedersen::generate_random(&order_curve).unwrap());
+        for k in &ks {
+            let mut k_g = EcPoint::new(&tom_group).unwrap();
+            k_g.mul(&tom_group, &plain.g, k, &ctx).unwrap();
+            let mut k_h = EcPoint::new(&tom_group).unwrap();
+            k_h.mul(&tom_group, &plain.h, k, &ctx).unwrap();
+            assert!(precomputed.mul_g(k).unwrap().eq(&tom_group, &k_g, &mut ctx).unwrap());
+            assert!(precomputed.mul_h(k).unwrap().eq(&tom_group, &k_h, &mut ctx).unwrap());
+
+            let com = precomputed.commit_with_randomness(k, &ks[6]).unwrap();
+            let com_plain = plain.commit_with_randomness(k, &ks[6]).unwrap();
+            assert!(com.p.eq(&tom_group, &com_plain.p, &mut ctx).unwrap());
+        }
+
+        // any window from 1 to 16 bits
+        let table_1 = pedersen::FixedBaseTable::new(&tom_group, &plain.h, 1).unwrap();
+        let mut k_h = EcPoint::new(&tom_group).unwrap();
+        k_h.mul(&tom_group, &plain.h, &ks[6], &ctx).unwrap();
+        assert!(table_1.mul(&tom_group, &ks[6]).unwrap().eq(&tom_group, &k_h, &mut ctx).unwrap());
+        assert!(pedersen::FixedBaseTable::new(&tom_group, &plain.h, 0).is_err());
+        assert!(pedersen::FixedBaseTable::new(&tom_group, &plain.h, 17).is_err());
+        assert!(pedersen::FixedBaseTable::new(&tom_group, &EcPoint::new(&tom_group).unwrap(), 6).is_err());
+
+        // every digit of the two lowest windows, zero digits included
+        for k in 0..(1u32 << (2 * pedersen::FIXED_BASE_WINDOW)) {
+            let k = BigNum::from_u32(k).unwrap();
+            let mut k_h = EcPoint::new(&tom_group).unwrap();
+            k_h.mul(&tom_group, &plain.h, &k, &ctx).unwrap();
+            assert!(precomputed.mul_h(&k).unwrap().eq(&tom_group, &k_h, &mut ctx).unwrap());
+        }
+
+        // OpenSSL is faster than the tables on P-256, which keeps mul_full
+        let p256_params = pedersen::generate_pedersen_params(&group).unwrap().with_precomputation().unwrap();
+        assert!(p256_params.table_g.is_none() && p256_params.table_h.is_none());
+        println!("precomputed tables agree with plain multiplication: true");
+    }
+
+    { // ================= MultiMult: Bos–Coster and Pippenger ====================
+        let mut ctx = BigNumContext::new().unwrap();
+        let mut order_curve = BigNum::new().unwrap();
+        group.order(&mut order_curve, &mut ctx).unwrap();
+
+        for n in [0, 1, 2, 5, PIPPENGER_THRESHOLD - 1, PIPPENGER_THRESHOLD, 3 * PIPPENGER_THRESHOLD] {
+            let mut bos_coster = MultiMult::new(&group);
+            let mut pippenger = MultiMult::new(&group);
+            // sum of s_i k_i g, computed directly
+            let mut expected_k = BigNum::new().unwrap();
+            for i in 0..n {
+                let k = pedersen::generate_random(&order_curve).unwrap();
+                // zero and the order itself are valid scalars too
+                let s = match i {
+                    0 => BigNum::new().unwrap(),
+                    1 => order_curve.to_owned().unwrap(),
+                    _ => pedersen::generate_random(&order_curve).unwrap(),
+                };
+                let mut pt = EcPoint::new(&group).unwrap();
+                pt.mul_generator2(&group, &k, &mut ctx).unwrap();
+
+                let mut sk = BigNum::new().unwrap();
+                sk.mod_mul(&s, &k, &order_curve, &mut ctx).unwrap();
+                let mut sum = BigNum::new().unwrap();
+                sum.mod_add(&expected_k, &sk, &order_curve, &mut ctx).unwrap();
+                expected_k = sum;
+
+                bos_coster.insert(pt.to_owned(&group).unwrap(), s.to_owned().unwrap()).unwrap();
+                pippenger.insert(pt, s).unwrap();
+            }
+            let mut expected = EcPoint::new(&group).unwrap();
+            expected.mul_generator2(&group, &expected_k, &mut ctx).unwrap();
+
+            let pt_pippenger = pippenger.evaluate_pippenger().unwrap();
+            let pt_bos_coster = bos_coster.evaluate_bos_coster().unwrap();
+            assert!(pt_pippenger.eq(&group, &expected, &mut ctx).unwrap());
+            assert!(pt_bos_coster.eq(&group, &expected, &mut ctx).unwrap());
+            // evaluate picks either
+            assert!(pippenger.evaluate().unwrap().eq(&group, &expected, &mut ctx).unwrap());
+        }
+        println!("MultiMult Bos–Coster and Pippenger agree: true");
+
+        // inserts of a known base merge into one pair, summed mod the order
+        let mut multi = MultiMult::new(&group);
+        let g = group.generator().to_owned(&group).unwrap();
+        multi.add_known(g.to_owned(&group).unwrap()).unwrap();
+        multi.add_known(g.to_owned(&group).unwrap()).unwrap();
+        let mut n_minus_1 = BigNum::new().unwrap();
+        n_minus_1.checked_sub(&order_curve, &BigNum::from_u32(1).unwrap()).unwrap();
+        for _ in 0..3 {
+            multi.insert(g.to_owned(&group).unwrap(), n_minus_1.to_owned().unwrap()).unwrap();
+        }
+        assert!(multi.pairs.len() == 1);
+        // 3 (n - 1) = n - 3
+        let mut n_minus_3 = BigNum::new().unwrap();
+        n_minus_3.checked_sub(&order_curve, &BigNum::from_u32(3).unwrap()).unwrap();
+        assert!(multi.pairs[0].scalar == n_minus_3);
+        let mut minus_3g = EcPoint::new(&group).unwrap();
+        minus_3g.mul_generator2(&group, &n_minus_3, &mut ctx).unwrap();
+        assert!(multi.evaluate().unwrap().eq(&group, &minus_3g, &mut ctx).unwrap());
+        println!("MultiMult merges inserts of known bases: true");
+    }
+
+    { // =========================== eq ===============================
+        let pp_1 = pedersen::generate_pedersen_params(&group).unwrap();
+        let pp_2 = pedersen::generate_pedersen_params_from_seed(&group, b"another seed").unwrap();
+    
+        let bool_false = pp_1.eq(&pp_2);
+        let bool_true = pp_1.eq(&pp_1);
+
+        assert!(!bool_false);
+        assert!(bool_true);
+
+        // parameters are reproducible and checkable from their seed
+        assert!(pp_1.eq(&pedersen::generate_pedersen_params(&group).unwrap()));
+        assert!(pedersen::verify_pedersen_params(&pp_1, pedersen::PEDERSEN_SEED).unwrap());
+        assert!(pedersen::verify_pedersen_params(&pp_2, b"another seed").unwrap());
+        assert!(!pedersen::verify_pedersen_params(&pp_2, pedersen::PEDERSEN_SEED).unwrap());
+
+        // h is the RFC 9380 hash of the seed to the curve, under a tag naming the suite of the curve
+        let mut ctx = BigNumContext::new().unwrap();
+        assert_eq!(curves::suite_id(&group).unwrap(), "P256_XMD:SHA-256_SSWU_RO_");
+        let h_seed = curves::hash_to_curve(&group, b"zkattest-V01-CS01-pedersen-h-P256_XMD:SHA-256_SSWU_RO_", pedersen::PEDERSEN_SEED).unwrap();
+        assert!(pp_1.h.eq(&group, &h_seed, &mut ctx).unwrap());
+
+        // h = r g for a known r is rejected
+        let mut order_curve = BigNum::new().unwrap();
+        group.order(&mut order_curve, &mut ctx).unwrap();
+        let r = pedersen::generate_random(&order_curve).unwrap();
+        let mut h = EcPoint::new(&group).unwrap();
+        h.mul_generator(&group, &r, &ctx).unwrap();
+        let pp_dlog = pedersen::PedersenParams::new(&group, group.generator().to_owned(&group).unwrap(), h);
+        let honest = pedersen::verify_pedersen_params(&pp_dlog, pedersen::PEDERSEN_SEED).unwrap();
+        println!("verify_pedersen_params rejects h with known discrete log: {}", !honest);
+        assert!(!honest);
+
+        // h is derived on the companion curves too
+        for pair in [CurvePair::P256Tom256, CurvePair::P384Tom384, CurvePair::P521Tom521, CurvePair::Secp256k1Secq256k1] {
+            let tom = pair.tom().unwrap();
+            let pp_tom = pedersen::generate_pedersen_params(&tom).unwrap();
+            assert!(pedersen::verify_pedersen_params(&pp_tom, pedersen::PEDERSEN_SEED).unwrap());
+        }
+    }
+    
+
+    // ========================== hash_to_curve.rs ==========================
+
+    {
+        let mut ctx = BigNumContext::new().unwrap();
+        let to_hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{b:02x}")).collect::<String>();
+
+        // RFC 9380, Appendix K.1: expand_message_xmd(SHA-256)
+        let dst_xmd = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        for (msg, uniform_bytes) in [
+            (&b""[..], "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"),
+            (&b"abc"[..], "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"),
+        ] {
+            assert_eq!(to_hex(&curves::expand_message_xmd(MessageDigest::sha256(), msg, dst_xmd, 0x20).unwrap()), uniform_bytes);
+        }
+
+        // RFC 9380, Appendix J: P256_XMD:SHA-256_SSWU_RO_, P384_XMD:SHA-384_SSWU_RO_,
+        // P521_XMD:SHA-512_SSWU_RO_ and secp256k1_XMD:SHA-256_SSWU_RO_
+        assert!(curves::find_z_sswu(&group).unwrap() == BigNum::from_hex_str("ffffffff00000001000000000000000000000000fffffffffffffffffffffff5").unwrap());
+        let q128 = format!("q128_{}", "q".repeat(128));
+        let a512 = format!("a512_{}", "a".repeat(512));
+        let suites = [
+            (curves::CurvePair::P256Tom256, &b"QUUX-V01-CS02-with-P256_XMD:SHA-256_SSWU_RO_"[..], [
+                ("", "2c15230b26dbc6fc9a37051158c95b79656e17a1a920b11394ca91c44247d3e4", "8a7a74985cc5c776cdfe4b1f19884970453912e9d31528c060be9ab5c43e8415"),
+                ("abc", "0bb8b87485551aa43ed54f009230450b492fead5f1cc91658775dac4a3388a0f", "5c41b3d0731a27a7b14bc0bf0ccded2d8751f83493404c84a88e71ffd424212e"),
+                (&a512, "457ae2981f70ca85d8e24c308b14db22f3e3862c5ea0f652ca38b5e49cd64bc5", "ecb9f0eadc9aeed232dabc53235368c1394c78de05dd96893eefa62b0f4757dc"),
+            ]),
+            (curves::CurvePair::P384Tom384, &b"QUUX-V01-CS02-with-P384_XMD:SHA-384_SSWU_RO_"[..], [
+                ("", "eb9fe1b4f4e14e7140803c1d99d0a93cd823d2b024040f9c067a8eca1f5a2eeac9ad604973527a356f3fa3aeff0e4d83", "0c21708cff382b7f4643c07b105c2eaec2cead93a917d825601e63c8f21f6abd9abc22c93c2bed6f235954b25048bb1a"),
+                ("abc", "e02fc1a5f44a7519419dd314e29863f30df55a514da2d655775a81d413003c4d4e7fd59af0826dfaad4200ac6f60abe1", "01f638d04d98677d65bef99aef1a12a70a4cbb9270ec55248c04530d8bc1f8f90f8a6a859a7c1f1ddccedf8f96d675f6"),
+                (&q128, "03c3a9f401b78c6c36a52f07eeee0ec1289f178adf78448f43a3850e0456f5dd7f7633dd31676d990eda32882ab486c0", "cc183d0d7bdfd0a3af05f50e16a3f2de4abbc523215bf57c848d5ea662482b8c1f43dc453a93b94a8026db58f3f5d878"),
+            ]),
+            (curves::CurvePair::P521Tom521, &b"QUUX-V01-CS02-with-P521_XMD:SHA-512_SSWU_RO_"[..], [
+                ("", "00fd767cebb2452030358d0e9cf907f525f50920c8f607889a6a35680727f64f4d66b161fafeb2654bea0d35086bec0a10b30b14adef3556ed9f7f1bc23cecc9c088", "0169ba78d8d851e930680322596e39c78f4fe31b97e57629ef6460ddd68f8763fd7bd767a4e94a80d3d21a3c2ee98347e024fc73ee1c27166dc3fe5eeef782be411d"),
+                ("abc", "002f89a1677b28054b50d15e1f81ed6669b5a2158211118ebdef8a6efc77f8ccaa528f698214e4340155abc1fa08f8f613ef14a043717503d57e267d57155cf784a4", "010e0be5dc8e753da8ce51091908b72396d3deed14ae166f66d8ebf0a4e7059ead169ea4bead0232e9b700dd380b316e9361cfdba55a08c73545563a80966ecbb86d"),
+                (&a512, "00c12bc3e28db07b6b4d2a2b1167ab9e26fc2fa85c7b0498a17b0347edf52392856d7e28b8fa7a2dd004611159505835b687ecf1a764857e27e9745848c436ef3925", "01cd287df9a50c22a9231beb452346720bb163344a41c5f5a24e8335b6ccc595fd436aea89737b1281aecb411eb835f0b939073fdd1dd4d5a2492e91ef4a3c55bcbd"),
+            ]),
+            (curves::CurvePair::Secp256k1Secq256k1, &b"QUUX-V01-CS02-with-secp256k1_XMD:SHA-256_SSWU_RO_"[..], [
+                ("", "c1cae290e291aee617ebaef1be6d73861479c48b841eaba9b7b5852ddfeb1346", "64fa678e07ae116126f08b022a94af6de15985c996c3a91b64c406a960e51067"),
+                ("abc", "3377e01eab42db296b512293120c6cee72b6ecf9f9205760bd9ff11fb3cb2c4b", "7f95890f33efebd1044d382a01b1bee0900fb6116f94688d487c6c7b9c8371f6"),
+                (&q128, "e2167bc785333a37aa562f021f1e881defb853839babf52a7f72b102e41890e9", "f2401dd95cc35867ffed4f367cd564763719fbc6a53e969fb8496a1e6685d873"),
+            ]),
+        ];
+        for (pair, dst, vectors) in suites {
+            let nist = pair.nist().unwrap();
+            for (msg, P_x, P_y) in vectors {
+                let P = curves::hash_to_curve(&nist, dst, msg.as_bytes()).unwrap();
+                let mut x = BigNum::new().unwrap();
+                let mut y = BigNum::new().unwrap();
+                P.affine_coordinates_gfp(&nist, &mut x, &mut y, &mut ctx).unwrap();
+                assert!(x == BigNum::from_hex_str(P_x).unwrap() && y == BigNum::from_hex_str(P_y).unwrap());
+            }
+        }
+        println!("hash_to_curve matches the RFC 9380 P-256, P-384, P-521 and secp256k1 vectors: true");
+
+        // The companion curves have no standard suite: Z is the one of RFC 9380,
+        // Appendix H, and points are on the curve, deterministic and separated by
+        // message and tag
+        let small = |z: i32, p: &BigNum| {
+            let mut r = BigNum::new().unwrap();
+            r.checked_sub(p, BigNum::from_u32(z.unsigned_abs()).unwrap().as_ref()).unwrap();
+            if z < 0 { r } else { BigNum::from_u32(z as u32).unwrap() }
+        };
+        for (pair, z) in [
+            (curves::CurvePair::P256Tom256, curves::TOM256_Z),
+            (curves::CurvePair::P384Tom384, curves::TOM384_Z),
+            (curves::CurvePair::P521Tom521, curves::TOM521_Z),
+            (curves::CurvePair::Secp256k1Secq256k1, curves::SECQ256K1_Z),
+        ] {
+            let tom = pair.tom().unwrap();
+            let mut p = BigNum::new().unwrap();
+            let mut a = BigNum::new().unwrap();
+            let mut b = BigNum::new().unwrap();
+            tom.components_gfp(&mut p, &mut a, &mut b, &mut ctx).unwrap();
+            let found = if a.num_bits() == 0 { curves::find_z_svdw(&tom) } else { curves::find_z_sswu(&tom) };
+            assert!(found.unwrap() == small(z, &p));
+
+            let dst_tom = format!("zkattest-V01-CS01-with-{}", pair.name());
+            let dst_tom = dst_tom.as_bytes();
+            let P_1 = curves::hash_to_curve(&tom, dst_tom, b"abc").unwrap();
+            let P_2 = curves::hash_to_curve(&tom, dst_tom, b"abc").unwrap();
+            let P_3 = curves::hash_to_curve(&tom, dst_tom, b"abd").unwrap();
+            let P_4 = curves::hash_to_curve(&tom, b"another tag", b"abc").unwrap();
+            assert!(P_1.is_on_curve(&tom, &mut ctx).unwrap() && !P_1.is_infinity(&tom));
+            assert!(P_1.eq(&tom, &P_2, &mut ctx).unwrap());
+            assert!(!P_1.eq(&tom, &P_3, &mut ctx).unwrap());
+            assert!(!P_1.eq(&tom, &P_4, &mut ctx).unwrap());
+            for i in 0..32u32 {
+                let P = curves::hash_to_curve(&tom, dst_tom, &i.to_be_bytes()).unwrap();
+                assert!(P.is_on_curve(&tom, &mut ctx).unwrap());
+            }
+        }
+        println!("hash_to_curve on the companion curves is deterministic and on the curve: true");
+
+        // curves without a suite are rejected
+        let brainpool = openssl::ec::EcGroup::from_curve_name(openssl::nid::Nid::BRAINPOOL_P256R1).unwrap();
+        assert!(matches!(curves::hash_to_curve(&brainpool, b"tag", b"abc"), Err(zkattest_rs::ZkError::InvalidCurve(_))));
+    }
+
+
+    // ========================== equality.rs ==========================
+
+    {
+        
+        let mut ctx = BigNumContext::new().unwrap();
+
+        let g = group.generator();
+        
+        let mut order_curve = BigNum::new().unwrap();
+        group.order(&mut order_curve, &mut ctx).unwrap();
+        let r = pedersen::generate_random(&order_curve).unwrap();
+
+        // println!("Size is: {:?}", r);
+        
+        let mut h = EcPoint::new(&group).unwrap();
+        h.mul(&group, g, &r, &ctx).unwrap();
+
+        let pp = pedersen::generate_pedersen_params(&group).unwrap();
+        let g = g.to_owned(&group).unwrap();
+        // challenge over the parameters and g, h
+        let challenge = |hash_fn: HashFunction, label: &[u8], params: &pedersen::PedersenParams| {
+            let mut transcript = Transcript::with_hash(label, hash_fn);
+            transcript.append_params(b"params", params).unwrap();
+            transcript.append_point(b"point", &group, &g).unwrap();
+            transcript.append_point(b"point", &group, &h).unwrap();
+            transcript.challenge_scalar(b"challenge", &group).unwrap()
+        };
+        let hash_value = challenge(HashFunction::Sha256, equality::LABEL_EQUALITY, &pp);
+        println!("hash_value is: {:?}", hash_value);
+        assert!(hash_value < order_curve);
+
+        // challenges are separated by protocol label and parameters
+        let hash_mult = challenge(HashFunction::Sha256, mult::LABEL_MULT, &pp);
+        assert!(hash_value != hash_mult);
+        let other_pp = pedersen::generate_pedersen_params_from_seed(&group, b"another seed").unwrap();
+        let hash_other = challenge(HashFunction::Sha256, equality::LABEL_EQUALITY, &other_pp);
+        assert!(hash_value != hash_other);
+
+        // and by hash function
+        for hash_fn in [HashFunction::Sha384, HashFunction::Sha512, HashFunction::Sha3_256, HashFunction::Shake256] {
+            let hash_fn_value = challenge(hash_fn, equality::LABEL_EQUALITY, &pp);
+            assert!(hash_fn_value < order_curve);
+            assert!(hash_value != hash_fn_value);
+            assert_eq!(HashFunction::from_id(hash_fn.id()).unwrap(), hash_fn);
+            assert_eq!(HashFunction::from_name(hash_fn.name()).unwrap(), hash_fn);
+        }
+        assert!(HashFunction::from_id(0).is_err());
+
+    }
+
+    {
+        let g = EcPoint::new(&group).unwrap();
+        let infinity = g.is_infinity(&group);
+        println!("EcPoint initialized at infinity: {}", infinity);
+    }
+
+    {  // Check invert is the same as multiply by -1
+        //Generate random point::
+        let mut ctx = BigNumContext::new().unwrap();
+        let g = group.generator();
+        let mut order_curve = BigNum::new().unwrap();
+        group.order(&mut order_curve, &mut ctx).unwrap();
+        let r = pedersen::generate_random(&order_curve).unwrap();
+        let mut h = EcPoint::new(&group).unwrap();
+        h.mul(&group, g, &r, &ctx).unwrap();
+
+
+
+        // Multiply by -1
+        let minus_1 = BigNum::from_dec_str("-1").unwrap(); 
+        let mut minus_h = EcPoint::new(&group).unwrap();
+        minus_h.mul(&group, &h, &minus_1, &ctx).unwrap();
+
+        //use invert
+        h.invert(&group, &ctx).unwrap();
+
+        let invertibility = h.eq(&group, &minus_h, &mut ctx).unwrap();
+
+        println!("h.inv == -h is: {}", invertibility);
+
+    }
+
+    {       // ====== CHECK THE EQUALITY FUNCTIONS ====== //
+
+        let pparams = pedersen::generate_pedersen_params(&group).unwrap();
+        
+        let same_bign10 = BigNum::from_dec_str("10").unwrap();
+        //let same_bign10 = BigNum::from_dec_str("10").unwrap();
+        let diff_bign11 = BigNum::from_dec_str("11").unwrap();
+        
+        // ============== Generate commitments & points
+        // commitments
+        let com_1_same_bign10 = pparams.commit(&same_bign10).unwrap();
+        let com_2_same_bign10 = pparams.commit(&same_bign10).unwrap();
+        // points
+        let com_1_same_bign10_point = com_1_same_bign10.p.to_owned(&group).unwrap();
+        let com_2_same_bign10_point = com_2_same_bign10.p.to_owned(&group).unwrap();
+
+        // commitments
+        let com_1_diff_bign10 = pparams.commit(&same_bign10).unwrap();
+        let com_2_diff_bign11 = pparams.commit(&diff_bign11).unwrap();
+        // points
+        let com_1_diff_bign10_point = com_1_diff_bign10.p.to_owned(&group).unwrap();
+        let com_2_diff_bign11_point = com_2_diff_bign11.p.to_owned(&group).unwrap();
+
+
+        // ============== Test true 
+
+        let pi_eq_same = equality::prove_equality(&pparams, same_bign10, com_1_same_bign10, com_2_same_bign10, &mut Transcript::new(b"demo")).unwrap();
+
+        let ver_eq_true = equality::verify_equality(&pparams, com_1_same_bign10_point, com_2_same_bign10_point, &pi_eq_same, &mut Transcript::new(b"demo")).unwrap();
+        println!("The true equality test is: {}", ver_eq_true);
+        assert!(ver_eq_true);
+
+        let pi_eq_bytes = pi_eq_same.to_bytes().unwrap();
+        let pi_eq_decoded = EqualityProof::from_bytes(&group, &pi_eq_bytes).unwrap();
+        println!("EqualityProof encoding roundtrips: {}", pi_eq_decoded.eq(&pi_eq_same));
+        assert!(pi_eq_decoded.eq(&pi_eq_same));
+
+
+        // ============== Test false 
+
+        let pi_eq_diff = equality::prove_equality(&pparams, diff_bign11, com_1_diff_bign10, com_2_diff_bign11, &mut Transcript::new(b"demo")).unwrap();
+
+        let ver_eq_false = equality::verify_equality(&pparams, com_1_diff_bign10_point, com_2_diff_bign11_point, &pi_eq_diff, &mut Transcript::new(b"demo")).unwrap();
+        println!("The false equality test is: {}", ver_eq_false);
+        assert!(!ver_eq_false);
+
+        // ============== Test custom base g
+
+        let mut ctx = BigNumContext::new().unwrap();
+        let mut g_5 = EcPoint::new(&group).unwrap();
+        g_5.mul_generator(&group, &BigNum::from_u32(5).unwrap(), &ctx).unwrap();
+        let custom_params = pedersen::PedersenParams::new(&group, g_5, pparams.h.to_owned(&group).unwrap());
+
+        // C = 3 (5 G) + r H
+        let com_custom = custom_params.commit(&BigNum::from_u32(3).unwrap()).unwrap();
+        let mut expected = EcPoint::new(&group).unwrap();
+        expected.mul_full(&group, &BigNum::from_u32(15).unwrap(), &custom_params.h, &com_custom.r, &mut ctx).unwrap();
+        assert!(com_custom.p.eq(&group, &expected, &mut ctx).unwrap());
+
+        let com_custom_2 = custom_params.commit(&BigNum::from_u32(3).unwrap()).unwrap();
+        let com_custom_point = com_custom.p.to_owned(&group).unwrap();
+        let com_custom_2_point = com_custom_2.p.to_owned(&group).unwrap();
+        let pi_eq_custom = equality::prove_equality(&custom_params, BigNum::from_u32(3).unwrap(), com_custom, com_custom_2, &mut Transcript::new(b"demo")).unwrap();
+        let ver_eq_custom = equality::verify_equality(&custom_params, com_custom_point, com_custom_2_point, &pi_eq_custom, &mut Transcript::new(b"demo")).unwrap();
+        println!("equality proof works with a custom base g: {}", ver_eq_custom);
+        assert!(ver_eq_custom);
+
+        // ============== Test batch verification
+
+        // proofs that 0, ..., 7 are committed twice
+        let mut statements = vec![];
+        let mut proofs = vec![];
+        for v in 0..8 {
+            let value = BigNum::from_u32(v).unwrap();
+            let com_1 = pparams.commit(&value).unwrap();
+            let com_2 = pparams.commit(&value).unwrap();
+            statements.push((com_1.p.to_owned(&group).unwrap(), com_2.p.to_owned(&group).unwrap()));
+            proofs.push(equality::prove_equality(&pparams, value, com_1, com_2, &mut Transcript::new(b"demo")).unwrap());
+        }
+        let other_com = pparams.commit(&BigNum::from_u32(100).unwrap()).unwrap();
+        let batch_items = |invalid: bool| -> Vec<EqualityBatchItem> {
+            statements.iter().zip(&proofs).enumerate().map(|(i, ((C1, C2), pi))| {
+                // 2 is made in another session and 5 is about another commitment
+                let label: &[u8] = if invalid && i == 2 { b"other session" } else { b"demo" };
+                let C2 = if invalid && i == 5 { &other_com.p } else { C2 };
+                EqualityBatchItem {
+                    C1: C1.as_ref().to_owned(&group).unwrap(),
+                    C2: C2.as_ref().to_owned(&group).unwrap(),
+                    pi,
+                    transcript: Transcript::new(label),
+                }
+            }).collect()
+        };
+        let batch_valid = batch_verify_equality(&pparams, batch_items(false)).unwrap();
+        assert!(batch_valid.is_empty());
+        let batch_invalid = batch_verify_equality(&pparams, batch_items(true)).unwrap();
+        println!("batch equality verification finds the invalid proofs: {:?}", batch_invalid);
+        assert_eq!(batch_invalid, vec![2, 5]);
+        assert!(batch_verify_equality(&pparams, vec![]).unwrap().is_empty());
+
+    }
+
+    {   // ====== CHECK THE MULT FUNCTIONS ====== //
+
+        // ZK(x, y, z, rx, ry, rz: z = x * y and Cx = xG + rx H and Cy = yG + ry H and Cz = zG + rz H)
+ 
+        let pparams = pedersen::generate_pedersen_params(&tom_group).unwrap();
+        
+        // Generate numbers
+        let x = BigNum::from_dec_str("2").unwrap();         // x = 2
+        let y = BigNum::from_dec_str("3").unwrap();         // y = 3
+        let z = BigNum::from_dec_str("6").unwrap();         // z = x * y = 6
+        let z_diff = BigNum::from_dec_str("7").unwrap();    // z != x * y
+
+        // Generate commitments
+        let com_x = pparams.commit(&x).unwrap();
+        let com_y = pparams.commit(&y).unwrap();
+        let com_z = pparams.commit(&z).unwrap();
+        let com_z_diff = pparams.commit(&z_diff).unwrap();
+        
+        // ============== Test true 
+        let pi_mult_true = mult::prov_mult(&pparams, 
+                                            x.to_owned().unwrap(), 
+                                            y.to_owned().unwrap(), 
+                                            z.to_owned().unwrap(), 
+                                            com_x.to_owned().unwrap(), 
+                                            com_y.to_owned().unwrap(), 
+                                            com_z.to_owned().unwrap(),
+                                            &mut Transcript::new(b"demo")).unwrap();
+
+        let ver_mult_true = mult::verify_mult(&pparams, 
+                                            com_x.p.to_owned(pparams.c).unwrap(), 
+                                            com_y.p.to_owned(pparams.c).unwrap(),
+                                            com_z.p.to_owned(pparams.c).unwrap(),
+                                            &pi_mult_true,
+                                            &mut Transcript::new(b"demo")).unwrap();
+
+        println!("The true mult test is: {}", ver_mult_true);
+        assert!(ver_mult_true);
+
+        let pi_mult_bytes = pi_mult_true.to_bytes().unwrap();
+        let pi_mult_decoded = MultProof::from_bytes(&tom_group, &pi_mult_bytes).unwrap();
+        println!("MultProof encoding roundtrips: {}", pi_mult_decoded.eq(&pi_mult_true));
+        assert!(pi_mult_decoded.eq(&pi_mult_true));
+
+        // ============== Test false 
+        let _pi_mult_false = mult::prov_mult(&pparams, 
+                                        x.to_owned().unwrap(), 
+                                        y.to_owned().unwrap(), 
+                                        z_diff.to_owned().unwrap(), 
+                                        com_x.to_owned().unwrap(), 
+                                        com_y.to_owned().unwrap(), 
+                                        com_z_diff.to_owned().unwrap(),
+                                        &mut Transcript::new(b"demo")).unwrap();
+
+        let ver_mult_false = mult::verify_mult(&pparams, 
+                                        com_x.p.to_owned(pparams.c).unwrap(), 
+                                        com_y.p.to_owned(pparams.c).unwrap(),
+                                        com_z_diff.p.to_owned(pparams.c).unwrap(),
+                                        &pi_mult_true,
+                                        &mut Transcript::new(b"demo")).unwrap();
+
+        println!("The false mult test is: {}", ver_mult_false);
+        assert!(!ver_mult_false);
+
+
+
+    }
+
+    { 
+        let mut ctx = BigNumContext::new().unwrap();
+
+        let mut order_curve = BigNum::new().unwrap();
+        group.order(&mut order_curve, &mut ctx).unwrap();
+
+        let bign_minus10 = BigNum::from_dec_str("-10").unwrap();
+
+        let mut cc = BigNum::new().unwrap();
+        cc.nnmod(&bign_minus10, &order_curve, &mut ctx).unwrap();
+
+        println!("-10 mod order_curve = {} > 0", cc);
+    }
+
+    {       // ====== CHECK THE pointAdd FUNCTIONS ====== //
+
+        let mut ctx = BigNumContext::new().unwrap();
+
+        let pparams = pedersen::generate_pedersen_params(&group).unwrap();
+        let tom_pparams = pedersen::generate_pedersen_params(&tom_group).unwrap();
+        
+        let g = group.generator();
+
+        let mut order_curve = BigNum::new().unwrap();
+        group.order(&mut order_curve, &mut ctx).unwrap();
+        
+        // ============== Generate commitments & points
+
+        // P + Q = R
+        
+        // =======              P
+        let mut P = EcPoint::new(pparams.c).unwrap();
+        let r = pedersen::generate_random(&order_curve).unwrap();        
+        P.mul(&group, g, &r, &ctx).unwrap();
+
+        // commitment PX, PY
+        let mut x1 = BigNum::new().unwrap();
+        let mut y1 = BigNum::new().unwrap();
+    
+        P.affine_coordinates_gfp(pparams.c, &mut x1, &mut y1, &mut ctx).unwrap();
+        let PX = tom_pparams.commit(&x1).unwrap();
+        let PY = tom_pparams.commit(&y1).unwrap();
+
+        let PX_point = PX.p.to_owned(tom_pparams.c).unwrap();
+        let PY_point = PY.p.to_owned(tom_pparams.c).unwrap();
+
+
+         // =======              Q
+         let mut Q = EcPoint::new(pparams.c).unwrap();
+         let r = pedersen::generate_random(&order_curve).unwrap();        
+         Q.mul(&group, g, &r, &ctx).unwrap();
+ 
+         // commitment QX, QY
+         let mut x2 = BigNum::new().unwrap();
+         let mut y2 = BigNum::new().unwrap();
+     
+         Q.affine_coordinates_gfp(pparams.c, &mut x2, &mut y2, &mut ctx).unwrap();
+         let QX = tom_pparams.commit(&x2).unwrap();
+         let QY = tom_pparams.commit(&y2).unwrap();
+
+        let QX_point = QX.p.to_owned(tom_pparams.c).unwrap();
+        let QY_point = QY.p.to_owned(tom_pparams.c).unwrap();     
+
+
+         // =======              R
+         let mut R = EcPoint::new(pparams.c).unwrap();
+        R.add(pparams.c, &P, &Q, &mut ctx).unwrap();
+
+         
+         // commitment RX, RY
+         let mut x3 = BigNum::new().unwrap();
+         let mut y3 = BigNum::new().unwrap();
+     
+         R.affine_coordinates_gfp(pparams.c, &mut x3, &mut y3, &mut ctx).unwrap();
+         let RX = tom_pparams.commit(&x3).unwrap();
+         let RY = tom_pparams.commit(&y3).unwrap();  
+
+         let RX_point = RX.p.to_owned(tom_pparams.c).unwrap();
+         let RY_point = RY.p.to_owned(tom_pparams.c).unwrap();
+
+
+        // ============== Test true 
+
+        let pi_point_add = prove_point_add(&pparams, &tom_pparams, P, Q, R, PX, PY, QX, QY, RX, RY, &mut Transcript::new(b"demo")).unwrap();
+
+        let coordinates = || [&PX_point, &PY_point, &QX_point, &QY_point, &RX_point, &RY_point].map(|p| p.as_ref().to_owned(tom_pparams.c).unwrap());
+
+        let [px, py, qx, qy, rx, ry] = coordinates();
+        let ver_pa_true = verify_point_add(&tom_pparams, px, py, qx, qy, rx, ry, &pi_point_add, &mut Transcript::new(b"demo")).unwrap();
+        println!("pointAdd proof is working: {}", ver_pa_true);
+        assert!(ver_pa_true);
+
+        // the sub-proofs are bound to the transcript of the prover
+        let [px, py, qx, qy, rx, ry] = coordinates();
+        let ver_pa_other = verify_point_add(&tom_pparams, px, py, qx, qy, rx, ry, &pi_point_add, &mut Transcript::new(b"other session")).unwrap();
+        println!("pointAdd proof rejects other transcripts: {}", !ver_pa_other);
+        assert!(!ver_pa_other);
+
+        let pi_pa_bytes = pi_point_add.to_bytes().unwrap();
+        let pi_pa_decoded = PointAddProof::from_bytes(&tom_group, &pi_pa_bytes).unwrap();
+        println!("PointAddProof encoding roundtrips: {}", pi_pa_decoded.eq(&pi_point_add));
+        assert!(pi_pa_decoded.eq(&pi_point_add));
+
+        let batch_items: Vec<_> = [(&pi_point_add, b"demo"), (&pi_point_add, b"else"), (&pi_pa_decoded, b"demo")]
+            .into_iter()
+            .map(|(pi, label)| {
+                let [PX, PY, QX, QY, RX, RY] = coordinates();
+                PointAddBatchItem { PX, PY, QX, QY, RX, RY, pi, transcript: Transcript::new(label) }
+            })
+            .collect();
+        let batch_invalid = batch_verify_point_add(&tom_pparams, batch_items).unwrap();
+        println!("batch pointAdd verification finds the invalid proofs: {:?}", batch_invalid);
+        assert_eq!(batch_invalid, vec![1]);
+
+    }
+
+    {// ============ Test Exp aux functions
+
+        let bign_11_binary_1011 = BigNum::from_dec_str("11").unwrap();
+
+        let paddedBits = padded_bits(&bign_11_binary_1011, 8).unwrap();
+
+        println!{"Vector is {:?}", paddedBits};
+
+        let vec_shuffled = generate_indices(5);
+
+        println!("Shuffled vector is: {vec_shuffled:?}");
+
+        // a seeded rng gives the same permutation
+        let seeded_1 = generate_indices_with_rng(20, &mut ChaCha20Rng::seed_from_u64(7));
+        let seeded_2 = generate_indices_with_rng(20, &mut ChaCha20Rng::seed_from_u64(7));
+        assert_eq!(seeded_1, seeded_2);
+        
+        
+    }
+
+{       // ====== CHECK THE EXP FUNCTIONS ====== //
+
+    let mut ctx = BigNumContext::new().unwrap();
+
+    let nist_params = pedersen::generate_pedersen_params(&group).unwrap();
+    let tom_params = pedersen::generate_pedersen_params(&tom_group).unwrap();
+    
+    let nist_g = group.generator();
+    let _tom_g = tom_group.generator();
+
+    let mut nist_order = BigNum::new().unwrap();
+    group.order(&mut nist_order, &mut ctx).unwrap();
+
+    let mut tom_order = BigNum::new().unwrap();
+    tom_group.order(&mut tom_order, &mut ctx).unwrap();
+    
+    // ============== Generate commitments & points
+
+    // (x, y) = lambda g = P
+    
+    let secparam = 80;
+
+    // commitment lambda
+    let lambda = pedersen::generate_random(&nist_order).unwrap(); 
+    let Clambda = nist_params.commit(&lambda).unwrap();
+
+    // =======              P = lambda g
+    let mut P = EcPoint::new(nist_params.c).unwrap();
+    P.mul(&group, nist_g, &lambda, &ctx).unwrap();
+
+    // commitment PX, PY
+    let mut x = BigNum::new().unwrap();
+    let mut y = BigNum::new().unwrap();
+
+    P.affine_coordinates_gfp(nist_params.c, &mut x, &mut y, &mut ctx).unwrap();
+    let PX = tom_params.commit(&x).unwrap();
+    let PY = tom_params.commit(&y).unwrap();
+
+    let PX_point = PX.p.to_owned(tom_params.c).unwrap();
+    let PY_point = PY.p.to_owned(tom_params.c).unwrap();
+    let Clambda_point = Clambda.p.to_owned(nist_params.c).unwrap();
+
+
+    // ============== Test true 
+
+    let pi_point_add = prov_exp(
+        &nist_params, 
+        &tom_params, 
+        lambda.to_owned().unwrap(), 
+        Clambda.to_owned().unwrap(),
+        P.to_owned(nist_params.c).unwrap(),
+        PX.to_owned().unwrap(),
+        PY.to_owned().unwrap(),
+        secparam,
+        None,
+        None,
+        &mut Transcript::new(b"demo")
+    ).unwrap();
+
+/*     paramsNIST: &'a PedersenParams<'a>,
+    paramsWario: &'a PedersenParams<'a>,
+    Clambda : EcPoint,
+    Px: EcPoint,
+    Py: EcPoint,
+    pi: &'a Vec<ExpProof<'a>>,
+    secparam: usize,
+    Q: Option<EcPoint>, */
+
+    let ver_exp_true = verify_exp(
+        &nist_params, 
+        &tom_params, 
+        Clambda_point.to_owned(nist_params.c).unwrap(),
+        PX_point,
+        PY_point,
+        &pi_point_add,
+        secparam,
+        None,
+        None,
+        &mut Transcript::new(b"demo")
+    ).unwrap();
+
+    println!("verifyExp proof is working: {}", ver_exp_true);
+    assert!(ver_exp_true);
+
+    let pi_exp_bytes = exp_to_bytes(&pi_point_add).unwrap();
+    let pi_exp_decoded = exp_from_bytes(&group, &tom_group, &pi_exp_bytes).unwrap();
+    let exp_roundtrip = pi_exp_decoded.len() == pi_point_add.len() &&
+        pi_exp_decoded.iter().zip(pi_point_add.iter()).all(|(a, b)| a.eq(b));
+    println!("ExpProof encoding roundtrips: {}", exp_roundtrip);
+    assert!(exp_roundtrip);
+
+    // ============== Test proofs are bound to the transcript
+
+    let ver_exp_other = verify_exp(
+        &nist_params,
+        &tom_params,
+        Clambda_point.to_owned(nist_params.c).unwrap(),
+        PX.p.to_owned(tom_params.c).unwrap(),
+        PY.p.to_owned(tom_params.c).unwrap(),
+        &pi_point_add,
+        secparam,
+        None,
+        None,
+        &mut Transcript::new(b"other session")
+    ).unwrap();
+    println!("verifyExp rejects other transcripts: {}", !ver_exp_other);
+    assert!(!ver_exp_other);
+
+    // ============== Test malformed proofs are rejected without panicking
+
+    let ver_exp_short = verify_exp(
+        &nist_params,
+        &tom_params,
+        Clambda_point.to_owned(nist_params.c).unwrap(),
+        PX.p.to_owned(tom_params.c).unwrap(),
+        PY.p.to_owned(tom_params.c).unwrap(),
+        &pi_point_add[..secparam - 1],
+        secparam,
+        None,
+        None,
+        &mut Transcript::new(b"demo")
+    );
+    println!("verifyExp rejects short proofs: {}", ver_exp_short.is_err());
+    assert!(ver_exp_short.is_err());
+
+    // every repetition is checked, so extra repetitions are rejected too
+    let mut pi_exp_long = exp_from_bytes(&group, &tom_group, &pi_exp_bytes).unwrap();
+    pi_exp_long.extend(exp_from_bytes(&group, &tom_group, &pi_exp_bytes).unwrap());
+    let ver_exp_long = verify_exp(
+        &nist_params,
+        &tom_params,
+        Clambda_point.to_owned(nist_params.c).unwrap(),
+        PX.p.to_owned(tom_params.c).unwrap(),
+        PY.p.to_owned(tom_params.c).unwrap(),
+        &pi_exp_long,
+        secparam,
+        None,
+        None,
+        &mut Transcript::new(b"demo")
+    );
+    println!("verifyExp rejects long proofs: {}", ver_exp_long.is_err());
+    assert!(ver_exp_long.is_err());
+
+    // an empty proof for secparam 0 has no repetitions to check
+    let ver_exp_empty = verify_exp(
+        &nist_params,
+        &tom_params,
+        Clambda_point.to_owned(nist_params.c).unwrap(),
+        PX.p.to_owned(tom_params.c).unwrap(),
+        PY.p.to_owned(tom_params.c).unwrap(),
+        &[],
+        0,
+        None,
+        None,
+        &mut Transcript::new(b"demo")
+    );
+    println!("verifyExp rejects empty proofs: {}", ver_exp_empty.is_err());
+    assert!(ver_exp_empty.is_err());
+
+    // the challenge has no more bits than the NIST order
+    let pi_exp_wide = prov_exp(
+        &nist_params,
+        &tom_params,
+        lambda.to_owned().unwrap(),
+        Clambda.to_owned().unwrap(),
+        P.to_owned(nist_params.c).unwrap(),
+        PX.to_owned().unwrap(),
+        PY.to_owned().unwrap(),
+        nist_order.num_bits() as usize + 1,
+        None,
+        None,
+        &mut Transcript::new(b"demo")
+    );
+    println!("proveExp rejects more repetitions than challenge bits: {}", pi_exp_wide.is_err());
+    assert!(pi_exp_wide.is_err());
+
+    // verification does not depend on local randomness
+    for _ in 0..3 {
+        assert!(verify_exp(
+            &nist_params,
+            &tom_params,
+            Clambda_point.to_owned(nist_params.c).unwrap(),
+            PX.p.to_owned(tom_params.c).unwrap(),
+            PY.p.to_owned(tom_params.c).unwrap(),
+            &pi_exp_decoded,
+            secparam,
+            None,
+            None,
+            &mut Transcript::new(b"demo")
+        ).unwrap());
+    }
+
+    // a seeded proof does not depend on the order (or threads) the repetitions run in
+    let seeded_exp = || {
+        let pi = prov_exp_with_rng(
+            &nist_params,
+            &tom_params,
+            lambda.to_owned().unwrap(),
+            Clambda.to_owned().unwrap(),
+            P.to_owned(nist_params.c).unwrap(),
+            PX.to_owned().unwrap(),
+            PY.to_owned().unwrap(),
+            secparam,
+            None,
+            None,
+            &mut Transcript::new(b"demo"),
+            &mut ChaCha20Rng::seed_from_u64(24)
+        ).unwrap();
+        exp_to_bytes(&pi).unwrap()
+    };
+    assert!(seeded_exp() == seeded_exp());
+    println!("seeded ExpProof is reproducible: true");
+
+    // ============== Test batch verification
+
+    // a proof for another session and a short proof are found among valid ones
+    let batch_items: Vec<_> = [
+        (&pi_point_add[..], b"demo".as_slice()),
+        (&pi_point_add[..], b"other session".as_slice()),
+        (&pi_exp_decoded[..secparam - 1], b"demo".as_slice()),
+        (&pi_exp_decoded[..], b"demo".as_slice()),
+    ].into_iter().map(|(pi, label)| ExpBatchItem {
+        Clambda: Clambda_point.to_owned(nist_params.c).unwrap(),
+        Px: PX.p.to_owned(tom_params.c).unwrap(),
+        Py: PY.p.to_owned(tom_params.c).unwrap(),
+        pi,
+        Q: None,
+        context: None,
+        transcript: Transcript::new(label),
+    }).collect();
+    let batch_invalid = batch_verify_exp(&nist_params, &tom_params, batch_items, secparam).unwrap();
+    println!("batch verifyExp finds the invalid proofs: {:?}", batch_invalid);
+    assert_eq!(batch_invalid, vec![1, 2]);
+
+}
+
+{       // ====== CHECK THE SIGNATURE FUNCTIONS ====== //
+
+    let mut ctx = BigNumContext::new().unwrap();
+
+    let nist_params = pedersen::generate_pedersen_params(&group).unwrap();
+    let tom_params = pedersen::generate_pedersen_params(&tom_group).unwrap();
+
+    let sys_params = SystemParameters {
+        pair: CurvePair::P256Tom256,
+        groupNIST: &group,
+        groupWario: &tom_group,
+        secLevel: 80,
+        hash: HashFunction::Sha256,
+    };
+
+    // Sign a message with a fresh P256 key
+    let key = EcKey::generate(&group).unwrap();
+    let digest = hash(MessageDigest::sha256(), b"zkattest").unwrap();
+    let sig = EcdsaSig::sign(&digest, &key).unwrap();
+    let msg_hash = BigNum::from_slice(&digest).unwrap();
+
+    // commitment to the public key coordinates
+    let mut x = BigNum::new().unwrap();
+    let mut y = BigNum::new().unwrap();
+
+    key.public_key().affine_coordinates_gfp(&group, &mut x, &mut y, &mut ctx).unwrap();
+    let keyX = tom_params.commit(&x).unwrap();
+    let keyY = tom_params.commit(&y).unwrap();
+
+    let public_key = key.public_key().to_owned(&group).unwrap();
+
+    // ============== Test true 
+
+    let pi_sig = prove_signature(
+        &sys_params,
+        &nist_params,
+        &tom_params,
+        &msg_hash,
+        sig.r(),
+        sig.s(),
+        &public_key,
+        keyX,
+        keyY,
+        Some(b"nonce-1".as_slice())
+    ).unwrap();
+
+    let ver_sig_true = verify_signature(&sys_params, &nist_params, &tom_params, &msg_hash, &pi_sig, Some(b"nonce-1".as_slice())).unwrap();
+    println!("verifySignature proof is working: {}", ver_sig_true);
+    assert!(ver_sig_true);
+
+    // ============== Test encoding
+
+    let pi_sig_bytes = pi_sig.to_bytes().unwrap();
+    let pi_sig_decoded = SignatureProof::from_bytes(&group, &tom_group, &pi_sig_bytes).unwrap();
+    assert!(pi_sig_decoded.eq(&pi_sig));
+    let ver_sig_decoded = verify_signature(&sys_params, &nist_params, &tom_params, &msg_hash, &pi_sig_decoded, Some(b"nonce-1".as_slice())).unwrap();
+    println!("decoded SignatureProof verifies ({} bytes): {}", pi_sig_bytes.len(), ver_sig_decoded);
+    assert!(ver_sig_decoded);
+
+    // truncated, extended and tampered encodings are rejected
+    assert!(SignatureProof::from_bytes(&group, &tom_group, &pi_sig_bytes[..pi_sig_bytes.len() - 1]).is_err());
+    let mut extended = pi_sig_bytes.clone();
+    extended.push(0);
+    assert!(SignatureProof::from_bytes(&group, &tom_group, &extended).is_err());
+    let mut tampered = pi_sig_bytes.clone();
+    tampered[0] = 0;
+    assert!(SignatureProof::from_bytes(&group, &tom_group, &tampered).is_err());
+
+    // ============== Test false 
+
+    let other_digest = hash(MessageDigest::sha256(), b"another message").unwrap();
+    let other_msg_hash = BigNum::from_slice(&other_digest).unwrap();
+
+    let ver_sig_false = verify_signature(&sys_params, &nist_params, &tom_params, &other_msg_hash, &pi_sig, Some(b"nonce-1".as_slice())).unwrap();
+    println!("verifySignature rejects other messages: {}", !ver_sig_false);
+    assert!(!ver_sig_false);
+
+    // ============== Test proofs are bound to the context
+
+    let ver_sig_other_nonce = verify_signature(&sys_params, &nist_params, &tom_params, &msg_hash, &pi_sig, Some(b"nonce-2".as_slice())).unwrap();
+    println!("verifySignature rejects other contexts: {}", !ver_sig_other_nonce);
+    assert!(!ver_sig_other_nonce);
+    let ver_sig_no_nonce = verify_signature(&sys_params, &nist_params, &tom_params, &msg_hash, &pi_sig, None).unwrap();
+    assert!(!ver_sig_no_nonce);
+
+    // ============== Test reproducible proofs
+
+    // the same seed gives the same commitments and proof
+    let prove_seeded = |seed: u64| {
+        let mut rng = ChaCha20Rng::seed_from_u64(seed);
+        let keyX = tom_params.commit_with_rng(&x, &mut rng).unwrap();
+        let keyY = tom_params.commit_with_rng(&y, &mut rng).unwrap();
+        prove_signature_with_rng(&sys_params, &nist_params, &tom_params, &msg_hash, sig.r(), sig.s(), &public_key, keyX, keyY, None, &mut rng).unwrap()
+    };
+    let pi_seeded = prove_seeded(1);
+    let pi_seeded_bytes = pi_seeded.to_bytes().unwrap();
+    assert_eq!(pi_seeded_bytes, prove_seeded(1).to_bytes().unwrap());
+    assert_ne!(pi_seeded_bytes, prove_seeded(2).to_bytes().unwrap());
+    let ver_sig_seeded = verify_signature(&sys_params, &nist_params, &tom_params, &msg_hash, &pi_seeded, None).unwrap();
+    println!("seeded SignatureProof is reproducible and verifies: {}", ver_sig_seeded);
+    assert!(ver_sig_seeded);
+
+    // precomputed tables do not change the proof
+    let nist_precomputed = pedersen::generate_pedersen_params(&group).unwrap().with_precomputation().unwrap();
+    let tom_precomputed = pedersen::generate_pedersen_params(&tom_group).unwrap().with_precomputation().unwrap();
+    let mut rng = ChaCha20Rng::seed_from_u64(1);
+    let keyX = tom_precomputed.commit_with_rng(&x, &mut rng).unwrap();
+    let keyY = tom_precomputed.commit_with_rng(&y, &mut rng).unwrap();
+    let pi_precomputed = prove_signature_with_rng(&sys_params, &nist_precomputed, &tom_precomputed, &msg_hash, sig.r(), sig.s(), &public_key, keyX, keyY, None, &mut rng).unwrap();
+    assert_eq!(pi_precomputed.to_bytes().unwrap(), pi_seeded_bytes);
+    println!("precomputed params give the same SignatureProof: true");
+
+    // the derandomized prover depends only on its inputs
+    let keyX = tom_params.commit(&x).unwrap();
+    let keyY = tom_params.commit(&y).unwrap();
+    let prove_derandomized = |context: &[u8]| {
+        prove_signature_deterministic(
+            &sys_params, &nist_params, &tom_params, &msg_hash, sig.r(), sig.s(), &public_key,
+            keyX.to_owned().unwrap(), keyY.to_owned().unwrap(), Some(context)).unwrap()
+    };
+    let pi_derandomized = prove_derandomized(b"nonce-1");
+    let pi_derandomized_bytes = pi_derandomized.to_bytes().unwrap();
+    assert_eq!(pi_derandomized_bytes, prove_derandomized(b"nonce-1").to_bytes().unwrap());
+    assert_ne!(pi_derandomized_bytes, prove_derandomized(b"nonce-2").to_bytes().unwrap());
+    let ver_sig_derandomized = verify_signature(&sys_params, &nist_params, &tom_params, &msg_hash, &pi_derandomized, Some(b"nonce-1".as_slice())).unwrap();
+    println!("derandomized SignatureProof is reproducible and verifies: {}", ver_sig_derandomized);
+    assert!(ver_sig_derandomized);
+
+}
+
+{       // ====== CHECK THE SIGNATURE LIST FUNCTIONS ====== //
+
+    let nist_params = pedersen::generate_pedersen_params(&group).unwrap();
+    let tom_params = pedersen::generate_pedersen_params(&tom_group).unwrap();
+
+    let sys_params = SystemParameters {
+        pair: CurvePair::P256Tom256,
+        groupNIST: &group,
+        groupWario: &tom_group,
+        secLevel: 80,
+        hash: HashFunction::Sha256,
+    };
+
+    // Sign a message with the third key of a list of five
+    let keys: Vec<EcKey<_>> = (0..5).map(|_| EcKey::generate(&group).unwrap()).collect();
+    let key_list: Vec<EcPoint> = keys.iter().map(|k| k.public_key().to_owned(&group).unwrap()).collect();
+
+    let digest = hash(MessageDigest::sha256(), b"zkattest").unwrap();
+    let sig = EcdsaSig::sign(&digest, &keys[2]).unwrap();
+    let msg_hash = BigNum::from_slice(&digest).unwrap();
+
+    // ============== Test true 
+
+    let pi_sig_list = prove_signature_list(
+        &sys_params,
+        &nist_params,
+        &tom_params,
+        &msg_hash,
+        sig.r(),
+        sig.s(),
+        &key_list[2],
+        &key_list,
+        None
+    ).unwrap();
+
+    let ver_sig_list_true = verify_signature_list(&sys_params, &nist_params, &tom_params, &msg_hash, &key_list, &pi_sig_list, None).unwrap();
+    println!("verifySignatureList proof is working: {}", ver_sig_list_true);
+    assert!(ver_sig_list_true);
+
+    let pi_sig_list_bytes = pi_sig_list.to_bytes().unwrap();
+    let pi_sig_list_decoded = SignatureListProof::from_bytes(&group, &tom_group, &pi_sig_list_bytes).unwrap();
+    assert!(pi_sig_list_decoded.eq(&pi_sig_list));
+    let ver_sig_list_decoded = verify_signature_list(&sys_params, &nist_params, &tom_params, &msg_hash, &key_list, &pi_sig_list_decoded, None).unwrap();
+    println!("decoded SignatureListProof verifies: {}", ver_sig_list_decoded);
+    assert!(ver_sig_list_decoded);
+
+    #[cfg(feature = "serde")]
+    { // ============== Test serde
+
+        use zkattest_rs::{PedersenParams, deserialize_with};
+
+        let json = serde_json::to_string(&pi_sig_list).unwrap();
+        let mut de = serde_json::Deserializer::from_str(&json);
+        let pi_sig_list_json: SignatureListProof = deserialize_with((&*group, &*tom_group), &mut de).unwrap();
+        assert!(pi_sig_list_json.eq(&pi_sig_list));
+        let ver_sig_list_json = verify_signature_list(&sys_params, &nist_params, &tom_params, &msg_hash, &key_list, &pi_sig_list_json, None).unwrap();
+        println!("SignatureListProof JSON roundtrip verifies: {}", ver_sig_list_json);
+        assert!(ver_sig_list_json);
+
+        let json = serde_json::to_string(&tom_params).unwrap();
+        let mut de = serde_json::Deserializer::from_str(&json);
+        let tom_params_json: PedersenParams = deserialize_with(&*tom_group, &mut de).unwrap();
+        assert!(tom_params_json.eq(&tom_params));
+
+        // points that are not compressed or are too long are rejected
+        let not_compressed = json.replacen("\"g\":\"0", "\"g\":\"4", 1);
+        let too_long = json.replacen("\",\"h\"", "00\",\"h\"", 1);
+        for tampered in [not_compressed, too_long] {
+            assert!(tampered != json);
+            let mut de = serde_json::Deserializer::from_str(&tampered);
+            assert!(deserialize_with::<PedersenParams, _>(&*tom_group, &mut de).is_err());
+        }
+    }
+
+    // ============== Test false 
+
+    let other_list: Vec<EcPoint> = key_list.iter()
+        .enumerate()
+        .filter(|(i, _)| *i != 2)
+        .map(|(_, k)| k.as_ref().to_owned(&group).unwrap())
+        .collect();
+
+    let ver_sig_list_false = verify_signature_list(&sys_params, &nist_params, &tom_params, &msg_hash, &other_list, &pi_sig_list, None).unwrap();
+    println!("verifySignatureList rejects other lists: {}", !ver_sig_list_false);
+    assert!(!ver_sig_list_false);
+
+    // ============== Test derandomized proofs
+
+    let pi_sig_list_det = prove_signature_list_deterministic(&sys_params, &nist_params, &tom_params, &msg_hash, sig.r(), sig.s(), &key_list[2], &key_list, None).unwrap();
+    let pi_sig_list_det_again = prove_signature_list_deterministic(&sys_params, &nist_params, &tom_params, &msg_hash, sig.r(), sig.s(), &key_list[2], &key_list, None).unwrap();
+    assert_eq!(pi_sig_list_det.to_bytes().unwrap(), pi_sig_list_det_again.to_bytes().unwrap());
+    let ver_sig_list_det = verify_signature_list(&sys_params, &nist_params, &tom_params, &msg_hash, &key_list, &pi_sig_list_det, None).unwrap();
+    println!("derandomized SignatureListProof is reproducible and verifies: {}", ver_sig_list_det);
+    assert!(ver_sig_list_det);
+
+}
+
+{       // ====== CHECK THE P384 AND P521 CURVE PAIRS ====== //
+
+    for (pair, md, hash_fn) in [
+        (CurvePair::P384Tom384, MessageDigest::sha384(), HashFunction::Sha384),
+        (CurvePair::P521Tom521, MessageDigest::sha512(), HashFunction::Shake256),
+    ] {
+
+        let mut ctx = BigNumContext::new().unwrap();
+
+        let nist_group = pair.nist().unwrap();
+        let tom_group = pair.tom().unwrap();
+        assert!(pair.matches(&nist_group, &tom_group).unwrap());
+
+        // same field, coefficients and order, but generator 2 G
+        let (mut p, mut a, mut b) = (BigNum::new().unwrap(), BigNum::new().unwrap(), BigNum::new().unwrap());
+        nist_group.components_gfp(&mut p, &mut a, &mut b, &mut ctx).unwrap();
+        let mut order = BigNum::new().unwrap();
+        nist_group.order(&mut order, &mut ctx).unwrap();
+        let mut other_group = EcGroup::from_components(p, a, b, &mut ctx).unwrap();
+        let mut g_2 = EcPoint::new(&nist_group).unwrap();
+        g_2.mul_generator(&nist_group, &BigNum::from_u32(2).unwrap(), &ctx).unwrap();
+        let g_2_bytes = g_2.to_bytes(&nist_group, PointConversionForm::COMPRESSED, &mut ctx).unwrap();
+        let g_2 = EcPoint::from_bytes(&other_group, &g_2_bytes, &mut ctx).unwrap();
+        other_group.set_generator(g_2, order, BigNum::from_u32(1).unwrap()).unwrap();
+        assert!(!pair.matches(&other_group, &tom_group).unwrap());
+        println!("{} rejects a curve with the same order but another generator", pair.name());
+
+        let nist_params = pedersen::generate_pedersen_params(&nist_group).unwrap();
+        let tom_params = pedersen::generate_pedersen_params(&tom_group).unwrap();
+
+        let sys_params = SystemParameters {
+            pair,
+            groupNIST: &nist_group,
+            groupWario: &tom_group,
+            secLevel: 80,
+            hash: hash_fn,
+        };
+
+        let key = EcKey::generate(&nist_group).unwrap();
+        let digest = hash(md, b"zkattest").unwrap();
+        let sig = EcdsaSig::sign(&digest, &key).unwrap();
+        let msg_hash = BigNum::from_slice(&digest).unwrap();
+
+        let mut x = BigNum::new().unwrap();
+        let mut y = BigNum::new().unwrap();
+
+        key.public_key().affine_coordinates_gfp(&nist_group, &mut x, &mut y, &mut ctx).unwrap();
+        let keyX = tom_params.commit(&x).unwrap();
+        let keyY = tom_params.commit(&y).unwrap();
+
+        let public_key = key.public_key().to_owned(&nist_group).unwrap();
+
+        let pi_sig = prove_signature(
+            &sys_params,
+            &nist_params,
+            &tom_params,
+            &msg_hash,
+            sig.r(),
+            sig.s(),
+            &public_key,
+            keyX,
+            keyY,
+            None
+        ).unwrap();
+
+        let ver_sig = verify_signature(&sys_params, &nist_params, &tom_params, &msg_hash, &pi_sig, None).unwrap();
+        println!("verifySignature proof is working on {} with {}: {}", pair.name(), hash_fn.name(), ver_sig);
+        assert!(ver_sig);
+
+        // the hash is recorded in the header and enforced on verification
+        let pi_sig_bytes = pi_sig.to_bytes().unwrap();
+        assert_eq!(pi_sig_bytes[2], hash_fn.id());
+        let pi_sig_decoded = SignatureProof::from_bytes(&nist_group, &tom_group, &pi_sig_bytes).unwrap();
+        assert!(pi_sig_decoded.hash == hash_fn);
+
+        let sha256_params = SystemParameters { hash: HashFunction::Sha256, ..sys_params };
+        let ver_sig_sha256 = verify_signature(&sha256_params, &nist_params, &tom_params, &msg_hash, &pi_sig_decoded, None).unwrap();
+        println!("verifySignature rejects proofs with another hash: {}", !ver_sig_sha256);
+        assert!(!ver_sig_sha256);
+
+        let mut unknown_hash = pi_sig_bytes.clone();
+        unknown_hash[2] = 0;
+        assert!(SignatureProof::from_bytes(&nist_group, &tom_group, &unknown_hash).is_err());
+    }
+
+}
+
+
+{       // ====== CHECK THE SECP256K1 CURVE PAIR ====== //
+
+    let mut ctx = BigNumContext::new().unwrap();
+
+    let pair = CurvePair::Secp256k1Secq256k1;
+    let k1_group = pair.nist().unwrap();
+    let q1_group = pair.tom().unwrap();
+    assert!(pair.matches(&k1_group, &q1_group).unwrap());
+
+    let k1_params = pedersen::generate_pedersen_params(&k1_group).unwrap();
+    let q1_params = pedersen::generate_pedersen_params(&q1_group).unwrap();
+
+    let mut k1_order = BigNum::new().unwrap();
+    k1_group.order(&mut k1_order, &mut ctx).unwrap();
+
+    // ============== Test exp: P = lambda g
+
+    let lambda = pedersen::generate_random(&k1_order).unwrap();
+    let Clambda = k1_params.commit(&lambda).unwrap();
+
+    let mut P = EcPoint::new(&k1_group).unwrap();
+    P.mul_generator(&k1_group, &lambda, &ctx).unwrap();
+
+    let mut x = BigNum::new().unwrap();
+    let mut y = BigNum::new().unwrap();
+    P.affine_coordinates_gfp(&k1_group, &mut x, &mut y, &mut ctx).unwrap();
+    let PX = q1_params.commit(&x).unwrap();
+    let PY = q1_params.commit(&y).unwrap();
+
+    let PX_point = PX.p.to_owned(&q1_group).unwrap();
+    let PY_point = PY.p.to_owned(&q1_group).unwrap();
+
+    let pi_exp = prov_exp(&k1_params, &q1_params, lambda, Clambda.to_owned().unwrap(), P, PX, PY, 80, None, None, &mut Transcript::new(b"demo")).unwrap();
+    let ver_exp = verify_exp(&k1_params, &q1_params, Clambda.p, PX_point, PY_point, &pi_exp, 80, None, None, &mut Transcript::new(b"demo")).unwrap();
+    println!("verifyExp proof is working on {}: {}", pair.name(), ver_exp);
+    assert!(ver_exp);
+
+    // ============== Test signature: fixed secp256k1 vector over sha256("zkattest")
+
+    let sys_params = SystemParameters {
+        pair,
+        groupNIST: &k1_group,
+        groupWario: &q1_group,
+        secLevel: 80,
+        hash: HashFunction::Sha256,
+    };
+
+    let pk_x = BigNum::from_hex_str("6122618c266158abb47cfdaec0ecf3574cf1a4341e643fd1b2137a20ce80b2f2").unwrap();
+    let pk_y = BigNum::from_hex_str("a87c67c1dc349e1525ad008de137e5d84803c2b8df08e45288955fdd95b36498").unwrap();
+    let r = BigNum::from_hex_str("4e5b0309b5c67cfd0dfbcfb3d3f5f717680ad33540aa23a49317d4ef5fda2d7e").unwrap();
+    let s = BigNum::from_hex_str("4707e7c86a7334506967b6b830732673b356b2e77d3270b067a5281734d5e051").unwrap();
+
+    let digest = hash(MessageDigest::sha256(), b"zkattest").unwrap();
+    let msg_hash = BigNum::from_slice(&digest).unwrap();
+    assert!(msg_hash == BigNum::from_hex_str("ca6b69f8c33e14e4bef0b626882f0a357d7741b0458ea188e4a9b982d8cb5350").unwrap());
+
+    let mut public_key = EcPoint::new(&k1_group).unwrap();
+    public_key.set_affine_coordinates_gfp(&k1_group, &pk_x, &pk_y, &mut ctx).unwrap();
+
+    // the vector is a valid ECDSA signature
+    let key = EcKey::from_public_key(&k1_group, &public_key).unwrap();
+    let sig = EcdsaSig::from_private_components(r.to_owned().unwrap(), s.to_owned().unwrap()).unwrap();
+    assert!(sig.verify(&digest, &key).unwrap());
+
+    let keyX = q1_params.commit(&pk_x).unwrap();
+    let keyY = q1_params.commit(&pk_y).unwrap();
+
+    let pi_sig = prove_signature(&sys_params, &k1_params, &q1_params, &msg_hash, &r, &s, &public_key, keyX, keyY, None).unwrap();
+
+    let ver_sig = verify_signature(&sys_params, &k1_params, &q1_params, &msg_hash, &pi_sig, None).unwrap();
+    println!("verifySignature proof is working on {}: {}", pair.name(), ver_sig);
+    assert!(ver_sig);
+
+    let other_digest = hash(MessageDigest::sha256(), b"zkattest!").unwrap();
+    let other_hash = BigNum::from_slice(&other_digest).unwrap();
+    let ver_sig_false = verify_signature(&sys_params, &k1_params, &q1_params, &other_hash, &pi_sig, None).unwrap();
+    println!("verifySignature rejects other messages on {}: {}", pair.name(), !ver_sig_false);
+    assert!(!ver_sig_false);
+
+}
+
+
+}
@@ -1,3 +1,4 @@
 pub mod equality;
+pub mod membership;
 pub mod mult;
 pub mod pedersen;
\ No newline at end of file
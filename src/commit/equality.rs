@@ -3,13 +3,16 @@
 //use sha2::{Sha256, Digest};
 //use std::convert::TryFrom;
 
-use openssl::ec::{EcGroupRef, EcPoint, PointConversionForm};
+use openssl::ec::{EcGroupRef, EcPoint};
 use openssl::bn::{BigNum, BigNumContext};
-use openssl::error::ErrorStack;
-use openssl::hash::{hash, MessageDigest};
+use rand::{CryptoRng, RngCore};
+use rand::rngs::OsRng;
 
-use crate::commit::pedersen::{Commitment, PedersenParams, generate_random};
-use crate::curves::multimult::{MultiMult, Relation};
+use crate::commit::pedersen::{Commitment, PedersenParams, generate_random_with_rng};
+use crate::curves::multimult::{Batch, MultiMult, Relation};
+use crate::encoding::{Reader, Writer, TAG_EQUALITY};
+use crate::error::Result;
+use crate::transcript::Transcript;
 
 
 
@@ -24,42 +27,8 @@ use crate::curves::multimult::{MultiMult, Relation};
 */
 
 
-pub fn hash_points(hash_id: MessageDigest, groups: &[&EcGroupRef], points: &[&EcPoint]) -> Result< BigNum, ErrorStack > {  //-> impl Future<Output = Result<u128, ()>> {
-    
-    //async move {
-
-        let mut ctx = BigNumContext::new().unwrap();
-
-        let bytes_points: Vec<_>; 
-        if groups.len() == 1 {
-            bytes_points = points.iter().map(|p| p.to_bytes(groups[0], PointConversionForm::COMPRESSED, &mut ctx).unwrap()).collect();
-        } else {
-            bytes_points = groups.iter().zip(points.iter()).map(|(g, p)| p.to_bytes(g, PointConversionForm::COMPRESSED, &mut ctx).unwrap()).collect();
-        }
-        // bytes_points is a vec< [g.to_bytes, h.to_bytes]>
-        
-
-        // flatten bytes_points
-
-        /* ====== as in .ts version
-                
-        // total size of bytes_points to create a new bytes vec
-
-        let size : usize = bytes_points.iter().map(|b| b.len()).sum();
-        let mut bytes = vec![0u8; size].into_boxed_slice();
-        let mut offset = 0;
-
-        for bp in &bytes_points {
-            bytes[offset..offset + bp.len()].copy_from_slice(bp);
-            offset += bp.len();
-        }
-        ==================================*/
-        let flatten_bytes: Vec<_> = bytes_points.into_iter().flat_map(|x| x).collect();
-
-        let hash = openssl::hash::hash(hash_id, &flatten_bytes[..]).map_err(|_| ()).unwrap();
-        BigNum::from_slice(&hash[..10])
-    //}
-}
+/// Fiat-Shamir label of the equality proof.
+pub const LABEL_EQUALITY: &[u8] = b"zkattest/equality";
 
 
 
@@ -75,16 +44,68 @@ pub struct EqualityProof<'a> {
 
 
 impl<'a> EqualityProof<'a> {
+    #[allow(clippy::should_implement_trait)]
     pub fn eq(&self, other: &EqualityProof) -> bool {
         
-        let mut ctx = BigNumContext::new().unwrap();
+        let Ok(mut ctx) = BigNumContext::new() else { return false };
 
-        self.a_1.eq(self.group, &other.a_1, &mut ctx).unwrap() &&
-        self.a_2.eq(self.group, &other.a_2, &mut ctx).unwrap() &&
+        self.a_1.eq(self.group, &other.a_1, &mut ctx).unwrap_or(false) &&
+        self.a_2.eq(self.group, &other.a_2, &mut ctx).unwrap_or(false) &&
         self.t_x == other.t_x &&
         self.t_r1 == other.t_r1 &&
         self.t_r2 == other.t_r2
     }
+
+    pub(crate) fn write(&self, w: &mut Writer) -> Result<()> {
+        w.point(self.group, &self.a_1)?;
+        w.point(self.group, &self.a_2)?;
+        w.scalar(self.group, &self.t_x)?;
+        w.scalar(self.group, &self.t_r1)?;
+        w.scalar(self.group, &self.t_r2)
+    }
+
+    pub(crate) fn read(r: &mut Reader, group: &'a EcGroupRef) -> Result<Self> {
+        Ok(EqualityProof {
+            group,
+            a_1: r.point(group)?,
+            a_2: r.point(group)?,
+            t_x: r.scalar(group)?,
+            t_r1: r.scalar(group)?,
+            t_r2: r.scalar(group)?,
+        })
+    }
+
+    /// Encodes the proof as version || tag (no hash id, see encoding) || A1 || A2 || t_x || t_r1 || t_r2
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut w = Writer::new(TAG_EQUALITY)?;
+        self.write(&mut w)?;
+        Ok(w.finish())
+    }
+
+    pub fn from_bytes(group: &'a EcGroupRef, bytes: &[u8]) -> Result<Self> {
+        let mut r = Reader::new(bytes, TAG_EQUALITY)?;
+        let pi = Self::read(&mut r, group)?;
+        r.finish()?;
+        Ok(pi)
+    }
+}
+
+// Challenge c = H(transcript, params, C1, C2, A1, A2)
+fn equality_challenge(
+    transcript: &mut Transcript,
+    params: &PedersenParams,
+    C1: &EcPoint,
+    C2: &EcPoint,
+    A1: &EcPoint,
+    A2: &EcPoint
+) -> Result<BigNum> {
+    transcript.append_message(b"protocol", LABEL_EQUALITY);
+    transcript.append_params(b"params", params)?;
+    transcript.append_point(b"C1", params.c, C1)?;
+    transcript.append_point(b"C2", params.c, C2)?;
+    transcript.append_point(b"A1", params.c, A1)?;
+    transcript.append_point(b"A2", params.c, A2)?;
+    transcript.challenge_scalar(b"c", params.c)
 }
 
 /**
@@ -94,59 +115,73 @@ impl<'a> EqualityProof<'a> {
  * @param x
  * @param C1
  * @param C2
+ * @param transcript transcript shared with the enclosing proof
  */
 pub fn prove_equality<'a>(
-    params: &'a PedersenParams<'a>,
+    params: &PedersenParams<'a>,
     x: BigNum,
     C1: Commitment,
-    C2: Commitment
-)-> EqualityProof<'a> {
+    C2: Commitment,
+    transcript: &mut Transcript,
+) -> Result<EqualityProof<'a>> {
+    prove_equality_with_rng(params, x, C1, C2, transcript, &mut OsRng)
+}
+
+/// prove_equality with the randomness drawn from rng.
+pub fn prove_equality_with_rng<'a>(
+    params: &PedersenParams<'a>,
+    x: BigNum,
+    C1: Commitment,
+    C2: Commitment,
+    transcript: &mut Transcript,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Result<EqualityProof<'a>> {
 
-    let mut ctx = BigNumContext::new().unwrap();
+    let mut ctx = BigNumContext::new()?;
 
-    let mut order_curve = BigNum::new().unwrap();
-    params.c.order(&mut order_curve, &mut ctx).unwrap();
-    let k = generate_random(&order_curve).unwrap(); //Scalar::random(&mut rand::thread_rng());
+    let mut order_curve = BigNum::new()?;
+    params.c.order(&mut order_curve, &mut ctx)?;
+    let k = generate_random_with_rng(&order_curve, rng)?; //Scalar::random(&mut rand::thread_rng());
 
-    let A1 = params.commit(&k);
-    let A2 = params.commit(&k);
+    let A1 = params.commit_with_rng(&k, rng)?;
+    let A2 = params.commit_with_rng(&k, rng)?;
 
-    let c = hash_points(MessageDigest::sha256(), &[params.c], &[&C1.p, &C2.p, &A1.p, &A2.p]).unwrap();
+    let c = equality_challenge(transcript, params, &C1.p, &C2.p, &A1.p, &A2.p)?;
 
-    let mut cc = BigNum::new().unwrap();
-    cc.nnmod(&c, &order_curve, &mut ctx).unwrap();
-    let mut xx = BigNum::new().unwrap();
-    xx.nnmod(&x, &order_curve, &mut ctx).unwrap();
-    let mut kk = BigNum::new().unwrap();
-    kk.nnmod(&k, &order_curve, &mut ctx).unwrap();
+    let mut cc = BigNum::new()?;
+    cc.nnmod(&c, &order_curve, &mut ctx)?;
+    let mut xx = BigNum::new()?;
+    xx.nnmod(&x, &order_curve, &mut ctx)?;
+    let mut kk = BigNum::new()?;
+    kk.nnmod(&k, &order_curve, &mut ctx)?;
 
 
     // Compute  t_x = k - c * x
-    let mut cc_times_xx = BigNum::new().unwrap();
-    cc_times_xx.mod_mul(&cc, &xx, &order_curve, &mut ctx).unwrap();       
-    let mut t_x = BigNum::new().unwrap();
-    t_x.mod_sub(&kk,  &cc_times_xx, &order_curve, &mut ctx).unwrap();
+    let mut cc_times_xx = BigNum::new()?;
+    cc_times_xx.mod_mul(&cc, &xx, &order_curve, &mut ctx)?;       
+    let mut t_x = BigNum::new()?;
+    t_x.mod_sub(&kk,  &cc_times_xx, &order_curve, &mut ctx)?;
     
     // Compute t_r1 = s1 - c * r1
-    let mut cc_times_r1 = BigNum::new().unwrap();
-    cc_times_r1.mod_mul(&cc, &C1.r, &order_curve, &mut ctx).unwrap();     
-    let mut t_r1 = BigNum::new().unwrap();
-    t_r1.mod_sub(&A1.r, &cc_times_r1, &order_curve, &mut ctx).unwrap();
+    let mut cc_times_r1 = BigNum::new()?;
+    cc_times_r1.mod_mul(&cc, &C1.r, &order_curve, &mut ctx)?;     
+    let mut t_r1 = BigNum::new()?;
+    t_r1.mod_sub(&A1.r, &cc_times_r1, &order_curve, &mut ctx)?;
 
     // Compute t_r2 = s2 - c * r2
-    let mut cc_times_r2 = BigNum::new().unwrap();
-    cc_times_r2.mod_mul(&cc, &C2.r, &order_curve, &mut ctx).unwrap();   
-    let mut t_r2 = BigNum::new().unwrap();
-    t_r2.mod_sub(&A2.r, &cc_times_r2, &order_curve, &mut ctx).unwrap();
+    let mut cc_times_r2 = BigNum::new()?;
+    cc_times_r2.mod_mul(&cc, &C2.r, &order_curve, &mut ctx)?;   
+    let mut t_r2 = BigNum::new()?;
+    t_r2.mod_sub(&A2.r, &cc_times_r2, &order_curve, &mut ctx)?;
 
-    EqualityProof {
+    Ok(EqualityProof {
         group: params.c,
         a_1: A1.p,
         a_2: A2.p,
-        t_x: t_x,
-        t_r1: t_r1,
-        t_r2: t_r2,
-    }
+        t_x,
+        t_r1,
+        t_r2,
+    })
 
 }
 
@@ -154,18 +189,19 @@ pub fn verify_equality<'a>(
     params: &'a PedersenParams<'a>,
     C1: EcPoint,
     C2: EcPoint,
-    pi: &'a EqualityProof<'a>
-) -> bool {
+    pi: &'a EqualityProof<'a>,
+    transcript: &mut Transcript
+) -> Result<bool> {
     
     let mut multi = MultiMult::new(params.c);
 
-    let ok = aggregate_equality(params, C1, C2, pi, &mut multi);
+    let ok = aggregate_equality(params, C1, C2, pi, &mut multi, transcript)?;
 
     if !ok {
-        return false
+        return Ok(false)
     }
     
-    multi.evaluate().is_infinity(&params.c)
+    Ok(multi.evaluate()?.is_infinity(params.c))
 }
 
 
@@ -174,45 +210,81 @@ pub fn aggregate_equality<'a> (
     C1: EcPoint,
     C2: EcPoint,
     pi: &'a EqualityProof<'a>,
-    multi: &mut MultiMult
-) -> bool {
+    multi: &mut MultiMult,
+    transcript: &mut Transcript
+) -> Result<bool> {
 
-    let mut ctx = BigNumContext::new().unwrap();
+    let mut ctx = BigNumContext::new()?;
 
-    let challenge = hash_points(MessageDigest::sha256(), &[params.c], &[&C1, &C2, &pi.a_1, &pi.a_2]).unwrap();
+    let challenge = equality_challenge(transcript, params, &C1, &C2, &pi.a_1, &pi.a_2)?;
     // new scalar challenge
-    let mut order_curve = BigNum::new().unwrap();
-    params.c.order(&mut order_curve, &mut ctx).unwrap();
-    let mut cc = BigNum::new().unwrap();
-    cc.nnmod(&challenge, &order_curve, &mut ctx).unwrap();
+    let mut order_curve = BigNum::new()?;
+    params.c.order(&mut order_curve, &mut ctx)?;
+    let mut cc = BigNum::new()?;
+    cc.nnmod(&challenge, &order_curve, &mut ctx)?;
 
 
     let mut A1rel = Relation::new(params.c);
-    A1rel.insert(params.g.to_owned(&params.c).unwrap(), pi.t_x.to_owned().unwrap());
-    A1rel.insert(params.h.to_owned(&params.c).unwrap(), pi.t_r1.to_owned().unwrap());
-    A1rel.insert(C1, cc.to_owned().unwrap());
+    A1rel.insert(params.g.to_owned(params.c)?, pi.t_x.to_owned()?)?;
+    A1rel.insert(params.h.to_owned(params.c)?, pi.t_r1.to_owned()?)?;
+    A1rel.insert(C1, cc.to_owned()?)?;
     // pi.a_1.invert(&params.c, &mut ctx);
     // invert function cannot be used because:
     // `pi` is a `&` reference, so the data it refers to cannot be borrowed as mutable
-    let minus_1 = BigNum::from_dec_str("-1").unwrap(); 
-    let mut minus_a_1 = EcPoint::new(&params.c).unwrap();
-    minus_a_1.mul(&params.c, &pi.a_1, &minus_1, &mut ctx).unwrap();
-    A1rel.insert(minus_a_1, BigNum::from_u32(1).unwrap());
+    let minus_1 = BigNum::from_dec_str("-1")?; 
+    let mut minus_a_1 = EcPoint::new(params.c)?;
+    minus_a_1.mul(params.c, &pi.a_1, &minus_1, &ctx)?;
+    A1rel.insert(minus_a_1, BigNum::from_u32(1)?)?;
     
     let mut A2rel = Relation::new(params.c);
-    A2rel.insert(params.g.to_owned(&params.c).unwrap(), pi.t_x.to_owned().unwrap());
-    A2rel.insert(params.h.to_owned(&params.c).unwrap(), pi.t_r2.to_owned().unwrap());
-    A2rel.insert(C2, cc.to_owned().unwrap());
+    A2rel.insert(params.g.to_owned(params.c)?, pi.t_x.to_owned()?)?;
+    A2rel.insert(params.h.to_owned(params.c)?, pi.t_r2.to_owned()?)?;
+    A2rel.insert(C2, cc.to_owned()?)?;
     // pi.a_1.invert(&params.c, &mut ctx);
     // invert function cannot be used because:
     // `pi` is a `&` reference, so the data it refers to cannot be borrowed as mutable
-    let minus_1 = BigNum::from_dec_str("-1").unwrap(); 
-    let mut minus_a_2 = EcPoint::new(&params.c).unwrap();
-    minus_a_2.mul(&params.c, &pi.a_2, &minus_1, &mut ctx).unwrap();
-    A2rel.insert(minus_a_2, BigNum::from_u32(1).unwrap());
+    let minus_1 = BigNum::from_dec_str("-1")?; 
+    let mut minus_a_2 = EcPoint::new(params.c)?;
+    minus_a_2.mul(params.c, &pi.a_2, &minus_1, &ctx)?;
+    A2rel.insert(minus_a_2, BigNum::from_u32(1)?)?;
     
-    A1rel.drain(multi);
-    A2rel.drain(multi);
+    A1rel.drain(multi)?;
+    A2rel.drain(multi)?;
+
+    Ok(true)
+}
+
+
+/// An equality proof of a batch, with the commitments it is about and the
+/// transcript it was made with.
+pub struct EqualityBatchItem<'a> {
+    pub C1: EcPoint,
+    pub C2: EcPoint,
+    pub pi: &'a EqualityProof<'a>,
+    pub transcript: Transcript,
+}
 
-    true
-}
\ No newline at end of file
+/**
+ * Verifies many equality proofs under the same params with one multi-scalar
+ * multiplication, and bisects the batch if it fails.
+ * Returns the indices of the proofs that do not verify, empty if all do.
+ *
+ * @param params
+ * @param items proofs with their commitments and transcripts
+ */
+pub fn batch_verify_equality<'a>(
+    params: &'a PedersenParams<'a>,
+    items: Vec<EqualityBatchItem<'a>>,
+) -> Result<Vec<usize>> {
+
+    let mut batch = Batch::new(vec![params.c]);
+    batch.add_known(0, params.g.to_owned(params.c)?)?;
+    batch.add_known(0, params.h.to_owned(params.c)?)?;
+
+    for item in items {
+        let EqualityBatchItem { C1, C2, pi, mut transcript } = item;
+        batch.push(|multi| aggregate_equality(params, C1, C2, pi, &mut multi[0], &mut transcript))?;
+    }
+
+    batch.invalid()
+}
@@ -0,0 +1,493 @@
+use openssl::ec::{EcGroupRef, EcPoint};
+use openssl::bn::{BigNum, BigNumContext};
+use rand::{CryptoRng, RngCore};
+use rand::rngs::OsRng;
+
+use crate::commit::pedersen::{Commitment, PedersenParams, generate_random_with_rng};
+use crate::curves::multimult::{MultiMult, Relation};
+use crate::encoding::{Reader, Writer, TAG_MEMBERSHIP};
+use crate::error::{Result, ZkError};
+use crate::transcript::Transcript;
+
+/// Fiat-Shamir label of the membership proof.
+pub const LABEL_MEMBERSHIP: &[u8] = b"zkattest/membership";
+
+
+//#[derive(Serialize, Deserialize)]
+pub struct MembershipProof<'a> {
+    pub group: &'a EcGroupRef,
+    pub c_l: Vec<EcPoint>,
+    pub c_a: Vec<EcPoint>,
+    pub c_b: Vec<EcPoint>,
+    pub c_d: Vec<EcPoint>,
+    pub f: Vec<BigNum>,
+    pub z_a: Vec<BigNum>,
+    pub z_b: Vec<BigNum>,
+    pub z_d: BigNum,
+}
+
+
+impl<'a> MembershipProof<'a> {
+    #[allow(clippy::should_implement_trait)]
+    pub fn eq(&self, other: &MembershipProof) -> bool {
+
+        let Ok(mut ctx) = BigNumContext::new() else { return false };
+
+        let eq_points = |a: &Vec<EcPoint>, b: &Vec<EcPoint>, ctx: &mut BigNumContext| {
+            a.len() == b.len() &&
+            a.iter().zip(b.iter()).all(|(p, q)| p.eq(self.group, q, ctx).unwrap_or(false))
+        };
+
+        eq_points(&self.c_l, &other.c_l, &mut ctx) &&
+        eq_points(&self.c_a, &other.c_a, &mut ctx) &&
+        eq_points(&self.c_b, &other.c_b, &mut ctx) &&
+        eq_points(&self.c_d, &other.c_d, &mut ctx) &&
+        self.f == other.f &&
+        self.z_a == other.z_a &&
+        self.z_b == other.z_b &&
+        self.z_d == other.z_d
+    }
+
+    pub(crate) fn write(&self, w: &mut Writer) -> Result<()> {
+        w.length(self.c_l.len())?;
+        for points in [&self.c_l, &self.c_a, &self.c_b, &self.c_d] {
+            if points.len() != self.c_l.len() {
+                return Err(ZkError::MalformedProof("proof length mismatch"));
+            }
+            points.iter().try_for_each(|p| w.point(self.group, p))?;
+        }
+        for scalars in [&self.f, &self.z_a, &self.z_b] {
+            if scalars.len() != self.c_l.len() {
+                return Err(ZkError::MalformedProof("proof length mismatch"));
+            }
+            scalars.iter().try_for_each(|s| w.scalar(self.group, s))?;
+        }
+        w.scalar(self.group, &self.z_d)
+    }
+
+    pub(crate) fn read(r: &mut Reader, group: &'a EcGroupRef) -> Result<Self> {
+        let item_len = 4 * r.point_len(group) + 3 * r.scalar_len(group)?;
+        let n = r.length(item_len)?;
+
+        let points = |r: &mut Reader| (0..n).map(|_| r.point(group)).collect::<Result<Vec<_>>>();
+        let c_l = points(r)?;
+        let c_a = points(r)?;
+        let c_b = points(r)?;
+        let c_d = points(r)?;
+
+        let scalars = |r: &mut Reader| (0..n).map(|_| r.scalar(group)).collect::<Result<Vec<_>>>();
+        let f = scalars(r)?;
+        let z_a = scalars(r)?;
+        let z_b = scalars(r)?;
+
+        Ok(MembershipProof {
+            group,
+            c_l,
+            c_a,
+            c_b,
+            c_d,
+            f,
+            z_a,
+            z_b,
+            z_d: r.scalar(group)?,
+        })
+    }
+
+    /// Encodes the proof as version || tag (no hash id, see encoding) || n || c_l || c_a || c_b || c_d || f || z_a || z_b || z_d
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut w = Writer::new(TAG_MEMBERSHIP)?;
+        self.write(&mut w)?;
+        Ok(w.finish())
+    }
+
+    pub fn from_bytes(group: &'a EcGroupRef, bytes: &[u8]) -> Result<Self> {
+        let mut r = Reader::new(bytes, TAG_MEMBERSHIP)?;
+        let pi = Self::read(&mut r, group)?;
+        r.finish()?;
+        Ok(pi)
+    }
+}
+
+
+/*
+
+        UTIL::lists
+
+*/
+
+// Number of bits n such that the list padded to N = 2^n covers len
+fn list_bits(len: usize) -> usize {
+    let mut n = 1;
+    while (1 << n) < len {
+        n += 1;
+    }
+    n
+}
+
+// Commitments C_i = C - v_i g to zero, padded with the last value up to 2^n
+fn zero_commitments(params: &PedersenParams, C: &EcPoint, values: &[BigNum], n: usize) -> Result<Vec<EcPoint>> {
+
+    let mut ctx = BigNumContext::new()?;
+
+    let mut order_curve = BigNum::new()?;
+    params.c.order(&mut order_curve, &mut ctx)?;
+
+    (0..(1 << n)).map(|i| {
+        let v = &values[i.min(values.len() - 1)];
+
+        let mut minus_v = BigNum::new()?;
+        minus_v.mod_sub(BigNum::from_u32(0)?.as_ref(), v, &order_curve, &mut ctx)?;
+
+        let mut minus_v_g = EcPoint::new(params.c)?;
+        minus_v_g.mul(params.c, &params.g, &minus_v, &ctx)?;
+
+        let mut C_i = EcPoint::new(params.c)?;
+        C_i.add(params.c, C, &minus_v_g, &mut ctx)?;
+        Ok(C_i)
+    }).collect()
+}
+
+// Multiplies the polynomial poly by (a + b X)
+fn poly_mul_linear(poly: &[BigNum], a: &BigNum, b: &BigNum, order_curve: &BigNum, ctx: &mut BigNumContext) -> Result<Vec<BigNum>> {
+
+    let mut ret: Vec<BigNum> = (0..poly.len() + 1).map(|_| BigNum::from_u32(0)).collect::<std::result::Result<_, _>>()?;
+
+    for (k, coef) in poly.iter().enumerate() {
+        let mut coef_a = BigNum::new()?;
+        coef_a.mod_mul(coef, a, order_curve, ctx)?;
+        let mut sum = BigNum::new()?;
+        sum.mod_add(&ret[k], &coef_a, order_curve, ctx)?;
+        ret[k] = sum;
+
+        let mut coef_b = BigNum::new()?;
+        coef_b.mod_mul(coef, b, order_curve, ctx)?;
+        let mut sum = BigNum::new()?;
+        sum.mod_add(&ret[k + 1], &coef_b, order_curve, ctx)?;
+        ret[k + 1] = sum;
+    }
+
+    Ok(ret)
+}
+
+
+// Challenge x = H(transcript, params, C, C_i, c_l, c_a, c_b, c_d)
+fn membership_challenge(transcript: &mut Transcript, params: &PedersenParams, points: &[&EcPoint]) -> Result<BigNum> {
+    transcript.append_message(b"protocol", LABEL_MEMBERSHIP);
+    transcript.append_params(b"params", params)?;
+    transcript.append_u64(b"points", points.len() as u64);
+    for p in points {
+        transcript.append_point(b"point", params.c, p)?;
+    }
+    transcript.challenge_scalar(b"x", params.c)
+}
+
+
+/**
+ * One-out-of-many proof (Groth-Kohlweiss)
+ * ZK(l, r: C = values[l] G + r H)
+ *
+ * @param params
+ * @param values public list of values
+ * @param which index l of the committed value in values
+ * @param C commitment to values[which]
+ * @param transcript transcript shared with the enclosing proof
+ */
+pub fn prove_membership<'a>(
+    params: &PedersenParams<'a>,
+    values: &[BigNum],
+    which: usize,
+    C: Commitment,
+    transcript: &mut Transcript,
+) -> Result<MembershipProof<'a>> {
+    prove_membership_with_rng(params, values, which, C, transcript, &mut OsRng)
+}
+
+/// prove_membership with the randomness drawn from rng.
+pub fn prove_membership_with_rng<'a>(
+    params: &PedersenParams<'a>,
+    values: &[BigNum],
+    which: usize,
+    C: Commitment,
+    transcript: &mut Transcript,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Result<MembershipProof<'a>> {
+
+    if which >= values.len() {
+        return Err(ZkError::InvalidInput("index out of range"));
+    }
+
+    let mut ctx = BigNumContext::new()?;
+
+    // Take group order
+    let mut order_curve = BigNum::new()?;
+    params.c.order(&mut order_curve, &mut ctx)?;
+
+    let zero = BigNum::from_u32(0)?;
+    let one = BigNum::from_u32(1)?;
+
+    let n = list_bits(values.len());
+    let C_i = zero_commitments(params, &C.p, values, n)?;
+
+    // Step 1: Commit to the bits of l
+    let l_bits: Vec<bool> = (0..n).map(|j| (which >> j) & 1 == 1).collect();
+
+    let mut a : Vec<BigNum> = Vec::with_capacity(n);
+    let mut c_l : Vec<Commitment> = Vec::with_capacity(n);
+    let mut c_a : Vec<Commitment> = Vec::with_capacity(n);
+    let mut c_b : Vec<Commitment> = Vec::with_capacity(n);
+
+    for &l_j in &l_bits {
+        let a_j = generate_random_with_rng(&order_curve, rng)?;
+
+        c_l.push(params.commit_with_rng(if l_j { &one } else { &zero }, rng)?);
+        c_a.push(params.commit_with_rng(&a_j, rng)?);
+        c_b.push(params.commit_with_rng(if l_j { &a_j } else { &zero }, rng)?);
+
+        a.push(a_j);
+    }
+
+    // Step 2: Coefficients p_{i,k} of p_i(X) = prod_j f_{j,i_j}(X)
+    //      f_{j,1}(X) = l_j X + a_j
+    //      f_{j,0}(X) = (1 - l_j) X - a_j
+    let mut coefs : Vec<Vec<BigNum>> = Vec::with_capacity(1 << n);
+    for i in 0..(1 << n) {
+        let mut poly = vec![BigNum::from_u32(1)?];
+        for j in 0..n {
+            let mut minus_a_j = BigNum::new()?;
+            minus_a_j.mod_sub(&zero, &a[j], &order_curve, &mut ctx)?;
+
+            poly = match ((i >> j) & 1 == 1, l_bits[j]) {
+                (true, true) => poly_mul_linear(&poly, &a[j], &one, &order_curve, &mut ctx)?,
+                (true, false) => poly_mul_linear(&poly, &a[j], &zero, &order_curve, &mut ctx)?,
+                (false, true) => poly_mul_linear(&poly, &minus_a_j, &zero, &order_curve, &mut ctx)?,
+                (false, false) => poly_mul_linear(&poly, &minus_a_j, &one, &order_curve, &mut ctx)?,
+            };
+        }
+        coefs.push(poly);
+    }
+
+    // c_d_k = sum_i p_{i,k} C_i + rho_k H
+    let rho : Vec<BigNum> = (0..n).map(|_| generate_random_with_rng(&order_curve, rng)).collect::<Result<_>>()?;
+    let mut c_d : Vec<EcPoint> = rho.iter().map(|rho_k| {
+        let mut rho_k_h = EcPoint::new(params.c)?;
+        rho_k_h.mul(params.c, &params.h, rho_k, &ctx)?;
+        Ok(rho_k_h)
+    }).collect::<Result<_>>()?;
+
+    for (poly, C_i) in coefs.iter().zip(C_i.iter()) {
+        for (c_d_k, p_ik) in c_d.iter_mut().zip(poly.iter()) {
+            let mut p_ik_C_i = EcPoint::new(params.c)?;
+            p_ik_C_i.mul(params.c, C_i, p_ik, &ctx)?;
+            let c_d_k_int = c_d_k.to_owned(params.c)?;
+            c_d_k.add(params.c, &c_d_k_int, &p_ik_C_i, &mut ctx)?;
+        }
+    }
+
+    // Step 3: Compute challenge H(C, C_i, c_l, c_a, c_b, c_d)
+    let mut arr : Vec<&EcPoint> = Vec::with_capacity(C_i.len() + 4 * n + 1);
+    arr.push(&C.p);
+    arr.extend(C_i.iter());
+    arr.extend(c_l.iter().map(|c| &c.p));
+    arr.extend(c_a.iter().map(|c| &c.p));
+    arr.extend(c_b.iter().map(|c| &c.p));
+    arr.extend(c_d.iter());
+    let challenge = membership_challenge(transcript, params, &arr)?;
+
+    let mut x = BigNum::new()?;
+    x.nnmod(&challenge, &order_curve, &mut ctx)?;
+
+    // Step 4: Responses
+    let mut f : Vec<BigNum> = Vec::with_capacity(n);
+    let mut z_a : Vec<BigNum> = Vec::with_capacity(n);
+    let mut z_b : Vec<BigNum> = Vec::with_capacity(n);
+    for j in 0..n {
+        // f_j = l_j x + a_j
+        let mut f_j = BigNum::new()?;
+        if l_bits[j] {
+            f_j.mod_add(&x, &a[j], &order_curve, &mut ctx)?;
+        } else {
+            f_j.nnmod(&a[j], &order_curve, &mut ctx)?;
+        }
+
+        // z_a_j = r_j x + s_j
+        let mut r_j_x = BigNum::new()?;
+        r_j_x.mod_mul(&c_l[j].r, &x, &order_curve, &mut ctx)?;
+        let mut z_a_j = BigNum::new()?;
+        z_a_j.mod_add(&r_j_x, &c_a[j].r, &order_curve, &mut ctx)?;
+
+        // z_b_j = r_j (x - f_j) + t_j
+        let mut x_minus_f_j = BigNum::new()?;
+        x_minus_f_j.mod_sub(&x, &f_j, &order_curve, &mut ctx)?;
+        let mut r_j_x_minus_f_j = BigNum::new()?;
+        r_j_x_minus_f_j.mod_mul(&c_l[j].r, &x_minus_f_j, &order_curve, &mut ctx)?;
+        let mut z_b_j = BigNum::new()?;
+        z_b_j.mod_add(&r_j_x_minus_f_j, &c_b[j].r, &order_curve, &mut ctx)?;
+
+        f.push(f_j);
+        z_a.push(z_a_j);
+        z_b.push(z_b_j);
+    }
+
+    // z_d = r x^n - sum_k rho_k x^k
+    let mut x_k = BigNum::from_u32(1)?;
+    let mut sum_rho = BigNum::from_u32(0)?;
+    for rho_k in &rho {
+        let mut rho_k_x_k = BigNum::new()?;
+        rho_k_x_k.mod_mul(rho_k, &x_k, &order_curve, &mut ctx)?;
+        let sum_rho_int = sum_rho.to_owned()?;
+        sum_rho.mod_add(&sum_rho_int, &rho_k_x_k, &order_curve, &mut ctx)?;
+
+        let x_k_int = x_k.to_owned()?;
+        x_k.mod_mul(&x_k_int, &x, &order_curve, &mut ctx)?;
+    }
+    let mut r_x_n = BigNum::new()?;
+    r_x_n.mod_mul(&C.r, &x_k, &order_curve, &mut ctx)?;
+    let mut z_d = BigNum::new()?;
+    z_d.mod_sub(&r_x_n, &sum_rho, &order_curve, &mut ctx)?;
+
+    Ok(MembershipProof {
+        group: params.c,
+        c_l: c_l.into_iter().map(|c| c.p).collect(),
+        c_a: c_a.into_iter().map(|c| c.p).collect(),
+        c_b: c_b.into_iter().map(|c| c.p).collect(),
+        c_d,
+        f,
+        z_a,
+        z_b,
+        z_d,
+    })
+}
+
+
+pub fn verify_membership<'a>(
+    params: &'a PedersenParams<'a>,
+    values: &[BigNum],
+    C: EcPoint,
+    pi: &'a MembershipProof<'a>,
+    transcript: &mut Transcript
+) -> Result<bool> {
+
+    let mut multi = MultiMult::new(params.c);
+
+    let ok = aggregate_membership(params, values, C, pi, &mut multi, transcript)?;
+
+    if !ok {
+        return Ok(false)
+    }
+
+    Ok(multi.evaluate()?.is_infinity(params.c))
+}
+
+
+pub fn aggregate_membership<'a> (
+    params: &'a PedersenParams<'a>,
+    values: &[BigNum],
+    C: EcPoint,
+    pi: &'a MembershipProof<'a>,
+    multi: &mut MultiMult,
+    transcript: &mut Transcript
+) -> Result<bool> {
+
+    if values.is_empty() {
+        return Ok(false)
+    }
+
+    let n = list_bits(values.len());
+    if pi.c_l.len() != n || pi.c_a.len() != n || pi.c_b.len() != n || pi.c_d.len() != n ||
+        pi.f.len() != n || pi.z_a.len() != n || pi.z_b.len() != n {
+        return Ok(false)
+    }
+
+    let mut ctx = BigNumContext::new()?;
+
+    let C_i = zero_commitments(params, &C, values, n)?;
+
+    // Compute challenge
+    let mut arr : Vec<&EcPoint> = Vec::with_capacity(C_i.len() + 4 * n + 1);
+    arr.push(&C);
+    arr.extend(C_i.iter());
+    arr.extend(pi.c_l.iter());
+    arr.extend(pi.c_a.iter());
+    arr.extend(pi.c_b.iter());
+    arr.extend(pi.c_d.iter());
+    let challenge = membership_challenge(transcript, params, &arr)?;
+    // new scalar challenge
+    let mut order_curve = BigNum::new()?;
+    params.c.order(&mut order_curve, &mut ctx)?;
+    let mut x = BigNum::new()?;
+    x.nnmod(&challenge, &order_curve, &mut ctx)?;
+
+    let zero = BigNum::from_u32(0)?;
+    let minus_1 = BigNum::from_dec_str("-1")?;
+
+    for j in 0..n {
+        let mut minus_f_j = BigNum::new()?;
+        minus_f_j.mod_sub(&zero, &pi.f[j], &order_curve, &mut ctx)?;
+        let mut minus_z_a_j = BigNum::new()?;
+        minus_z_a_j.mod_sub(&zero, &pi.z_a[j], &order_curve, &mut ctx)?;
+        let mut minus_z_b_j = BigNum::new()?;
+        minus_z_b_j.mod_sub(&zero, &pi.z_b[j], &order_curve, &mut ctx)?;
+        let mut x_minus_f_j = BigNum::new()?;
+        x_minus_f_j.mod_sub(&x, &pi.f[j], &order_curve, &mut ctx)?;
+
+        // x c_l_j + c_a_j - f_j G - z_a_j H = 0
+        let mut A_rel = Relation::new(params.c);
+        A_rel.insert_m(
+            &[pi.c_l[j].to_owned(params.c)?,
+            pi.c_a[j].to_owned(params.c)?,
+            params.g.to_owned(params.c)?,
+            params.h.to_owned(params.c)?],
+            &[x.to_owned()?,
+            BigNum::from_u32(1)?,
+            minus_f_j,
+            minus_z_a_j])?;
+
+        // (x - f_j) c_l_j + c_b_j - z_b_j H = 0
+        let mut B_rel = Relation::new(params.c);
+        B_rel.insert_m(
+            &[pi.c_l[j].to_owned(params.c)?,
+            pi.c_b[j].to_owned(params.c)?,
+            params.h.to_owned(params.c)?],
+            &[x_minus_f_j,
+            BigNum::from_u32(1)?,
+            minus_z_b_j])?;
+
+        A_rel.drain(multi)?;
+        B_rel.drain(multi)?;
+    }
+
+    // sum_i p_i(x) C_i - sum_k x^k c_d_k - z_d H = 0
+    let mut D_rel = Relation::new(params.c);
+    for (i, C_i) in C_i.into_iter().enumerate() {
+        let mut p_i = BigNum::from_u32(1)?;
+        for j in 0..n {
+            let mut f_j_i = BigNum::new()?;
+            if (i >> j) & 1 == 1 {
+                f_j_i.nnmod(&pi.f[j], &order_curve, &mut ctx)?;
+            } else {
+                f_j_i.mod_sub(&x, &pi.f[j], &order_curve, &mut ctx)?;
+            }
+            let p_i_int = p_i.to_owned()?;
+            p_i.mod_mul(&p_i_int, &f_j_i, &order_curve, &mut ctx)?;
+        }
+        D_rel.insert(C_i, p_i)?;
+    }
+
+    let mut x_k = BigNum::from_u32(1)?;
+    for c_d_k in &pi.c_d {
+        let mut minus_c_d_k = EcPoint::new(params.c)?;
+        minus_c_d_k.mul(params.c, c_d_k, &minus_1, &ctx)?;
+        D_rel.insert(minus_c_d_k, x_k.to_owned()?)?;
+
+        let x_k_int = x_k.to_owned()?;
+        x_k.mod_mul(&x_k_int, &x, &order_curve, &mut ctx)?;
+    }
+
+    let mut minus_z_d = BigNum::new()?;
+    minus_z_d.mod_sub(&zero, &pi.z_d, &order_curve, &mut ctx)?;
+    D_rel.insert(params.h.to_owned(params.c)?, minus_z_d)?;
+
+    D_rel.drain(multi)?;
+
+    Ok(true)
+}
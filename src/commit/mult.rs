@@ -1,12 +1,16 @@
-use openssl::ec::{EcGroup, EcGroupRef, EcPoint, EcPointRef, PointConversionForm};
-use openssl::bn::{BigNum, BigNumRef, BigNumContext, MsbOption};
-use openssl::error::ErrorStack;
-use openssl::hash::{hash, MessageDigest};
+use openssl::ec::{EcGroupRef, EcPoint};
+use openssl::bn::{BigNum, BigNumContext};
+use rand::{CryptoRng, RngCore};
+use rand::rngs::OsRng;
 
-use crate::commit::pedersen::{Commitment, PedersenParams, generate_random};
-use crate::curves::multimult::{MultiMult, Relation};
+use crate::commit::pedersen::{Commitment, PedersenParams, generate_random_with_rng};
+use crate::curves::multimult::{Batch, MultiMult, Relation};
+use crate::encoding::{Reader, Writer, TAG_MULT};
+use crate::error::Result;
+use crate::transcript::Transcript;
 
-use crate::equality::hash_points;
+/// Fiat-Shamir label of the mult proof.
+pub const LABEL_MULT: &[u8] = b"zkattest/mult";
 
 //#[derive(Serialize, Deserialize)]
 pub struct MultProof<'a> {
@@ -28,16 +32,17 @@ pub struct MultProof<'a> {
 
 
 impl<'a> MultProof<'a> {
+    #[allow(clippy::should_implement_trait)]
     pub fn eq(&self, other: &MultProof) -> bool {
         
-        let mut ctx = BigNumContext::new().unwrap();
-
-        self.c_4.eq(self.group, &other.c_4, &mut ctx).unwrap() &&
-        self.a_x.eq(self.group, &other.a_x, &mut ctx).unwrap() &&
-        self.a_y.eq(self.group, &other.a_y, &mut ctx).unwrap() &&
-        self.a_z.eq(self.group, &other.a_z, &mut ctx).unwrap() &&
-        self.a_4_1.eq(self.group, &other.a_4_1, &mut ctx).unwrap() &&
-        self.a_4_2.eq(self.group, &other.a_4_2, &mut ctx).unwrap() &&
+        let Ok(mut ctx) = BigNumContext::new() else { return false };
+
+        self.c_4.eq(self.group, &other.c_4, &mut ctx).unwrap_or(false) &&
+        self.a_x.eq(self.group, &other.a_x, &mut ctx).unwrap_or(false) &&
+        self.a_y.eq(self.group, &other.a_y, &mut ctx).unwrap_or(false) &&
+        self.a_z.eq(self.group, &other.a_z, &mut ctx).unwrap_or(false) &&
+        self.a_4_1.eq(self.group, &other.a_4_1, &mut ctx).unwrap_or(false) &&
+        self.a_4_2.eq(self.group, &other.a_4_2, &mut ctx).unwrap_or(false) &&
         self.t_x == other.t_x &&
         self.t_y == other.t_y &&
         self.t_z == other.t_z &&
@@ -46,9 +51,64 @@ impl<'a> MultProof<'a> {
         self.t_rz == other.t_rz &&
         self.t_r4 == other.t_r4
     }
+
+    pub(crate) fn write(&self, w: &mut Writer) -> Result<()> {
+        for p in [&self.c_4, &self.a_x, &self.a_y, &self.a_z, &self.a_4_1, &self.a_4_2] {
+            w.point(self.group, p)?;
+        }
+        for t in [&self.t_x, &self.t_y, &self.t_z, &self.t_rx, &self.t_ry, &self.t_rz, &self.t_r4] {
+            w.scalar(self.group, t)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn read(r: &mut Reader, group: &'a EcGroupRef) -> Result<Self> {
+        Ok(MultProof {
+            group,
+            c_4: r.point(group)?,
+            a_x: r.point(group)?,
+            a_y: r.point(group)?,
+            a_z: r.point(group)?,
+            a_4_1: r.point(group)?,
+            a_4_2: r.point(group)?,
+            t_x: r.scalar(group)?,
+            t_y: r.scalar(group)?,
+            t_z: r.scalar(group)?,
+            t_rx: r.scalar(group)?,
+            t_ry: r.scalar(group)?,
+            t_rz: r.scalar(group)?,
+            t_r4: r.scalar(group)?,
+        })
+    }
+
+    /// Encodes the proof as version || tag (no hash id, see encoding) || points (C4, Ax, Ay, Az, A4_1, A4_2) || scalars (t_x ... t_r4)
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut w = Writer::new(TAG_MULT)?;
+        self.write(&mut w)?;
+        Ok(w.finish())
+    }
+
+    pub fn from_bytes(group: &'a EcGroupRef, bytes: &[u8]) -> Result<Self> {
+        let mut r = Reader::new(bytes, TAG_MULT)?;
+        let pi = Self::read(&mut r, group)?;
+        r.finish()?;
+        Ok(pi)
+    }
 }
 
 
+// Challenge c = H(transcript, params, Cx, Cy, Cz, C4, Ax, Ay, Az, A4_1, A4_2)
+fn mult_challenge(transcript: &mut Transcript, params: &PedersenParams, points: [&EcPoint; 9]) -> Result<BigNum> {
+    const LABELS: [&[u8]; 9] = [b"Cx", b"Cy", b"Cz", b"C4", b"Ax", b"Ay", b"Az", b"A4_1", b"A4_2"];
+
+    transcript.append_message(b"protocol", LABEL_MULT);
+    transcript.append_params(b"params", params)?;
+    for (label, p) in LABELS.iter().zip(points) {
+        transcript.append_point(label, params.c, p)?;
+    }
+    transcript.challenge_scalar(b"c", params.c)
+}
+
 /*
  * Proof of multiplication
  * ZK(x, y, z, rx, ry, rz: z = x * y and Cx = xG + rx H and Cy = yG + ry H and Cz = zG + rz H)
@@ -60,112 +120,130 @@ impl<'a> MultProof<'a> {
  * Cx: Commitment,
  * Cy: Commitment,
  * Cz: Commitment
- * 
+ * transcript: transcript shared with the enclosing proof
  */
 
  
+#[allow(clippy::too_many_arguments)]
 pub fn prov_mult<'a>(
-    params: &'a PedersenParams<'a>,
+    params: &PedersenParams<'a>,
+    x: BigNum,
+    y: BigNum,
+    z: BigNum,
+    Cx: Commitment,
+    Cy: Commitment,
+    Cz: Commitment,
+    transcript: &mut Transcript,
+) -> Result<MultProof<'a>> {
+    prov_mult_with_rng(params, x, y, z, Cx, Cy, Cz, transcript, &mut OsRng)
+}
+
+/// prov_mult with the randomness drawn from rng.
+#[allow(clippy::too_many_arguments)]
+pub fn prov_mult_with_rng<'a>(
+    params: &PedersenParams<'a>,
     x: BigNum,
     y: BigNum,
     z: BigNum,
     Cx: Commitment,
     Cy: Commitment,
-    Cz: Commitment
-) -> MultProof<'a> {
-    let mut ctx = BigNumContext::new().unwrap();
+    Cz: Commitment,
+    transcript: &mut Transcript,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Result<MultProof<'a>> {
+    let mut ctx = BigNumContext::new()?;
 
     // Take group order
-    let mut order_curve = BigNum::new().unwrap();
-    params.c.order(&mut order_curve, &mut ctx).unwrap();
+    let mut order_curve = BigNum::new()?;
+    params.c.order(&mut order_curve, &mut ctx)?;
 
     // Compute xx, C4 , r4
     // New scalar
-    let mut xx = BigNum::new().unwrap();
-    xx.nnmod(&x, &order_curve, &mut ctx).unwrap();
+    let mut xx = BigNum::new()?;
+    xx.nnmod(&x, &order_curve, &mut ctx)?;
 
-    let mut C4 = EcPoint::new(&params.c).unwrap();
-    C4.mul(&params.c, &Cy.p, &xx, &mut ctx).unwrap(); // C4 = Cy * x
+    let mut C4 = EcPoint::new(params.c)?;
+    C4.mul(params.c, &Cy.p, &xx, &ctx)?; // C4 = Cy * x
     
-    let mut r4 = BigNum::new().unwrap();
-    r4.mod_mul(&Cy.r, &xx, &order_curve, &mut ctx).unwrap(); // C4 = zG + r4H
+    let mut r4 = BigNum::new()?;
+    r4.mod_mul(&Cy.r, &xx, &order_curve, &mut ctx)?; // C4 = zG + r4H
 
     // Step 1: Compute commitments
-    let k_x = generate_random(&order_curve).unwrap(); 
-    let k_y = generate_random(&order_curve).unwrap(); 
-    let k_z = generate_random(&order_curve).unwrap(); 
+    let k_x = generate_random_with_rng(&order_curve, rng)?; 
+    let k_y = generate_random_with_rng(&order_curve, rng)?; 
+    let k_z = generate_random_with_rng(&order_curve, rng)?; 
 
-    let Ax = params.commit(&k_x);
-    let Ay = params.commit(&k_y);
-    let Az = params.commit(&k_z);
+    let Ax = params.commit_with_rng(&k_x, rng)?;
+    let Ay = params.commit_with_rng(&k_y, rng)?;
+    let Az = params.commit_with_rng(&k_z, rng)?;
 
-    let A4_1 = params.commit(&k_z); // TODO: check logic
+    let A4_1 = params.commit_with_rng(&k_z, rng)?; // TODO: check logic
 
     // New scalar
-    let mut kx = BigNum::new().unwrap();
-    kx.nnmod(&k_x, &order_curve, &mut ctx).unwrap();
+    let mut kx = BigNum::new()?;
+    kx.nnmod(&k_x, &order_curve, &mut ctx)?;
     
-    let mut A4_2 = EcPoint::new(&params.c).unwrap();
-    A4_2.mul(&params.c, &Cy.p, &kx, &mut ctx).unwrap(); // C4 = Cy * kx
+    let mut A4_2 = EcPoint::new(params.c)?;
+    A4_2.mul(params.c, &Cy.p, &kx, &ctx)?; // C4 = Cy * kx
     
     // Step 2: Compute challenge  H(Cx, Cy, Cz, C4, Ax, Ay, Az, A4_1, A4_2)
 
-    let c = hash_points(MessageDigest::sha256(), &[params.c], &[&Cx.p, &Cy.p, &Cz.p, &C4, &Ax.p, &Ay.p, &Az.p, &A4_1.p, &A4_2]).unwrap();
+    let c = mult_challenge(transcript, params, [&Cx.p, &Cy.p, &Cz.p, &C4, &Ax.p, &Ay.p, &Az.p, &A4_1.p, &A4_2])?;
     
     // New scalar
-    let mut cc = BigNum::new().unwrap();
-    cc.nnmod(&c, &order_curve, &mut ctx).unwrap();
-    let mut yy = BigNum::new().unwrap();
-    yy.nnmod(&y, &order_curve, &mut ctx).unwrap();
-    let mut zz = BigNum::new().unwrap();
-    zz.nnmod(&z, &order_curve, &mut ctx).unwrap();
+    let mut cc = BigNum::new()?;
+    cc.nnmod(&c, &order_curve, &mut ctx)?;
+    let mut yy = BigNum::new()?;
+    yy.nnmod(&y, &order_curve, &mut ctx)?;
+    let mut zz = BigNum::new()?;
+    zz.nnmod(&z, &order_curve, &mut ctx)?;
     // new scalar for ky and kz is missing
     //      ky = params.c.newScalar(k_y),
     //      kz = params.c.newScalar(k_z),
 
     // Compute tx = kx - c * x
-    let mut cc_times_xx = BigNum::new().unwrap();
-    cc_times_xx.mod_mul(&cc, &xx, &order_curve, &mut ctx).unwrap();
-    let mut t_x = BigNum::new().unwrap();
-    t_x.mod_sub(&kx, &cc_times_xx, &order_curve, &mut ctx).unwrap();
+    let mut cc_times_xx = BigNum::new()?;
+    cc_times_xx.mod_mul(&cc, &xx, &order_curve, &mut ctx)?;
+    let mut t_x = BigNum::new()?;
+    t_x.mod_sub(&kx, &cc_times_xx, &order_curve, &mut ctx)?;
 
     // Compute ty = ky - c * y
-    let mut cc_times_yy = BigNum::new().unwrap();
-    cc_times_yy.mod_mul(&cc, &yy, &order_curve, &mut ctx).unwrap();
-    let mut t_y = BigNum::new().unwrap();
-    t_y.mod_sub(&k_y, &cc_times_yy, &order_curve, &mut ctx).unwrap();
+    let mut cc_times_yy = BigNum::new()?;
+    cc_times_yy.mod_mul(&cc, &yy, &order_curve, &mut ctx)?;
+    let mut t_y = BigNum::new()?;
+    t_y.mod_sub(&k_y, &cc_times_yy, &order_curve, &mut ctx)?;
 
     // Compute tz = kz - c * z
-    let mut cc_times_zz = BigNum::new().unwrap();
-    cc_times_zz.mod_mul(&cc, &zz, &order_curve, &mut ctx).unwrap();
-    let mut t_z = BigNum::new().unwrap();
-    t_z.mod_sub(&k_z, &cc_times_zz, &order_curve, &mut ctx).unwrap();
+    let mut cc_times_zz = BigNum::new()?;
+    cc_times_zz.mod_mul(&cc, &zz, &order_curve, &mut ctx)?;
+    let mut t_z = BigNum::new()?;
+    t_z.mod_sub(&k_z, &cc_times_zz, &order_curve, &mut ctx)?;
 
     // Compute t_rx = sx - c * rx
-    let mut cc_times_rx = BigNum::new().unwrap();
-    cc_times_rx.mod_mul(&cc, &Cx.r, &order_curve, &mut ctx).unwrap();   
-    let mut t_rx = BigNum::new().unwrap();
-    t_rx.mod_sub(&Ax.r, &cc_times_rx, &order_curve, &mut ctx).unwrap();
+    let mut cc_times_rx = BigNum::new()?;
+    cc_times_rx.mod_mul(&cc, &Cx.r, &order_curve, &mut ctx)?;   
+    let mut t_rx = BigNum::new()?;
+    t_rx.mod_sub(&Ax.r, &cc_times_rx, &order_curve, &mut ctx)?;
     
     // Compute t_ry = sy - c * ry
-    let mut cc_times_ry = BigNum::new().unwrap();
-    cc_times_ry.mod_mul(&cc, &Cy.r, &order_curve, &mut ctx).unwrap();   
-    let mut t_ry = BigNum::new().unwrap();
-    t_ry.mod_sub(&Ay.r, &cc_times_ry, &order_curve, &mut ctx).unwrap();
+    let mut cc_times_ry = BigNum::new()?;
+    cc_times_ry.mod_mul(&cc, &Cy.r, &order_curve, &mut ctx)?;   
+    let mut t_ry = BigNum::new()?;
+    t_ry.mod_sub(&Ay.r, &cc_times_ry, &order_curve, &mut ctx)?;
 
     // Compute t_rz = sz - c * rz
-    let mut cc_times_rz = BigNum::new().unwrap();
-    cc_times_rz.mod_mul(&cc, &Cz.r, &order_curve, &mut ctx).unwrap();   
-    let mut t_rz = BigNum::new().unwrap();
-    t_rz.mod_sub(&Az.r, &cc_times_rz, &order_curve, &mut ctx).unwrap();
+    let mut cc_times_rz = BigNum::new()?;
+    cc_times_rz.mod_mul(&cc, &Cz.r, &order_curve, &mut ctx)?;   
+    let mut t_rz = BigNum::new()?;
+    t_rz.mod_sub(&Az.r, &cc_times_rz, &order_curve, &mut ctx)?;
 
     // Compute t_r4 = s4 - c * r4
-    let mut cc_times_r4 = BigNum::new().unwrap();
-    cc_times_r4.mod_mul(&cc, &r4, &order_curve, &mut ctx).unwrap();   
-    let mut t_r4 = BigNum::new().unwrap();
-    t_r4.mod_sub(&A4_1.r, &cc_times_r4, &order_curve, &mut ctx).unwrap();
+    let mut cc_times_r4 = BigNum::new()?;
+    cc_times_r4.mod_mul(&cc, &r4, &order_curve, &mut ctx)?;   
+    let mut t_r4 = BigNum::new()?;
+    t_r4.mod_sub(&A4_1.r, &cc_times_r4, &order_curve, &mut ctx)?;
 
-    MultProof {
+    Ok(MultProof {
         group: params.c,
         c_4: C4,
         a_x: Ax.p,
@@ -180,7 +258,7 @@ pub fn prov_mult<'a>(
         t_ry,
         t_rz,
         t_r4,
-    }
+    })
 }
 
 
@@ -190,18 +268,19 @@ pub fn verify_mult<'a>(
     Cx: EcPoint,
     Cy: EcPoint,
     Cz: EcPoint,
-    pi: &'a MultProof<'a>
-) -> bool {
+    pi: &'a MultProof<'a>,
+    transcript: &mut Transcript
+) -> Result<bool> {
     
     let mut multi = MultiMult::new(params.c);
 
-    let ok = aggregate_mult(params, Cx, Cy, Cz, pi, &mut multi);
+    let ok = aggregate_mult(params, Cx, Cy, Cz, pi, &mut multi, transcript)?;
 
     if !ok {
-        return false
+        return Ok(false)
     }
     
-    multi.evaluate().is_infinity(&params.c)
+    Ok(multi.evaluate()?.is_infinity(params.c))
 }
 
 pub fn aggregate_mult<'a> ( 
@@ -210,113 +289,149 @@ pub fn aggregate_mult<'a> (
     Cy: EcPoint,
     Cz: EcPoint,
     pi: &'a MultProof<'a>,
-    multi: &mut MultiMult
-) -> bool {
-    let mut ctx = BigNumContext::new().unwrap();
+    multi: &mut MultiMult,
+    transcript: &mut Transcript
+) -> Result<bool> {
+    let mut ctx = BigNumContext::new()?;
 
     // Compute scalar
-    let challenge = hash_points(
-        MessageDigest::sha256(), 
-        &[params.c], 
-        &[&Cx, &Cy, &Cz, &pi.c_4, &pi.a_x, &pi.a_y, &pi.a_z, &pi.a_4_1, &pi.a_4_2]).unwrap();
+    let challenge = mult_challenge(
+        transcript,
+        params,
+        [&Cx, &Cy, &Cz, &pi.c_4, &pi.a_x, &pi.a_y, &pi.a_z, &pi.a_4_1, &pi.a_4_2])?;
     // new scalar challenge
-    let mut order_curve = BigNum::new().unwrap();
-    params.c.order(&mut order_curve, &mut ctx).unwrap();
-    let mut cc = BigNum::new().unwrap();
-    cc.nnmod(&challenge, &order_curve, &mut ctx).unwrap();
+    let mut order_curve = BigNum::new()?;
+    params.c.order(&mut order_curve, &mut ctx)?;
+    let mut cc = BigNum::new()?;
+    cc.nnmod(&challenge, &order_curve, &mut ctx)?;
 
 
     let mut A_xrel = Relation::new(params.c);
     // Compute -A_x
-    let minus_1 = BigNum::from_dec_str("-1").unwrap(); 
-    let mut minus_a_x = EcPoint::new(&params.c).unwrap();
-    minus_a_x.mul(&params.c, &pi.a_x, &minus_1, &mut ctx).unwrap();
+    let minus_1 = BigNum::from_dec_str("-1")?; 
+    let mut minus_a_x = EcPoint::new(params.c)?;
+    minus_a_x.mul(params.c, &pi.a_x, &minus_1, &ctx)?;
     // insert several
     A_xrel.insert_m(
-        &[params.g.to_owned(&params.c).unwrap(),
-        params.h.to_owned(&params.c).unwrap(),
+        &[params.g.to_owned(params.c)?,
+        params.h.to_owned(params.c)?,
         Cx,
         minus_a_x],
-        &[pi.t_x.to_owned().unwrap(),
-        pi.t_rx.to_owned().unwrap(),
-        cc.to_owned().unwrap(),
-        BigNum::from_u32(1).unwrap()]);
+        &[pi.t_x.to_owned()?,
+        pi.t_rx.to_owned()?,
+        cc.to_owned()?,
+        BigNum::from_u32(1)?])?;
     
 
     let mut A_yrel = Relation::new(params.c);
     // Compute -A_y
-    let minus_1 = BigNum::from_dec_str("-1").unwrap(); 
-    let mut minus_a_y = EcPoint::new(&params.c).unwrap();
-    minus_a_y.mul(&params.c, &pi.a_y, &minus_1, &mut ctx).unwrap();
+    let minus_1 = BigNum::from_dec_str("-1")?; 
+    let mut minus_a_y = EcPoint::new(params.c)?;
+    minus_a_y.mul(params.c, &pi.a_y, &minus_1, &ctx)?;
     // insert several
     A_yrel.insert_m(
-        &[params.g.to_owned(&params.c).unwrap(),
-        params.h.to_owned(&params.c).unwrap(),
-        Cy.to_owned(&params.c).unwrap(),
+        &[params.g.to_owned(params.c)?,
+        params.h.to_owned(params.c)?,
+        Cy.to_owned(params.c)?,
         minus_a_y],
-        &[pi.t_y.to_owned().unwrap(),
-        pi.t_ry.to_owned().unwrap(),
-        cc.to_owned().unwrap(),
-        BigNum::from_u32(1).unwrap()]);
+        &[pi.t_y.to_owned()?,
+        pi.t_ry.to_owned()?,
+        cc.to_owned()?,
+        BigNum::from_u32(1)?])?;
 
 
     let mut A_zrel = Relation::new(params.c);
     // Compute -A_z
-    let minus_1 = BigNum::from_dec_str("-1").unwrap(); 
-    let mut minus_a_z = EcPoint::new(&params.c).unwrap();
-    minus_a_z.mul(&params.c, &pi.a_z, &minus_1, &mut ctx).unwrap();
+    let minus_1 = BigNum::from_dec_str("-1")?; 
+    let mut minus_a_z = EcPoint::new(params.c)?;
+    minus_a_z.mul(params.c, &pi.a_z, &minus_1, &ctx)?;
     // insert several
     A_zrel.insert_m(
-        &[params.g.to_owned(&params.c).unwrap(),
-        params.h.to_owned(&params.c).unwrap(),
+        &[params.g.to_owned(params.c)?,
+        params.h.to_owned(params.c)?,
         Cz,
         minus_a_z],
-        &[pi.t_z.to_owned().unwrap(),
-        pi.t_rz.to_owned().unwrap(),
-        cc.to_owned().unwrap(),
-        BigNum::from_u32(1).unwrap()]);
+        &[pi.t_z.to_owned()?,
+        pi.t_rz.to_owned()?,
+        cc.to_owned()?,
+        BigNum::from_u32(1)?])?;
 
 
     let mut A_4_1rel = Relation::new(params.c);
     // Compute -A_4_1
-    let minus_1 = BigNum::from_dec_str("-1").unwrap(); 
-    let mut minus_a_4_1 = EcPoint::new(&params.c).unwrap();
-    minus_a_4_1.mul(&params.c, &pi.a_4_1, &minus_1, &mut ctx).unwrap();
+    let minus_1 = BigNum::from_dec_str("-1")?; 
+    let mut minus_a_4_1 = EcPoint::new(params.c)?;
+    minus_a_4_1.mul(params.c, &pi.a_4_1, &minus_1, &ctx)?;
     // insert several
     A_4_1rel.insert_m(
-        &[params.g.to_owned(&params.c).unwrap(),
-        params.h.to_owned(&params.c).unwrap(),
-        pi.c_4.to_owned(&params.c).unwrap(),
+        &[params.g.to_owned(params.c)?,
+        params.h.to_owned(params.c)?,
+        pi.c_4.to_owned(params.c)?,
         minus_a_4_1],
-        &[pi.t_z.to_owned().unwrap(),
-        pi.t_r4.to_owned().unwrap(),
-        cc.to_owned().unwrap(),
-        BigNum::from_u32(1).unwrap()]);
+        &[pi.t_z.to_owned()?,
+        pi.t_r4.to_owned()?,
+        cc.to_owned()?,
+        BigNum::from_u32(1)?])?;
 
 
     let mut A_4_2rel = Relation::new(params.c);
     // Compute -A_4_2
-    let minus_1 = BigNum::from_dec_str("-1").unwrap(); 
-    let mut minus_a_4_2 = EcPoint::new(&params.c).unwrap();
-    minus_a_4_2.mul(&params.c, &pi.a_4_2, &minus_1, &mut ctx).unwrap();
+    let minus_1 = BigNum::from_dec_str("-1")?; 
+    let mut minus_a_4_2 = EcPoint::new(params.c)?;
+    minus_a_4_2.mul(params.c, &pi.a_4_2, &minus_1, &ctx)?;
     // insert several
     A_4_2rel.insert_m(
         &[Cy,
-        pi.c_4.to_owned(&params.c).unwrap(),
+        pi.c_4.to_owned(params.c)?,
         minus_a_4_2],
-        &[pi.t_x.to_owned().unwrap(),
-        cc.to_owned().unwrap(),
-        BigNum::from_u32(1).unwrap()]);
+        &[pi.t_x.to_owned()?,
+        cc.to_owned()?,
+        BigNum::from_u32(1)?])?;
     
 
-    A_xrel.drain(multi);
-    A_yrel.drain(multi);
-    A_zrel.drain(multi);
-    A_4_1rel.drain(multi);
-    A_4_2rel.drain(multi);
+    A_xrel.drain(multi)?;
+    A_yrel.drain(multi)?;
+    A_zrel.drain(multi)?;
+    A_4_1rel.drain(multi)?;
+    A_4_2rel.drain(multi)?;
+
+
+    Ok(true)
 
+}
 
-    true
 
+/// A multiplication proof of a batch, with the commitments it is about and
+/// the transcript it was made with.
+pub struct MultBatchItem<'a> {
+    pub Cx: EcPoint,
+    pub Cy: EcPoint,
+    pub Cz: EcPoint,
+    pub pi: &'a MultProof<'a>,
+    pub transcript: Transcript,
 }
 
+/**
+ * Verifies many multiplication proofs under the same params with one
+ * multi-scalar multiplication, and bisects the batch if it fails.
+ * Returns the indices of the proofs that do not verify, empty if all do.
+ *
+ * @param params
+ * @param items proofs with their commitments and transcripts
+ */
+pub fn batch_verify_mult<'a>(
+    params: &'a PedersenParams<'a>,
+    items: Vec<MultBatchItem<'a>>,
+) -> Result<Vec<usize>> {
+
+    let mut batch = Batch::new(vec![params.c]);
+    batch.add_known(0, params.g.to_owned(params.c)?)?;
+    batch.add_known(0, params.h.to_owned(params.c)?)?;
+
+    for item in items {
+        let MultBatchItem { Cx, Cy, Cz, pi, mut transcript } = item;
+        batch.push(|multi| aggregate_mult(params, Cx, Cy, Cz, pi, &mut multi[0], &mut transcript))?;
+    }
+
+    batch.invalid()
+}
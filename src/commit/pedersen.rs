@@ -1,8 +1,15 @@
-use openssl::ec::{EcGroupRef, EcPoint};
-use openssl::bn::{BigNum, BigNumContext, MsbOption};
-use openssl::error::ErrorStack;
+use std::sync::Arc;
+
+use openssl::ec::{EcGroupRef, EcPoint, EcPointRef, PointConversionForm};
+use openssl::bn::{BigNum, BigNumContext, BigNumRef};
+use openssl::nid::Nid;
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
 //use std::convert::TryFrom;
 
+use crate::curves::hash_to_curve::{hash_to_curve, suite_id};
+use crate::error::{Result, ZkError};
+
 pub struct Commitment<'a> {
     pub group: &'a EcGroupRef,
     pub p: EcPoint,
@@ -20,90 +27,90 @@ impl<'a> Commitment<'a> {
         Commitment{ group, p, r }
     }
     
-    pub fn to_owned(&self) -> Self {
-        Commitment{
-            group: &self.group,
-            p: self.p.to_owned(&self.group).unwrap(),
-            r: self.r.to_owned().unwrap()
-        }
+    pub fn to_owned(&self) -> Result<Self> {
+        Ok(Commitment{
+            group: self.group,
+            p: self.p.to_owned(self.group)?,
+            r: self.r.to_owned()?
+        })
     }
 
     /// Takes a commitment c and adds to self
-    pub fn add(&self, c: &Self) -> Self {
+    pub fn add(&self, c: &Self) -> Result<Self> {
 
-        let mut ctx = BigNumContext::new().unwrap();
+        let mut ctx = BigNumContext::new()?;
         
         // Take group order
-        let mut order_curve = BigNum::new().unwrap();
-        self.group.order(&mut order_curve, &mut ctx).unwrap();
+        let mut order_curve = BigNum::new()?;
+        self.group.order(&mut order_curve, &mut ctx)?;
 
         // Update p: sum_p = self.p + c.p
-        let mut sum_p = EcPoint::new(&self.group).unwrap();
-        sum_p.add(&self.group, &self.p, &c.p, &mut ctx).unwrap();
+        let mut sum_p = EcPoint::new(self.group)?;
+        sum_p.add(self.group, &self.p, &c.p, &mut ctx)?;
         
         
         // Update r: sum_r = self.r + c.r
-        let mut sum_r = BigNum::new().unwrap();
-        sum_r.mod_add(&self.r, &c.r, &order_curve, &mut ctx).unwrap();
+        let mut sum_r = BigNum::new()?;
+        sum_r.mod_add(&self.r, &c.r, &order_curve, &mut ctx)?;
 
 
-        Commitment{ 
-            group: &self.group, 
+        Ok(Commitment{ 
+            group: self.group, 
             p: sum_p, 
-            r: sum_r }
+            r: sum_r })
     }
 
 
     /// Takes a commitment c and subs to self
-    pub fn sub(&self, c: &Self) -> Self {
+    pub fn sub(&self, c: &Self) -> Result<Self> {
 
-        let mut ctx = BigNumContext::new().unwrap();
+        let mut ctx = BigNumContext::new()?;
 
         // Take group order
-        let mut order_curve = BigNum::new().unwrap();
-        self.group.order(&mut order_curve, &mut ctx).unwrap();
+        let mut order_curve = BigNum::new()?;
+        self.group.order(&mut order_curve, &mut ctx)?;
 
         // Update p: sum_p = self.p - c.p
-        let mut sub_p = EcPoint::new(&self.group).unwrap();
+        let mut sub_p = EcPoint::new(self.group)?;
         // // invert c.p
-        let inv = BigNum::from_dec_str("-1").unwrap(); 
-        let mut neg_c_p = EcPoint::new(&self.group).unwrap();
-        neg_c_p.mul(&self.group, &c.p, &inv, &mut ctx).unwrap();
+        let inv = BigNum::from_dec_str("-1")?; 
+        let mut neg_c_p = EcPoint::new(self.group)?;
+        neg_c_p.mul(self.group, &c.p, &inv, &ctx)?;
         // // add -c.p to it
-        sub_p.add(&self.group, &self.p, &neg_c_p, &mut ctx).unwrap();
+        sub_p.add(self.group, &self.p, &neg_c_p, &mut ctx)?;
         
         // Update r: sum_r = self.r - c.r
-        let mut sub_r = BigNum::new().unwrap();
-        sub_r.mod_sub(&self.r, &c.r, &order_curve, &mut ctx).unwrap();
+        let mut sub_r = BigNum::new()?;
+        sub_r.mod_sub(&self.r, &c.r, &order_curve, &mut ctx)?;
 
-        Commitment{ 
-            group: &self.group, 
+        Ok(Commitment{ 
+            group: self.group, 
             p: sub_p, 
-            r: sub_r }
+            r: sub_r })
     }
 
 
     /// Takes an integer k and multiplies the self by k
-    pub fn mul(&mut self, k: &BigNum) -> Self {
+    pub fn mul(&mut self, k: &BigNum) -> Result<Self> {
 
-        let mut ctx = BigNumContext::new().unwrap();
+        let mut ctx = BigNumContext::new()?;
 
         // Take group order
-        let mut order_curve = BigNum::new().unwrap();
-        self.group.order(&mut order_curve, &mut ctx).unwrap();
+        let mut order_curve = BigNum::new()?;
+        self.group.order(&mut order_curve, &mut ctx)?;
 
         // Update p: mul_p = k * self.p
-        let mut mul_p = EcPoint::new(&self.group).unwrap();
-        mul_p.mul(&self.group, &self.p, k, &mut ctx).unwrap();
+        let mut mul_p = EcPoint::new(self.group)?;
+        mul_p.mul(self.group, &self.p, k, &ctx)?;
 
         // Update r: mul_r = k * self.r 
-        let mut mul_r = BigNum::new().unwrap();
-        mul_r.mod_mul(&self.r, k, &order_curve, &mut ctx).unwrap();
+        let mut mul_r = BigNum::new()?;
+        mul_r.mod_mul(&self.r, k, &order_curve, &mut ctx)?;
 
-        Commitment{ 
-            group: &self.group, 
+        Ok(Commitment{ 
+            group: self.group, 
             p: mul_p, 
-            r: mul_r }
+            r: mul_r })
 
     }
 
@@ -115,6 +122,10 @@ pub struct PedersenParams<'a> {
     pub c: &'a EcGroupRef,
     pub g: EcPoint,
     pub h: EcPoint,
+    /// Precomputed multiples of g, see with_precomputation
+    pub table_g: Option<Arc<FixedBaseTable>>,
+    /// Precomputed multiples of h, see with_precomputation
+    pub table_h: Option<Arc<FixedBaseTable>>,
 }
 
 
@@ -122,45 +133,245 @@ impl<'a> PedersenParams<'a> {
 
     pub fn new(
         c: &'a EcGroupRef,
-        g: EcPoint, // Note:: currently g is not being used because it is taken 
-                    //        the generator of curve c.
+        g: EcPoint, // any base, e.g. the ECDSA R in the signature proof
         h: EcPoint,
     ) -> Self {
-        PedersenParams{ c, g, h }
+        PedersenParams{ c, g, h, table_g: None, table_h: None }
+    }
+
+    /// Precomputes the tables of g and h that are missing. On the 256-bit and
+    /// 384-bit curves of benches/pedersen.rs this makes mul_g and mul_h 3 to 4
+    /// times faster but commit only 1.6 to 1.8 times: it takes one table
+    /// multiplication per base, while without tables mul_full shares the
+    /// doublings of g and h. The tables are shared, so params with the same g
+    /// or h can reuse them.
+    ///
+    /// On P-256 this is a no-op: OpenSSL multiplies faster there than the
+    /// tables do, so table_g and table_h stay None and commit uses mul_full.
+    pub fn with_precomputation(mut self) -> Result<Self> {
+        if self.c.curve_name() == Some(Nid::X9_62_PRIME256V1) {
+            return Ok(self);
+        }
+        if self.table_g.is_none() {
+            self.table_g = Some(Arc::new(FixedBaseTable::new(self.c, &self.g, FIXED_BASE_WINDOW)?));
+        }
+        if self.table_h.is_none() {
+            self.table_h = Some(Arc::new(FixedBaseTable::new(self.c, &self.h, FIXED_BASE_WINDOW)?));
+        }
+        Ok(self)
     }
 
+    /// k * g, from the table of g if there is one.
+    pub fn mul_g(&self, k: &BigNumRef) -> Result<EcPoint> {
+        fixed_base_mul(self.c, &self.g, self.table_g.as_deref(), k)
+    }
+
+    /// k * h, from the table of h if there is one.
+    pub fn mul_h(&self, k: &BigNumRef) -> Result<EcPoint> {
+        fixed_base_mul(self.c, &self.h, self.table_h.as_deref(), k)
+    }
+
+    #[allow(clippy::should_implement_trait)]
     pub fn eq(&self, o: &PedersenParams) -> bool {
 
-        let mut ctx = BigNumContext::new().unwrap();
+        let Ok(mut ctx) = BigNumContext::new() else { return false };
         
-        self.g.eq(self.c, &o.g, &mut ctx).unwrap() &&
-        self.h.eq(self.c, &o.h, &mut ctx).unwrap() 
+        self.g.eq(self.c, &o.g, &mut ctx).unwrap_or(false) &&
+        self.h.eq(self.c, &o.h, &mut ctx).unwrap_or(false)
     }
 
-    pub fn commit(&self, input: &BigNum) -> Commitment {
+    pub fn commit(&self, input: &BigNum) -> Result<Commitment<'_>> {
+        self.commit_with_rng(input, &mut OsRng)
+    }
+
+    /// Commits to input with randomness drawn from rng.
+    pub fn commit_with_rng(&self, input: &BigNum, rng: &mut (impl RngCore + CryptoRng)) -> Result<Commitment<'_>> {
 
-        let mut ctx = BigNumContext::new().unwrap();
+        let mut ctx = BigNumContext::new()?;
 
         // Random element
-        let mut r = BigNum::new().unwrap();
-        let mut n_order = BigNum::new().unwrap();
-        let _ = &self.c.order(&mut n_order, &mut ctx).unwrap();
-        // Generates a 256-bit odd random number
-        let nbits = n_order.num_bits();
-
-        r.rand(nbits, MsbOption::MAYBE_ZERO, true).unwrap();
-
-        // Computes g * input + h * r, storing the result in self.
-        let mut p = EcPoint::new(&self.c).unwrap();
-        p.mul_full(
-            &self.c, 
-            input,
-            &self.h,
-            &r,
-            &mut ctx).unwrap();
-        
-        Commitment{group: &self.c, p, r}
+        let mut n_order = BigNum::new()?;
+        let _ = &self.c.order(&mut n_order, &mut ctx)?;
+        let r = generate_random_with_rng(&n_order, rng)?;
+
+        self.commit_with_randomness(input, &r)
     }
+
+    /// Commits to input with the caller's blinding r. Both are reduced mod the
+    /// order, so the commitment only depends on their residues.
+    pub fn commit_with_randomness(&self, input: &BigNum, r: &BigNum) -> Result<Commitment<'_>> {
+
+        let mut ctx = BigNumContext::new()?;
+
+        let mut n_order = BigNum::new()?;
+        self.c.order(&mut n_order, &mut ctx)?;
+        let mut value = BigNum::new()?;
+        value.nnmod(input, &n_order, &mut ctx)?;
+        let mut rr = BigNum::new()?;
+        rr.nnmod(r, &n_order, &mut ctx)?;
+
+        // Computes g * input + h * r
+        let mut p = EcPoint::new(self.c)?;
+        if self.table_g.is_some() && self.table_h.is_some() {
+            let g_value = self.mul_g(&value)?;
+            let h_r = self.mul_h(&rr)?;
+            p.add(self.c, &g_value, &h_r, &mut ctx)?;
+        } else if self.g.eq(self.c, self.c.generator(), &mut ctx)? {
+            // g is the generator, which OpenSSL multiplies faster
+            p.mul_full(
+                self.c, 
+                &value,
+                &self.h,
+                &rr,
+                &mut ctx)?;
+        } else {
+            let mut g_value = EcPoint::new(self.c)?;
+            g_value.mul(self.c, &self.g, &value, &ctx)?;
+            let mut h_r = EcPoint::new(self.c)?;
+            h_r.mul(self.c, &self.h, &rr, &ctx)?;
+            p.add(self.c, &g_value, &h_r, &mut ctx)?;
+        }
+
+        Ok(Commitment{group: self.c, p, r: rr})
+    }
+}
+
+/// Window of the tables of with_precomputation, in bits.
+pub const FIXED_BASE_WINDOW: usize = 6;
+
+/// Multiples of a base B for every window j and digit d of w bits, so that
+/// k B is a sum of one entry per window of k, without doublings.
+///
+/// Only the table lookup in mul is branch-free: every window reads all the
+/// entries of its row with a masked copy and adds the selected one. Entry d of
+/// window j is (d + 1) 2^(w j) B, which is never the identity, so a zero digit
+/// is added like any other; the public offset sum_j 2^(w j) B is subtracted once
+/// at the start. The reduction of k, EcPoint::from_bytes and EcPoint::add are
+/// OpenSSL's variable-time routines, so mul is not constant time.
+pub struct FixedBaseTable {
+    pub window: usize,
+    // rows[j] holds the uncompressed encodings of (d + 1) 2^(w j) B, d = 0..2^w
+    rows: Vec<Vec<u8>>,
+    entry_len: usize,
+    // -sum_j 2^(w j) B
+    offset: EcPoint,
+    order_curve: BigNum,
+    // bytes of the scalars, enough for every window
+    scalar_len: usize,
+}
+
+impl FixedBaseTable {
+
+    /**
+     * Table of base for scalars mod the order of group.
+     * @param group curve
+     * @param base point of the prime-order subgroup, not the identity
+     * @param window bits per window, between 1 and 16
+     */
+    pub fn new(group: &EcGroupRef, base: &EcPointRef, window: usize) -> Result<Self> {
+        if !(1..=16).contains(&window) {
+            return Err(ZkError::InvalidInput("window must be between 1 and 16 bits"));
+        }
+        if base.is_infinity(group) {
+            return Err(ZkError::InvalidInput("base is the identity"));
+        }
+
+        let mut ctx = BigNumContext::new()?;
+        let mut order_curve = BigNum::new()?;
+        group.order(&mut order_curve, &mut ctx)?;
+
+        let windows = (order_curve.num_bits() as usize).div_ceil(window);
+        let entry_len = base.to_bytes(group, PointConversionForm::UNCOMPRESSED, &mut ctx)?.len();
+        let mut rows = Vec::with_capacity(windows);
+        let mut offset = EcPoint::new(group)?;
+        // 2^(w j) B
+        let mut window_base = base.to_owned(group)?;
+        for _ in 0..windows {
+            let mut sum = EcPoint::new(group)?;
+            sum.add(group, &offset, &window_base, &mut ctx)?;
+            offset = sum;
+
+            let mut row = Vec::with_capacity(entry_len << window);
+            let mut entry = window_base.to_owned(group)?;
+            for d in 0..1usize << window {
+                let bytes = entry.to_bytes(group, PointConversionForm::UNCOMPRESSED, &mut ctx)?;
+                if bytes.len() != entry_len {
+                    return Err(ZkError::InvalidInput("base is not of prime order"));
+                }
+                row.extend_from_slice(&bytes);
+                if d + 1 < 1 << window {
+                    let mut next = EcPoint::new(group)?;
+                    next.add(group, &entry, &window_base, &mut ctx)?;
+                    entry = next;
+                }
+            }
+
+            // 2^w 2^(w j) B is the last entry of the row
+            window_base = entry;
+            rows.push(row);
+        }
+        offset.invert(group, &ctx)?;
+
+        let scalar_len = (windows * window).div_ceil(8);
+        Ok(FixedBaseTable { window, rows, entry_len, offset, order_curve, scalar_len })
+    }
+
+    /// k * base, for any k (reduced mod the order), with a branch-free table lookup.
+    pub fn mul(&self, group: &EcGroupRef, k: &BigNumRef) -> Result<EcPoint> {
+        let mut ctx = BigNumContext::new()?;
+        let mut kk = BigNum::new()?;
+        kk.nnmod(k, &self.order_curve, &mut ctx)?;
+        let bytes = kk.to_vec_padded(self.scalar_len as i32)?;
+
+        let mut acc = self.offset.to_owned(group)?;
+        let mut entry = vec![0u8; self.entry_len];
+        for (j, row) in self.rows.iter().enumerate() {
+            let d = window_digit(&bytes, j * self.window, self.window);
+
+            entry.fill(0);
+            for (e, candidate) in row.chunks_exact(self.entry_len).enumerate() {
+                let mask = ct_eq_mask(e, d);
+                for (b, c) in entry.iter_mut().zip(candidate) {
+                    *b |= c & mask;
+                }
+            }
+
+            let pt = EcPoint::from_bytes(group, &entry, &mut ctx)?;
+            let mut sum = EcPoint::new(group)?;
+            sum.add(group, &acc, &pt, &mut ctx)?;
+            acc = sum;
+        }
+        Ok(acc)
+    }
+}
+
+// The w bits of the big-endian bytes starting at bit offset; only the
+// positions read depend on offset and w
+fn window_digit(bytes: &[u8], offset: usize, w: usize) -> usize {
+    let mut d = 0;
+    for i in 0..w {
+        let bit = offset + i;
+        let byte = bytes[bytes.len() - 1 - bit / 8];
+        d |= (((byte >> (bit % 8)) & 1) as usize) << i;
+    }
+    d
+}
+
+// 0xff if a == b and 0 otherwise, without branching
+fn ct_eq_mask(a: usize, b: usize) -> u8 {
+    let x = std::hint::black_box((a ^ b) as u64);
+    let nonzero = (x | x.wrapping_neg()) >> 63;
+    (nonzero as u8).wrapping_sub(1)
+}
+
+fn fixed_base_mul(group: &EcGroupRef, base: &EcPointRef, table: Option<&FixedBaseTable>, k: &BigNumRef) -> Result<EcPoint> {
+    if let Some(table) = table {
+        return table.mul(group, k);
+    }
+    let ctx = BigNumContext::new()?;
+    let mut p = EcPoint::new(group)?;
+    p.mul(group, base, k, &ctx)?;
+    Ok(p)
 }
 
 /*
@@ -168,7 +379,8 @@ impl<'a> PedersenParams<'a> {
         UTIL::groups
 
 */
-pub fn generate_random(order_curve: &BigNum) -> Result< BigNum, ErrorStack > {
+/// Uniform scalar in [1, order_curve).
+pub fn generate_random(order_curve: &BigNum) -> Result<BigNum> {
     /* ------ old version --------
     let mut big = BigNum::new().unwrap();
  
@@ -176,30 +388,80 @@ pub fn generate_random(order_curve: &BigNum) -> Result< BigNum, ErrorStack > {
     big.rand(128, MsbOption::MAYBE_ZERO, true);
     */
 
-    let mut big_rnd = BigNum::new().unwrap();
-    order_curve.rand_range(&mut big_rnd);
-
-    Ok(big_rnd)
+    generate_random_with_rng(order_curve, &mut OsRng)
  }
 
+/// Uniform scalar in [1, order_curve) drawn from rng, by rejection sampling.
+pub fn generate_random_with_rng(order_curve: &BigNum, rng: &mut (impl RngCore + CryptoRng)) -> Result<BigNum> {
 
+    let nbits = order_curve.num_bits() as usize;
+    let mut bytes = vec![0u8; nbits.div_ceil(8)];
+    // Clears the bits of the first byte above the bit length of the order
+    let mask = 0xffu8 >> (bytes.len() * 8 - nbits);
 
-pub fn generate_pedersen_params(c: &EcGroupRef) -> PedersenParams {
-    
-    let mut ctx = BigNumContext::new().unwrap();
-    
-    let g = c.generator();
+    loop {
+        rng.fill_bytes(&mut bytes);
+        bytes[0] &= mask;
+        let big_rnd = BigNum::from_slice(&bytes)?;
+        if big_rnd.num_bits() > 0 && big_rnd < *order_curve {
+            return Ok(big_rnd)
+        }
+    }
+}
 
-    let mut order_curve = BigNum::new().unwrap();
-    c.order(&mut order_curve, &mut ctx).unwrap();
-    let r = generate_random(&order_curve).unwrap();
 
-    
-    let mut h = EcPoint::new(&c).unwrap();
-    h.mul(&c, &g, &r, &mut ctx).unwrap();
 
-    let g_deref = g.to_owned(c).unwrap();
+/// Public seed of the default Pedersen parameters.
+pub const PEDERSEN_SEED: &[u8] = b"zkattest/pedersen-h-v1";
+
+/// Prefix of the domain separation tag under which h is hashed to the curve,
+/// followed by the hash-to-curve suite ID of the curve.
+pub const PEDERSEN_DST: &[u8] = b"zkattest-V01-CS01-pedersen-h-";
+
 
-    PedersenParams::new(&c, g_deref, h)
+/**
+ * Pedersen parameters g = generator of c and h derived from PEDERSEN_SEED,
+ * so that nobody knows the discrete log of h with respect to g.
+ * @param c curve
+ */
+pub fn generate_pedersen_params(c: &EcGroupRef) -> Result<PedersenParams<'_>> {
+    generate_pedersen_params_from_seed(c, PEDERSEN_SEED)
+}
+
+/**
+ * Pedersen parameters g = generator of c and
+ * h = hash_to_curve(c, PEDERSEN_DST || suite_id(c), seed), so that anybody
+ * can check that h was generated honestly with verify_pedersen_params.
+ * @param c curve
+ * @param seed public seed
+ */
+pub fn generate_pedersen_params_from_seed<'a>(c: &'a EcGroupRef, seed: &[u8]) -> Result<PedersenParams<'a>> {
+    let g = c.generator().to_owned(c)?;
+    let h = derive_h(c, seed)?;
+    Ok(PedersenParams::new(c, g, h))
+}
+
+/**
+ * Checks that params were generated by generate_pedersen_params_from_seed
+ * with seed: g is the generator of the curve and h is derived from seed.
+ * @param params Pedersen parameters
+ * @param seed public seed they were generated from
+ */
+pub fn verify_pedersen_params(params: &PedersenParams, seed: &[u8]) -> Result<bool> {
+    let mut ctx = BigNumContext::new()?;
+    let h = derive_h(params.c, seed)?;
+    Ok(params.g.eq(params.c, params.c.generator(), &mut ctx)? &&
+       params.h.eq(params.c, &h, &mut ctx)?)
+}
+
+// h = hash_to_curve(c, PEDERSEN_DST || suite_id(c), seed), with the RFC 9380
+// suite of c, so that curves sharing a hash get unrelated h
+fn derive_h(c: &EcGroupRef, seed: &[u8]) -> Result<EcPoint> {
+    let dst = [PEDERSEN_DST, suite_id(c)?.as_bytes()].concat();
+    let h = hash_to_curve(c, &dst, seed)?;
+    if h.is_infinity(c) {
+        return Err(ZkError::InvalidCurve("h is at infinity"));
+    }
+    Ok(h)
 }
 
@@ -1 +1,7 @@
-pub mod multimult;
\ No newline at end of file
+pub mod hash_to_curve;
+pub mod instances;
+pub mod multimult;
+
+pub use self::hash_to_curve::{hash_to_curve, hash_to_field, suite_id, expand_message_xmd, find_z_sswu, find_z_svdw, TOM256_Z, TOM384_Z, TOM521_Z, SECQ256K1_Z};
+pub use self::instances::{CurveParams, CurvePair, TOM256, TOM384, TOM521, SECQ256K1, from_params, validate, is_companion};
+pub use self::instances::{p256, p384, p521, tom256, tom384, tom521, secp256k1, secq256k1};
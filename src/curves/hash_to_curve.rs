@@ -0,0 +1,747 @@
+use openssl::ec::{EcGroupRef, EcPoint};
+use openssl::bn::{BigNum, BigNumContext, BigNumRef};
+use openssl::hash::{hash, MessageDigest};
+use openssl::nid::Nid;
+
+use crate::curves::instances::{CurveParams, TOM256, TOM384, TOM521, SECQ256K1};
+use crate::error::{Result, ZkError};
+
+
+/*  Hash to curve following RFC 9380: expand_message_xmd, hash_to_field and the
+    random-oracle construction
+
+        hash_to_curve(msg) = clear_cofactor(map(u0) + map(u1)),  (u0, u1) = hash_to_field(msg, 2)
+
+    Each supported curve has a fixed suite:
+
+        P-256       P256_XMD:SHA-256_SSWU_RO_       (RFC 9380, Section 8.2)
+        P-384       P384_XMD:SHA-384_SSWU_RO_       (RFC 9380, Section 8.3)
+        P-521       P521_XMD:SHA-512_SSWU_RO_       (RFC 9380, Section 8.4)
+        secp256k1   secp256k1_XMD:SHA-256_SSWU_RO_  (RFC 9380, Section 8.7, 3-isogeny)
+
+    The companion curves are not covered by the RFC. They use the hash and the
+    security level k of their NIST curve, with the simplified SWU map for the Tom
+    curves and the Shallue-van de Woestijne map for secq256k1 (a = 0), and Z chosen
+    by Appendix H of RFC 9380:
+
+        Tom256      tom256_XMD:SHA-256_SSWU_RO_
+        Tom384      tom384_XMD:SHA-384_SSWU_RO_
+        Tom521      tom521_XMD:SHA-512_SSWU_RO_
+        secq256k1   secq256k1_XMD:SHA-256_SVDW_RO_
+
+    Other curves are rejected. */
+
+
+// Map from GF(p) to the curve
+enum Map {
+    // simplified SWU on the curve itself
+    Sswu,
+    // simplified SWU on the 3-isogenous curve y^2 = x^3 + A' x + B' of secp256k1
+    Secp256k1Isogeny,
+    // Shallue-van de Woestijne
+    Svdw,
+}
+
+struct Suite {
+    // suite ID as in RFC 9380, Section 8.10
+    id: &'static str,
+    hash: fn() -> MessageDigest,
+    // security level of hash_to_field in bits
+    k: usize,
+    // Z of the map, as a small integer
+    z: i32,
+    map: Map,
+}
+
+const P256_SUITE: Suite = Suite { id: "P256_XMD:SHA-256_SSWU_RO_", hash: MessageDigest::sha256, k: 128, z: -10, map: Map::Sswu };
+const P384_SUITE: Suite = Suite { id: "P384_XMD:SHA-384_SSWU_RO_", hash: MessageDigest::sha384, k: 192, z: -12, map: Map::Sswu };
+const P521_SUITE: Suite = Suite { id: "P521_XMD:SHA-512_SSWU_RO_", hash: MessageDigest::sha512, k: 256, z: -4, map: Map::Sswu };
+const SECP256K1_SUITE: Suite = Suite { id: "secp256k1_XMD:SHA-256_SSWU_RO_", hash: MessageDigest::sha256, k: 128, z: -11, map: Map::Secp256k1Isogeny };
+const TOM256_SUITE: Suite = Suite { id: "tom256_XMD:SHA-256_SSWU_RO_", hash: MessageDigest::sha256, k: 128, z: TOM256_Z, map: Map::Sswu };
+const TOM384_SUITE: Suite = Suite { id: "tom384_XMD:SHA-384_SSWU_RO_", hash: MessageDigest::sha384, k: 192, z: TOM384_Z, map: Map::Sswu };
+const TOM521_SUITE: Suite = Suite { id: "tom521_XMD:SHA-512_SSWU_RO_", hash: MessageDigest::sha512, k: 256, z: TOM521_Z, map: Map::Sswu };
+const SECQ256K1_SUITE: Suite = Suite { id: "secq256k1_XMD:SHA-256_SVDW_RO_", hash: MessageDigest::sha256, k: 128, z: SECQ256K1_Z, map: Map::Svdw };
+
+/// Z of the simplified SWU map on Tom256, as given by find_z_sswu.
+pub const TOM256_Z: i32 = -2;
+/// Z of the simplified SWU map on Tom384, as given by find_z_sswu.
+pub const TOM384_Z: i32 = -2;
+/// Z of the simplified SWU map on Tom521, as given by find_z_sswu.
+pub const TOM521_Z: i32 = 6;
+/// Z of the Shallue-van de Woestijne map on secq256k1, as given by find_z_svdw.
+pub const SECQ256K1_Z: i32 = 1;
+
+// 3-isogenous curve of secp256k1 and the isogeny map (RFC 9380, Appendix E.1)
+const SECP256K1_ISO_A: &str = "3f8731abdd661adca08a5558f0f5d272e953d363cb6f0e5d405447c01a444533";
+const SECP256K1_ISO_B: &str = "6eb";
+const SECP256K1_ISO_X_NUM: [&str; 4] = [
+    "8e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38daaaaa8c7",
+    "07d3d4c80bc321d5b9f315cea7fd44c5d595d2fc0bf63b92dfff1044f17c6581",
+    "534c328d23f234e6e2a413deca25caece4506144037c40314ecbd0b53d9dd262",
+    "8e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38daaaaa88c",
+];
+const SECP256K1_ISO_X_DEN: [&str; 3] = [
+    "d35771193d94918a9ca34ccbb7b640dd86cd409542f8487d9fe6b745781eb49b",
+    "edadc6f64383dc1df7c4b2d51b54225406d36b641f5e41bbc52a56612a8c6d14",
+    "1",
+];
+const SECP256K1_ISO_Y_NUM: [&str; 4] = [
+    "4bda12f684bda12f684bda12f684bda12f684bda12f684bda12f684b8e38e23c",
+    "c75e0c32d5cb7c0fa9d0a54b12a0a6d5647ab046d686da6fdffc90fc201d71a3",
+    "29a6194691f91a73715209ef6512e576722830a201be2018a765e85a9ecee931",
+    "2f684bda12f684bda12f684bda12f684bda12f684bda12f684bda12f38e38d84",
+];
+const SECP256K1_ISO_Y_DEN: [&str; 4] = [
+    "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffff93b",
+    "7a06534bb8bdb49fd5e9e6632722c2989467c1bfc8e8d978dfb425d2685c2573",
+    "6484aa716545ca2cf3a70c3fa8fe337e0a3d21162f0d6299a7bf8192bfd2a76f",
+    "1",
+];
+
+
+// The suite of group, identified by its name or, for the companion curves, its parameters
+fn suite(group: &EcGroupRef, ctx: &mut BigNumContext) -> Result<&'static Suite> {
+
+    match group.curve_name() {
+        Some(Nid::X9_62_PRIME256V1) => return Ok(&P256_SUITE),
+        Some(Nid::SECP384R1) => return Ok(&P384_SUITE),
+        Some(Nid::SECP521R1) => return Ok(&P521_SUITE),
+        Some(Nid::SECP256K1) => return Ok(&SECP256K1_SUITE),
+        _ => {}
+    }
+
+    let (p, a, b) = components(group, ctx)?;
+    let is = |params: &CurveParams| -> Result<bool> {
+        Ok(p == BigNum::from_hex_str(params.p)? &&
+           a == BigNum::from_hex_str(params.a)? &&
+           b == BigNum::from_hex_str(params.b)?)
+    };
+
+    if is(&TOM256)? {
+        Ok(&TOM256_SUITE)
+    } else if is(&TOM384)? {
+        Ok(&TOM384_SUITE)
+    } else if is(&TOM521)? {
+        Ok(&TOM521_SUITE)
+    } else if is(&SECQ256K1)? {
+        Ok(&SECQ256K1_SUITE)
+    } else {
+        Err(ZkError::InvalidCurve("no hash-to-curve suite for this curve"))
+    }
+}
+
+
+/**
+ * ID of the hash-to-curve suite of group, e.g. P256_XMD:SHA-256_SSWU_RO_,
+ * to make domain separation tags unique per curve.
+ * @param group supported curve
+ */
+pub fn suite_id(group: &EcGroupRef) -> Result<&'static str> {
+    let mut ctx = BigNumContext::new()?;
+    Ok(suite(group, &mut ctx)?.id)
+}
+
+
+/*
+
+        UTIL::expand
+
+*/
+
+/**
+ * expand_message_xmd (RFC 9380, Section 5.3.1)
+ *
+ * @param hash_id hash function H
+ * @param msg message
+ * @param dst domain separation tag, at most 255 bytes
+ * @param len_in_bytes length of the output, at most 255 times the output size of H
+ */
+pub fn expand_message_xmd(hash_id: MessageDigest, msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Result<Vec<u8>> {
+
+    // output size and input block size of H
+    let b_in_bytes = hash_id.size();
+    let s_in_bytes = hash_id.block_size();
+
+    let ell = len_in_bytes.div_ceil(b_in_bytes);
+    if ell > 255 || len_in_bytes > 65535 {
+        return Err(ZkError::InvalidInput("requested too many bytes"));
+    }
+    if dst.len() > 255 {
+        return Err(ZkError::InvalidInput("domain separation tag too long"));
+    }
+
+    // DST_prime = DST || I2OSP(len(DST), 1)
+    let mut dst_prime = dst.to_vec();
+    dst_prime.push(dst.len() as u8);
+
+    // msg_prime = Z_pad || msg || I2OSP(len_in_bytes, 2) || I2OSP(0, 1) || DST_prime
+    let mut msg_prime = vec![0u8; s_in_bytes];
+    msg_prime.extend_from_slice(msg);
+    msg_prime.extend_from_slice(&(len_in_bytes as u16).to_be_bytes());
+    msg_prime.push(0);
+    msg_prime.extend_from_slice(&dst_prime);
+
+    let b_0 = hash(hash_id, &msg_prime)?;
+
+    let mut uniform_bytes = Vec::with_capacity(ell * b_in_bytes);
+    let mut b_i = vec![0u8; b_in_bytes];
+    for i in 1..=ell {
+        // b_i = H(strxor(b_0, b_(i-1)) || I2OSP(i, 1) || DST_prime), with b_0 xor 0 = b_0 for b_1
+        let mut block: Vec<u8> = b_0.iter().zip(b_i.iter()).map(|(x, y)| x ^ y).collect();
+        block.push(i as u8);
+        block.extend_from_slice(&dst_prime);
+        b_i = hash(hash_id, &block)?.to_vec();
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+
+    uniform_bytes.truncate(len_in_bytes);
+    Ok(uniform_bytes)
+}
+
+
+/**
+ * hash_to_field (RFC 9380, Section 5.2): count elements of GF(p) of group,
+ * with the hash and security level of the suite of group
+ *
+ * @param group supported curve
+ * @param dst domain separation tag
+ * @param msg message
+ * @param count number of field elements
+ */
+pub fn hash_to_field(group: &EcGroupRef, dst: &[u8], msg: &[u8], count: usize) -> Result<Vec<BigNum>> {
+
+    let mut ctx = BigNumContext::new()?;
+    let suite = suite(group, &mut ctx)?;
+    let (p, _, _) = components(group, &mut ctx)?;
+
+    // L = ceil((ceil(log2(p)) + k) / 8)
+    let L = (p.num_bits() as usize + suite.k).div_ceil(8);
+    let uniform_bytes = expand_message_xmd((suite.hash)(), msg, dst, count * L)?;
+
+    uniform_bytes.chunks(L).map(|tv| {
+        let mut e = BigNum::new()?;
+        e.nnmod(BigNum::from_slice(tv)?.as_ref(), &p, &mut ctx)?;
+        Ok(e)
+    }).collect()
+}
+
+
+/*
+
+        UTIL::field
+
+*/
+
+fn components(group: &EcGroupRef, ctx: &mut BigNumContext) -> Result<(BigNum, BigNum, BigNum)> {
+    let mut p = BigNum::new()?;
+    let mut a = BigNum::new()?;
+    let mut b = BigNum::new()?;
+    group.components_gfp(&mut p, &mut a, &mut b, ctx)?;
+    Ok((p, a, b))
+}
+
+// g(x) = x^3 + a x + b
+fn curve_rhs(x: &BigNumRef, a: &BigNumRef, b: &BigNumRef, p: &BigNumRef, ctx: &mut BigNumContext) -> Result<BigNum> {
+    let mut x2 = BigNum::new()?;
+    x2.mod_sqr(x, p, ctx)?;
+    let mut x2_plus_a = BigNum::new()?;
+    x2_plus_a.mod_add(&x2, a, p, ctx)?;
+    let mut x3_plus_ax = BigNum::new()?;
+    x3_plus_ax.mod_mul(&x2_plus_a, x, p, ctx)?;
+    let mut gx = BigNum::new()?;
+    gx.mod_add(&x3_plus_ax, b, p, ctx)?;
+    Ok(gx)
+}
+
+// Euler's criterion, 0 is a square
+fn is_square(x: &BigNumRef, p: &BigNumRef, ctx: &mut BigNumContext) -> Result<bool> {
+    let mut p_minus_1 = BigNum::new()?;
+    p_minus_1.checked_sub(p, BigNum::from_u32(1)?.as_ref())?;
+    let mut e = BigNum::new()?;
+    e.rshift1(&p_minus_1)?;
+    let mut r = BigNum::new()?;
+    r.mod_exp(x, &e, p, ctx)?;
+    Ok(r.num_bits() <= 1)
+}
+
+fn inv0(x: &BigNumRef, p: &BigNumRef, ctx: &mut BigNumContext) -> Result<BigNum> {
+    let mut r = BigNum::new()?;
+    if x.num_bits() > 0 {
+        r.mod_inverse(x, p, ctx)?;
+    }
+    Ok(r)
+}
+
+fn sgn0(x: &BigNumRef) -> bool {
+    x.is_bit_set(0)
+}
+
+fn neg(x: &BigNumRef, p: &BigNumRef, ctx: &mut BigNumContext) -> Result<BigNum> {
+    let mut r = BigNum::new()?;
+    r.mod_sub(BigNum::new()?.as_ref(), x, p, ctx)?;
+    Ok(r)
+}
+
+// z mod p for a small integer z
+fn small(z: i32, p: &BigNumRef, ctx: &mut BigNumContext) -> Result<BigNum> {
+    let abs = BigNum::from_u32(z.unsigned_abs())?;
+    if z < 0 {
+        neg(&abs, p, ctx)
+    } else {
+        Ok(abs)
+    }
+}
+
+// sum of coeffs[i] x^i with hex coefficients, by Horner's rule
+fn poly_eval(coeffs: &[&str], x: &BigNumRef, p: &BigNumRef, ctx: &mut BigNumContext) -> Result<BigNum> {
+    let mut r = BigNum::new()?;
+    for c in coeffs.iter().rev() {
+        let mut r_x = BigNum::new()?;
+        r_x.mod_mul(&r, x, p, ctx)?;
+        r.mod_add(&r_x, BigNum::from_hex_str(c)?.as_ref(), p, ctx)?;
+    }
+    Ok(r)
+}
+
+// the point (x, y) of group
+fn point(group: &EcGroupRef, x: &BigNumRef, y: &BigNumRef, ctx: &mut BigNumContext) -> Result<EcPoint> {
+    let mut P = EcPoint::new(group)?;
+    P.set_affine_coordinates_gfp(group, x, y, ctx)?;
+    if !P.is_on_curve(group, ctx)? {
+        return Err(ZkError::InvalidCurve("mapped point is not on the curve"));
+    }
+    Ok(P)
+}
+
+
+/*
+
+        UTIL::polynomials
+
+*/
+
+// Polynomials over GF(p) as coefficients, lowest degree first, without leading zeros
+
+fn poly_copy(f: &[BigNum]) -> Result<Vec<BigNum>> {
+    Ok(poly_trim(f.iter().map(|c| c.as_ref().to_owned()).collect::<std::result::Result<_, _>>()?))
+}
+
+fn poly_trim(mut f: Vec<BigNum>) -> Vec<BigNum> {
+    while f.last().is_some_and(|c| c.num_bits() == 0) {
+        f.pop();
+    }
+    f
+}
+
+// f mod g, g != 0
+fn poly_rem(f: &[BigNum], g: &[BigNum], p: &BigNumRef, ctx: &mut BigNumContext) -> Result<Vec<BigNum>> {
+    let mut r = poly_copy(f)?;
+    let lead_inv = inv0(&g[g.len() - 1], p, ctx)?;
+
+    while r.len() >= g.len() {
+        let shift = r.len() - g.len();
+        let mut q = BigNum::new()?;
+        q.mod_mul(&r[r.len() - 1], &lead_inv, p, ctx)?;
+        for (i, g_i) in g.iter().enumerate() {
+            let mut q_g_i = BigNum::new()?;
+            q_g_i.mod_mul(&q, g_i, p, ctx)?;
+            let mut c = BigNum::new()?;
+            c.mod_sub(&r[shift + i], &q_g_i, p, ctx)?;
+            r[shift + i] = c;
+        }
+        r = poly_trim(r);
+    }
+    Ok(r)
+}
+
+fn poly_mul_rem(f: &[BigNum], g: &[BigNum], m: &[BigNum], p: &BigNumRef, ctx: &mut BigNumContext) -> Result<Vec<BigNum>> {
+    if f.is_empty() || g.is_empty() {
+        return Ok(Vec::new())
+    }
+    let mut fg = (0..f.len() + g.len() - 1).map(|_| BigNum::new()).collect::<std::result::Result<Vec<_>, _>>()?;
+    for (i, f_i) in f.iter().enumerate() {
+        for (j, g_j) in g.iter().enumerate() {
+            let mut f_i_g_j = BigNum::new()?;
+            f_i_g_j.mod_mul(f_i, g_j, p, ctx)?;
+            let mut c = BigNum::new()?;
+            c.mod_add(&fg[i + j], &f_i_g_j, p, ctx)?;
+            fg[i + j] = c;
+        }
+    }
+    poly_rem(&fg, m, p, ctx)
+}
+
+// A cubic is irreducible over GF(p) iff it has no root, iff gcd(x^p - x, f) = 1
+fn cubic_is_irreducible(f: &[BigNum], p: &BigNumRef, ctx: &mut BigNumContext) -> Result<bool> {
+
+    // x^p mod f by square and multiply
+    let x = vec![BigNum::new()?, BigNum::from_u32(1)?];
+    let mut x_p = vec![BigNum::from_u32(1)?];
+    for i in (0..p.num_bits()).rev() {
+        x_p = poly_mul_rem(&x_p, &x_p, f, p, ctx)?;
+        if p.is_bit_set(i) {
+            x_p = poly_mul_rem(&x_p, &x, f, p, ctx)?;
+        }
+    }
+
+    // x^p - x mod f
+    while x_p.len() < 2 {
+        x_p.push(BigNum::new()?);
+    }
+    let mut c = BigNum::new()?;
+    c.mod_sub(&x_p[1], BigNum::from_u32(1)?.as_ref(), p, ctx)?;
+    x_p[1] = c;
+
+    // gcd(f, x^p - x)
+    let mut a = poly_copy(f)?;
+    let mut b = poly_trim(x_p);
+    while !b.is_empty() {
+        let r = poly_rem(&a, &b, p, ctx)?;
+        a = b;
+        b = r;
+    }
+
+    Ok(a.len() == 1)
+}
+
+
+/*
+
+        UTIL::sswu
+
+*/
+
+/**
+ * find_z_sswu (RFC 9380, Appendix H.2): the first of 1, -1, 2, -2, ... that is
+ * a valid Z for the simplified SWU map of group. Gives Z = -10 on P-256.
+ *
+ * @param group curve y^2 = x^3 + a x + b with a, b != 0
+ */
+pub fn find_z_sswu(group: &EcGroupRef) -> Result<BigNum> {
+
+    let mut ctx = BigNumContext::new()?;
+    let (p, a, b) = components(group, &mut ctx)?;
+    if a.num_bits() == 0 || b.num_bits() == 0 {
+        return Err(ZkError::InvalidCurve("simplified SWU needs a, b != 0"));
+    }
+    let minus_one = neg(BigNum::from_u32(1)?.as_ref(), &p, &mut ctx)?;
+
+    for ctr in 1..=1000u32 {
+        let z_pos = BigNum::from_u32(ctr)?;
+        let z_neg = neg(&z_pos, &p, &mut ctx)?;
+
+        for z in [z_pos, z_neg] {
+            // 1. Z is non-square and 2. Z != -1
+            if is_square(&z, &p, &mut ctx)? || z == minus_one {
+                continue;
+            }
+
+            // 3. g(x) - Z is irreducible
+            let mut b_minus_z = BigNum::new()?;
+            b_minus_z.mod_sub(&b, &z, &p, &mut ctx)?;
+            let f = [b_minus_z, a.to_owned()?, BigNum::new()?, BigNum::from_u32(1)?];
+            if !cubic_is_irreducible(&f, &p, &mut ctx)? {
+                continue;
+            }
+
+            // 4. g(B / (Z A)) is square
+            let mut z_a = BigNum::new()?;
+            z_a.mod_mul(&z, &a, &p, &mut ctx)?;
+            let z_a_inv = inv0(&z_a, &p, &mut ctx)?;
+            let mut x = BigNum::new()?;
+            x.mod_mul(&b, &z_a_inv, &p, &mut ctx)?;
+            let gx = curve_rhs(&x, &a, &b, &p, &mut ctx)?;
+            if is_square(&gx, &p, &mut ctx)? {
+                return Ok(z);
+            }
+        }
+    }
+
+    Err(ZkError::InvalidCurve("no Z found for simplified SWU"))
+}
+
+
+// map_to_curve_simple_swu (RFC 9380, Section 6.6.2) on y^2 = x^3 + a x + b, as affine (x, y)
+fn map_to_curve_simple_swu(a: &BigNumRef, b: &BigNumRef, z: &BigNumRef, u: &BigNumRef, p: &BigNumRef, ctx: &mut BigNumContext) -> Result<(BigNum, BigNum)> {
+
+    // tv1 = inv0(Z^2 u^4 + Z u^2)
+    let mut u2 = BigNum::new()?;
+    u2.mod_sqr(u, p, ctx)?;
+    let mut z_u2 = BigNum::new()?;
+    z_u2.mod_mul(z, &u2, p, ctx)?;
+    let mut z2_u4 = BigNum::new()?;
+    z2_u4.mod_sqr(&z_u2, p, ctx)?;
+    let mut den = BigNum::new()?;
+    den.mod_add(&z2_u4, &z_u2, p, ctx)?;
+    let tv1 = inv0(&den, p, ctx)?;
+
+    let mut x1 = BigNum::new()?;
+    if tv1.num_bits() == 0 {
+        // x1 = B / (Z A)
+        let mut z_a = BigNum::new()?;
+        z_a.mod_mul(z, a, p, ctx)?;
+        let z_a_inv = inv0(&z_a, p, ctx)?;
+        x1.mod_mul(b, &z_a_inv, p, ctx)?;
+    } else {
+        // x1 = (-B / A) (1 + tv1)
+        let minus_b = neg(b, p, ctx)?;
+        let a_inv = inv0(a, p, ctx)?;
+        let mut minus_b_over_a = BigNum::new()?;
+        minus_b_over_a.mod_mul(&minus_b, &a_inv, p, ctx)?;
+        let mut one_plus_tv1 = BigNum::new()?;
+        one_plus_tv1.mod_add(BigNum::from_u32(1)?.as_ref(), &tv1, p, ctx)?;
+        x1.mod_mul(&minus_b_over_a, &one_plus_tv1, p, ctx)?;
+    }
+    let gx1 = curve_rhs(&x1, a, b, p, ctx)?;
+
+    // x2 = Z u^2 x1
+    let mut x2 = BigNum::new()?;
+    x2.mod_mul(&z_u2, &x1, p, ctx)?;
+
+    let (x, gx) = if is_square(&gx1, p, ctx)? {
+        (x1, gx1)
+    } else {
+        let gx2 = curve_rhs(&x2, a, b, p, ctx)?;
+        (x2, gx2)
+    };
+    let mut y = BigNum::new()?;
+    y.mod_sqrt(&gx, p, ctx)?;
+    if sgn0(u) != sgn0(&y) {
+        y = neg(&y, p, ctx)?;
+    }
+
+    Ok((x, y))
+}
+
+
+// iso_map (RFC 9380, Section 6.6.3) from the 3-isogenous curve to secp256k1
+fn iso_map_secp256k1(group: &EcGroupRef, x_iso: &BigNumRef, y_iso: &BigNumRef, p: &BigNumRef, ctx: &mut BigNumContext) -> Result<EcPoint> {
+
+    let x_num = poly_eval(&SECP256K1_ISO_X_NUM, x_iso, p, ctx)?;
+    let x_den = poly_eval(&SECP256K1_ISO_X_DEN, x_iso, p, ctx)?;
+    let y_num = poly_eval(&SECP256K1_ISO_Y_NUM, x_iso, p, ctx)?;
+    let y_den = poly_eval(&SECP256K1_ISO_Y_DEN, x_iso, p, ctx)?;
+
+    // the kernel of the isogeny maps to the identity
+    if x_den.num_bits() == 0 || y_den.num_bits() == 0 {
+        return Ok(EcPoint::new(group)?);
+    }
+
+    let x_den_inv = inv0(&x_den, p, ctx)?;
+    let y_den_inv = inv0(&y_den, p, ctx)?;
+    let mut x = BigNum::new()?;
+    x.mod_mul(&x_num, &x_den_inv, p, ctx)?;
+    let mut y_ratio = BigNum::new()?;
+    y_ratio.mod_mul(&y_num, &y_den_inv, p, ctx)?;
+    let mut y = BigNum::new()?;
+    y.mod_mul(y_iso, &y_ratio, p, ctx)?;
+
+    point(group, &x, &y, ctx)
+}
+
+
+/*
+
+        UTIL::svdw
+
+*/
+
+/**
+ * find_z_svdw (RFC 9380, Appendix H.1): the first of 1, -1, 2, -2, ... that is
+ * a valid Z for the Shallue-van de Woestijne map of group.
+ *
+ * @param group curve y^2 = x^3 + a x + b
+ */
+pub fn find_z_svdw(group: &EcGroupRef) -> Result<BigNum> {
+
+    let mut ctx = BigNumContext::new()?;
+    let (p, a, b) = components(group, &mut ctx)?;
+    let two_inv = inv0(BigNum::from_u32(2)?.as_ref(), &p, &mut ctx)?;
+
+    for ctr in 1..=1000u32 {
+        let z_pos = BigNum::from_u32(ctr)?;
+        let z_neg = neg(&z_pos, &p, &mut ctx)?;
+
+        for z in [z_pos, z_neg] {
+            // 1. g(Z) != 0
+            let gz = curve_rhs(&z, &a, &b, &p, &mut ctx)?;
+            if gz.num_bits() == 0 {
+                continue;
+            }
+
+            // 2. -(3 Z^2 + 4 A) / (4 g(Z)) != 0 and 3. is square
+            let (num, den) = svdw_ratio(&z, &a, &gz, &p, &mut ctx)?;
+            let minus_num = neg(&num, &p, &mut ctx)?;
+            let den_inv = inv0(&den, &p, &mut ctx)?;
+            let mut h = BigNum::new()?;
+            h.mod_mul(&minus_num, &den_inv, &p, &mut ctx)?;
+            if h.num_bits() == 0 || !is_square(&h, &p, &mut ctx)? {
+                continue;
+            }
+
+            // 4. g(Z) or g(-Z / 2) is square
+            let minus_z = neg(&z, &p, &mut ctx)?;
+            let mut minus_z_half = BigNum::new()?;
+            minus_z_half.mod_mul(&minus_z, &two_inv, &p, &mut ctx)?;
+            let g_minus_z_half = curve_rhs(&minus_z_half, &a, &b, &p, &mut ctx)?;
+            if is_square(&gz, &p, &mut ctx)? || is_square(&g_minus_z_half, &p, &mut ctx)? {
+                return Ok(z);
+            }
+        }
+    }
+
+    Err(ZkError::InvalidCurve("no Z found for Shallue-van de Woestijne"))
+}
+
+// (3 Z^2 + 4 A, 4 g(Z))
+fn svdw_ratio(z: &BigNumRef, a: &BigNumRef, gz: &BigNumRef, p: &BigNumRef, ctx: &mut BigNumContext) -> Result<(BigNum, BigNum)> {
+    let mut z2 = BigNum::new()?;
+    z2.mod_sqr(z, p, ctx)?;
+    let mut three_z2 = BigNum::new()?;
+    three_z2.mod_mul(&z2, BigNum::from_u32(3)?.as_ref(), p, ctx)?;
+    let mut four_a = BigNum::new()?;
+    four_a.mod_mul(a, BigNum::from_u32(4)?.as_ref(), p, ctx)?;
+    let mut num = BigNum::new()?;
+    num.mod_add(&three_z2, &four_a, p, ctx)?;
+    let mut den = BigNum::new()?;
+    den.mod_mul(gz, BigNum::from_u32(4)?.as_ref(), p, ctx)?;
+    Ok((num, den))
+}
+
+// map_to_curve_svdw (RFC 9380, Section 6.6.1) on y^2 = x^3 + a x + b, as affine (x, y)
+fn map_to_curve_svdw(a: &BigNumRef, b: &BigNumRef, z: &BigNumRef, u: &BigNumRef, p: &BigNumRef, ctx: &mut BigNumContext) -> Result<(BigNum, BigNum)> {
+
+    let one = BigNum::from_u32(1)?;
+
+    // c1 = g(Z), c2 = -Z / 2, c3 = sqrt(-g(Z) (3 Z^2 + 4 A)) with sgn0(c3) = 0,
+    // c4 = -4 g(Z) / (3 Z^2 + 4 A)
+    let c1 = curve_rhs(z, a, b, p, ctx)?;
+    let (ratio_num, ratio_den) = svdw_ratio(z, a, &c1, p, ctx)?;
+    let minus_z = neg(z, p, ctx)?;
+    let two_inv = inv0(BigNum::from_u32(2)?.as_ref(), p, ctx)?;
+    let mut c2 = BigNum::new()?;
+    c2.mod_mul(&minus_z, &two_inv, p, ctx)?;
+    let minus_c1 = neg(&c1, p, ctx)?;
+    let mut c3_sq = BigNum::new()?;
+    c3_sq.mod_mul(&minus_c1, &ratio_num, p, ctx)?;
+    let mut c3 = BigNum::new()?;
+    c3.mod_sqrt(&c3_sq, p, ctx)?;
+    if sgn0(&c3) {
+        c3 = neg(&c3, p, ctx)?;
+    }
+    let minus_ratio_den = neg(&ratio_den, p, ctx)?;
+    let ratio_num_inv = inv0(&ratio_num, p, ctx)?;
+    let mut c4 = BigNum::new()?;
+    c4.mod_mul(&minus_ratio_den, &ratio_num_inv, p, ctx)?;
+
+    // tv1 = u^2 c1, tv2 = 1 + tv1, tv1 = 1 - tv1, tv3 = inv0(tv1 tv2)
+    let mut u2 = BigNum::new()?;
+    u2.mod_sqr(u, p, ctx)?;
+    let mut u2_c1 = BigNum::new()?;
+    u2_c1.mod_mul(&u2, &c1, p, ctx)?;
+    let mut tv2 = BigNum::new()?;
+    tv2.mod_add(&one, &u2_c1, p, ctx)?;
+    let mut tv1 = BigNum::new()?;
+    tv1.mod_sub(&one, &u2_c1, p, ctx)?;
+    let mut tv1_tv2 = BigNum::new()?;
+    tv1_tv2.mod_mul(&tv1, &tv2, p, ctx)?;
+    let tv3 = inv0(&tv1_tv2, p, ctx)?;
+
+    // tv4 = u tv1 tv3 c3
+    let mut tv4 = BigNum::new()?;
+    tv4.mod_mul(u, &tv1, p, ctx)?;
+    let mut tv4_tv3 = BigNum::new()?;
+    tv4_tv3.mod_mul(&tv4, &tv3, p, ctx)?;
+    tv4.mod_mul(&tv4_tv3, &c3, p, ctx)?;
+
+    // x1 = c2 - tv4, x2 = c2 + tv4, x3 = Z + c4 (tv2^2 tv3)^2
+    let mut x1 = BigNum::new()?;
+    x1.mod_sub(&c2, &tv4, p, ctx)?;
+    let mut x2 = BigNum::new()?;
+    x2.mod_add(&c2, &tv4, p, ctx)?;
+    let mut tv2_sq = BigNum::new()?;
+    tv2_sq.mod_sqr(&tv2, p, ctx)?;
+    let mut t = BigNum::new()?;
+    t.mod_mul(&tv2_sq, &tv3, p, ctx)?;
+    let mut t_sq = BigNum::new()?;
+    t_sq.mod_sqr(&t, p, ctx)?;
+    let mut c4_t_sq = BigNum::new()?;
+    c4_t_sq.mod_mul(&c4, &t_sq, p, ctx)?;
+    let mut x3 = BigNum::new()?;
+    x3.mod_add(z, &c4_t_sq, p, ctx)?;
+
+    let gx1 = curve_rhs(&x1, a, b, p, ctx)?;
+    let gx2 = curve_rhs(&x2, a, b, p, ctx)?;
+    let (x, gx) = if is_square(&gx1, p, ctx)? {
+        (x1, gx1)
+    } else if is_square(&gx2, p, ctx)? {
+        (x2, gx2)
+    } else {
+        let gx3 = curve_rhs(&x3, a, b, p, ctx)?;
+        (x3, gx3)
+    };
+
+    let mut y = BigNum::new()?;
+    y.mod_sqrt(&gx, p, ctx)?;
+    if sgn0(u) != sgn0(&y) {
+        y = neg(&y, p, ctx)?;
+    }
+
+    Ok((x, y))
+}
+
+
+// map_to_curve of the suite of group
+fn map_to_curve(group: &EcGroupRef, suite: &Suite, u: &BigNumRef, ctx: &mut BigNumContext) -> Result<EcPoint> {
+
+    let (p, a, b) = components(group, ctx)?;
+    let z = small(suite.z, &p, ctx)?;
+
+    match suite.map {
+        Map::Sswu => {
+            let (x, y) = map_to_curve_simple_swu(&a, &b, &z, u, &p, ctx)?;
+            point(group, &x, &y, ctx)
+        }
+        Map::Secp256k1Isogeny => {
+            let a_iso = BigNum::from_hex_str(SECP256K1_ISO_A)?;
+            let b_iso = BigNum::from_hex_str(SECP256K1_ISO_B)?;
+            let (x, y) = map_to_curve_simple_swu(&a_iso, &b_iso, &z, u, &p, ctx)?;
+            iso_map_secp256k1(group, &x, &y, &p, ctx)
+        }
+        Map::Svdw => {
+            let (x, y) = map_to_curve_svdw(&a, &b, &z, u, &p, ctx)?;
+            point(group, &x, &y, ctx)
+        }
+    }
+}
+
+
+/**
+ * Hashes msg to a point of group (RFC 9380, random-oracle encoding) with the
+ * suite of group: the standard suites on P-256, P-384, P-521 and secp256k1 and
+ * the suites above on the companion curves. Other curves are rejected.
+ *
+ * @param group supported curve
+ * @param dst domain separation tag, unique to the application and the curve
+ * @param msg message
+ */
+pub fn hash_to_curve(group: &EcGroupRef, dst: &[u8], msg: &[u8]) -> Result<EcPoint> {
+
+    let mut ctx = BigNumContext::new()?;
+
+    let suite = suite(group, &mut ctx)?;
+    let u = hash_to_field(group, dst, msg, 2)?;
+
+    let Q0 = map_to_curve(group, suite, &u[0], &mut ctx)?;
+    let Q1 = map_to_curve(group, suite, &u[1], &mut ctx)?;
+    let mut R = EcPoint::new(group)?;
+    R.add(group, &Q0, &Q1, &mut ctx)?;
+
+    // clear_cofactor(R) = h_eff R
+    let mut cofactor = BigNum::new()?;
+    group.cofactor(&mut cofactor, &mut ctx)?;
+    let mut P = EcPoint::new(group)?;
+    P.mul(group, &R, &cofactor, &ctx)?;
+
+    Ok(P)
+}
@@ -0,0 +1,302 @@
+use openssl::ec::{EcGroup, EcGroupRef, EcPoint, PointConversionForm};
+use openssl::bn::{BigNum, BigNumContext};
+use openssl::nid::Nid;
+
+use crate::error::{Result, ZkError};
+
+
+/// Short Weierstrass curve y^2 = x^3 + a x + b over GF(p) given by its hex constants.
+pub struct CurveParams {
+    pub name: &'static str,
+    pub p: &'static str,
+    pub a: &'static str,
+    pub b: &'static str,
+    pub order: &'static str,
+    pub g_x: &'static str,
+    pub g_y: &'static str,
+    pub cofactor: u32,
+}
+
+
+/*  export const tom256 = new WeierstrassGroup(
+        'tom256',
+        BigInt('0xffffffff0000000100000000000000017e72b42b30e7317793135661b1c4b117'),   // p
+        BigInt('0xffffffff0000000100000000000000017e72b42b30e7317793135661b1c4b114'),   // a
+        BigInt('0xb441071b12f4a0366fb552f8e21ed4ac36b06aceeb354224863e60f20219fc56'),   // b
+        BigInt('0xffffffff00000001000000000000000000000000ffffffffffffffffffffffff'),   // order
+        [BigInt('0x3'), BigInt('0x5a6dd32df58708e64e97345cbe66600decd9d538a351bb3c30b4954925b1f02d')]   // generator
+    ) */
+
+/// Tom256 ("Wario"): its group order is the base-field prime of P-256.
+pub const TOM256: CurveParams = CurveParams {
+    name: "tom256",
+    p: "ffffffff0000000100000000000000017e72b42b30e7317793135661b1c4b117",
+    a: "ffffffff0000000100000000000000017e72b42b30e7317793135661b1c4b114",
+    b: "b441071b12f4a0366fb552f8e21ed4ac36b06aceeb354224863e60f20219fc56",
+    order: "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff",
+    g_x: "3",
+    g_y: "5a6dd32df58708e64e97345cbe66600decd9d538a351bb3c30b4954925b1f02d",
+    cofactor: 1,
+};
+
+
+/*  Tom384 and Tom521 follow the Tom256 construction: CM method with the smallest
+    discriminant (619 and 28243) giving a prime field and a model with a = -3,
+    generator with the smallest x coordinate. */
+
+/// Tom384: its group order is the base-field prime of P-384.
+pub const TOM384: CurveParams = CurveParams {
+    name: "tom384",
+    p: "fffffffffffffffffffffffffffffffffffffffffffffffeaf5f689f8669fb41b08d5f5edffd26599c434bbd978917c5",
+    a: "fffffffffffffffffffffffffffffffffffffffffffffffeaf5f689f8669fb41b08d5f5edffd26599c434bbd978917c2",
+    b: "66712deeb0abea5002da289634a90133b5322d817fb4682e7929123bf232ab4324a92d57cbc61e2834bf466a15dc50c1",
+    order: "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffff0000000000000000ffffffff",
+    g_x: "1",
+    g_y: "132539a18a2f22e8f29252f5713ea409493eed7788cde46dab9720aa1975dd589c63040273856cda5c78729136dbbfe9",
+    cofactor: 1,
+};
+
+
+/// Tom521: its group order is the base-field prime of P-521.
+pub const TOM521: CurveParams = CurveParams {
+    name: "tom521",
+    p: "200000000000000000000000000000000000000000000000000000000000000002c54be78524c33584f734a266748b2063accf5028e6778dc5056476d0690853249",
+    a: "200000000000000000000000000000000000000000000000000000000000000002c54be78524c33584f734a266748b2063accf5028e6778dc5056476d0690853246",
+    b: "19ce517189ecc577582ff838d9ecc785fc4f25031e7f9744dce37d6c565183bd5d42d25e3b6ca6552a8c68657948ed5d5030415d562822929d9163a19deac320b41",
+    order: "1ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+    g_x: "1",
+    g_y: "1bfb3a62787c3e2f47e3d6c2282342562f51987d3f78640fbfc8555af31bac28401fbc73bf77263a0bb6ac0212d68639a3f5e411dc1f8e7c17856ebf63957ffede",
+    cofactor: 1,
+};
+
+
+/*  secq256k1 swaps the field and the order of secp256k1: y^2 = x^3 + 7 over GF(n),
+    where n is the secp256k1 order, with group order the secp256k1 field prime.
+    Generator with the smallest x coordinate. */
+
+/// secq256k1: its group order is the base-field prime of secp256k1.
+pub const SECQ256K1: CurveParams = CurveParams {
+    name: "secq256k1",
+    p: "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+    a: "0",
+    b: "7",
+    order: "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+    g_x: "1",
+    g_y: "c7c97045a2074634909abdf82c9bd0248916189041f2af0c1b800d1ffc278c0",
+    cofactor: 1,
+};
+
+
+/// Builds the group described by params and validates its generator, order and cofactor.
+pub fn from_params(params: &CurveParams) -> Result<EcGroup> {
+
+    let mut ctx = BigNumContext::new()?;
+
+    let p = BigNum::from_hex_str(params.p)?;
+    let a = BigNum::from_hex_str(params.a)?;
+    let b = BigNum::from_hex_str(params.b)?;
+    let order = BigNum::from_hex_str(params.order)?;
+    let g_x = BigNum::from_hex_str(params.g_x)?;
+    let g_y = BigNum::from_hex_str(params.g_y)?;
+
+    // create group
+    let mut group = EcGroup::from_components(p, a, b, &mut ctx)?;
+
+    // create generator
+    let mut g = EcPoint::new(&group)?;
+    g.set_affine_coordinates_gfp(&group, &g_x, &g_y, &mut ctx)?;
+
+    // set generator and order on group
+    group.set_generator(g, order, BigNum::from_u32(params.cofactor)?)?;
+
+    validate(&group, params.cofactor)?;
+
+    Ok(group)
+}
+
+
+/// Checks that the generator lies on the curve and has prime order n,
+/// and that the group has the expected cofactor h, with n h within the
+/// Hasse bound of the base field.
+pub fn validate(group: &EcGroupRef, cofactor: u32) -> Result<()> {
+
+    let mut ctx = BigNumContext::new()?;
+
+    let g = group.generator();
+    if !g.is_on_curve(group, &mut ctx)? {
+        return Err(ZkError::InvalidCurve("generator not on curve"));
+    }
+    if g.is_infinity(group) {
+        return Err(ZkError::InvalidCurve("generator is at infinity"));
+    }
+
+    let mut order_curve = BigNum::new()?;
+    group.order(&mut order_curve, &mut ctx)?;
+    if !order_curve.is_prime(64, &mut ctx)? {
+        return Err(ZkError::InvalidCurve("order is not prime"));
+    }
+
+    // n g = O
+    let mut n_g = EcPoint::new(group)?;
+    n_g.mul(group, g, &order_curve, &ctx)?;
+    if !n_g.is_infinity(group) {
+        return Err(ZkError::InvalidCurve("generator order mismatch"));
+    }
+
+    let mut group_cofactor = BigNum::new()?;
+    group.cofactor(&mut group_cofactor, &mut ctx)?;
+    if group_cofactor != BigNum::from_u32(cofactor)? {
+        return Err(ZkError::InvalidCurve("cofactor mismatch"));
+    }
+
+    // Hasse: the number of points n h lies in [p + 1 - 2 sqrt(p), p + 1 + 2 sqrt(p)],
+    // i.e. (p + 1 - n h)^2 <= 4 p. With n g = O this pins down the cofactor.
+    let mut p = BigNum::new()?;
+    let mut a = BigNum::new()?;
+    let mut b = BigNum::new()?;
+    group.components_gfp(&mut p, &mut a, &mut b, &mut ctx)?;
+
+    let mut points = BigNum::new()?;
+    points.checked_mul(&order_curve, &group_cofactor, &mut ctx)?;
+    let mut p_plus_1 = BigNum::new()?;
+    p_plus_1.checked_add(&p, BigNum::from_u32(1)?.as_ref())?;
+    let mut trace = BigNum::new()?;
+    trace.checked_sub(&p_plus_1, &points)?;
+    let mut trace_sq = BigNum::new()?;
+    trace_sq.sqr(&trace, &mut ctx)?;
+    let mut four_p = BigNum::new()?;
+    four_p.lshift(&p, 2)?;
+    if trace_sq > four_p {
+        return Err(ZkError::InvalidCurve("group order outside the Hasse bound"));
+    }
+
+    Ok(())
+}
+
+
+/// Tom is a companion curve of NIST when the group order of Tom equals the
+/// base-field prime of NIST, so NIST coordinates can be committed in Tom.
+pub fn is_companion(nist: &EcGroupRef, tom: &EcGroupRef) -> Result<bool> {
+
+    let mut ctx = BigNumContext::new()?;
+
+    let mut p = BigNum::new()?;
+    let mut a = BigNum::new()?;
+    let mut b = BigNum::new()?;
+    nist.components_gfp(&mut p, &mut a, &mut b, &mut ctx)?;
+
+    let mut tom_order = BigNum::new()?;
+    tom.order(&mut tom_order, &mut ctx)?;
+
+    Ok(p == tom_order)
+}
+
+
+fn from_nid(nid: Nid) -> Result<EcGroup> {
+    let group = EcGroup::from_curve_name(nid)?;
+    validate(&group, 1)?;
+    Ok(group)
+}
+
+pub fn p256() -> Result<EcGroup> {
+    from_nid(Nid::X9_62_PRIME256V1)
+}
+
+pub fn p384() -> Result<EcGroup> {
+    from_nid(Nid::SECP384R1)
+}
+
+pub fn p521() -> Result<EcGroup> {
+    from_nid(Nid::SECP521R1)
+}
+
+pub fn secp256k1() -> Result<EcGroup> {
+    from_nid(Nid::SECP256K1)
+}
+
+pub fn tom256() -> Result<EcGroup> {
+    from_params(&TOM256)
+}
+
+pub fn tom384() -> Result<EcGroup> {
+    from_params(&TOM384)
+}
+
+pub fn tom521() -> Result<EcGroup> {
+    from_params(&TOM521)
+}
+
+pub fn secq256k1() -> Result<EcGroup> {
+    from_params(&SECQ256K1)
+}
+
+
+/// Pairs of a signature curve and its companion curve supported by the proof system.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurvePair {
+    P256Tom256,
+    P384Tom384,
+    P521Tom521,
+    Secp256k1Secq256k1,
+}
+
+impl CurvePair {
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CurvePair::P256Tom256 => "p256/tom256",
+            CurvePair::P384Tom384 => "p384/tom384",
+            CurvePair::P521Tom521 => "p521/tom521",
+            CurvePair::Secp256k1Secq256k1 => "secp256k1/secq256k1",
+        }
+    }
+
+    pub fn nist(&self) -> Result<EcGroup> {
+        match self {
+            CurvePair::P256Tom256 => p256(),
+            CurvePair::P384Tom384 => p384(),
+            CurvePair::P521Tom521 => p521(),
+            CurvePair::Secp256k1Secq256k1 => secp256k1(),
+        }
+    }
+
+    pub fn tom(&self) -> Result<EcGroup> {
+        match self {
+            CurvePair::P256Tom256 => tom256(),
+            CurvePair::P384Tom384 => tom384(),
+            CurvePair::P521Tom521 => tom521(),
+            CurvePair::Secp256k1Secq256k1 => secq256k1(),
+        }
+    }
+
+    /// Checks that nist and tom are the curves of this pair: same field,
+    /// coefficients, generator, order and cofactor.
+    pub fn matches(&self, nist: &EcGroupRef, tom: &EcGroupRef) -> Result<bool> {
+        let pair_nist = self.nist()?;
+        let pair_tom = self.tom()?;
+        Ok(same_curve(nist, &pair_nist)? && same_curve(tom, &pair_tom)?)
+    }
+}
+
+
+fn same_curve(x: &EcGroupRef, y: &EcGroupRef) -> Result<bool> {
+    Ok(curve_constants(x)? == curve_constants(y)?)
+}
+
+// p, a, b, order, cofactor and the uncompressed generator
+fn curve_constants(group: &EcGroupRef) -> Result<(Vec<BigNum>, Vec<u8>)> {
+
+    let mut ctx = BigNumContext::new()?;
+
+    let mut p = BigNum::new()?;
+    let mut a = BigNum::new()?;
+    let mut b = BigNum::new()?;
+    group.components_gfp(&mut p, &mut a, &mut b, &mut ctx)?;
+    let mut order = BigNum::new()?;
+    group.order(&mut order, &mut ctx)?;
+    let mut cofactor = BigNum::new()?;
+    group.cofactor(&mut cofactor, &mut ctx)?;
+
+    let g = group.generator().to_bytes(group, PointConversionForm::UNCOMPRESSED, &mut ctx)?;
+    Ok((vec![p, a, b, order, cofactor], g))
+}
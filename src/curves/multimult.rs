@@ -1,9 +1,14 @@
 use std::cmp::Ordering;
+use std::collections::HashMap;
 
-use openssl::ec::{EcGroupRef, EcPoint};
+use openssl::ec::{EcGroupRef, EcPoint, EcPointRef, PointConversionForm};
 use openssl::bn::{BigNum, BigNumContext};
 
 use crate::commit::pedersen::generate_random;
+use crate::error::{Result, ZkError};
+
+/// Number of pairs from which evaluate switches from Bos–Coster to Pippenger.
+pub const PIPPENGER_THRESHOLD: usize = 64;
 
 pub struct Pair {
     pub pt: EcPoint,
@@ -15,21 +20,17 @@ impl Pair {
         Pair { pt, scalar }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn cmp(&self, b: &Pair) -> Ordering {
         self.scalar.cmp(&b.scalar)
     }
 }
 
-
-pub struct Ptidx {
-    pub pt: EcPoint,
-    pub idx: usize
-}
-
 pub struct MultiMult<'a> {
     pub group: &'a EcGroupRef,
     pub pairs: Vec<Pair>,
-    pub known: Vec<Ptidx>,
+    /// Compressed encoding of each known base to the index of its pair
+    pub known: HashMap<Vec<u8>, usize>,
 }
 
 
@@ -39,67 +40,96 @@ impl<'a> MultiMult<'a> {
         MultiMult {
             group: g,
             pairs: vec![],
-            known: vec![],
+            known: HashMap::new(),
         }
     }
 
-    pub fn add_known(&mut self, pt: EcPoint) {
+    /// Registers pt as a base that recurs, so that the scalars of all the
+    /// inserts of pt are merged into a single pair.
+    pub fn add_known(&mut self, pt: EcPoint) -> Result<()> {
         
-        is_compat_point(&pt, self.group);
+        is_compat_point(&pt, self.group)?;
 
-        let mut ctx = BigNumContext::new().unwrap();
+        let mut ctx = BigNumContext::new()?;
         
-        let present = self.known.iter().any(|x| pt.eq(self.group, &x.pt, &mut ctx).unwrap());
-        if !present {
-            let new_scalar = BigNum::from_dec_str("0").unwrap();
-            let new_pair = Pair::new( pt.to_owned(&self.group).unwrap(), new_scalar );
-            let new_idx = self.pairs.len();
-
-            self.pairs.push(new_pair);
-            self.known.push(Ptidx { pt: pt.to_owned(&self.group).unwrap(), idx: new_idx });
+        let key = known_key(&pt, self.group, &mut ctx)?;
+        if !self.known.contains_key(&key) {
+            let new_scalar = BigNum::from_dec_str("0")?;
+            self.known.insert(key, self.pairs.len());
+            self.pairs.push(Pair::new(pt, new_scalar));
         }
+
+        Ok(())
     }
 
-    pub fn insert(&mut self, pt: EcPoint, s: BigNum) {
+    pub fn insert(&mut self, pt: EcPoint, s: BigNum) -> Result<()> {
         
-        is_compat_point(&pt, self.group);
-        is_compat_scalar(&s, self.group);
+        is_compat_point(&pt, self.group)?;
+        is_compat_scalar(&s, self.group)?;
 
-        let mut ctx = BigNumContext::new().unwrap();
+        let mut ctx = BigNumContext::new()?;
 
-        if let Some(matched_idx) = self.known.iter().position(|x| pt.eq(self.group, &x.pt, &mut ctx).unwrap()) {
+        let matched = if self.known.is_empty() {
+            None
+        } else {
+            self.known.get(&known_key(&pt, self.group, &mut ctx)?).copied()
+        };
+
+        if let Some(matched_idx) = matched {
             
+            // Take group order
+            let mut order_curve = BigNum::new()?;
+            self.group.order(&mut order_curve, &mut ctx)?;
+
             let pairs_scalar = &self.pairs[matched_idx].scalar;
-            let mut ps_sum_s = BigNum::new().unwrap();
-            
-            ps_sum_s.checked_add(&pairs_scalar, &s).unwrap();
+            let mut ps_sum_s = BigNum::new()?;
+            ps_sum_s.mod_add(pairs_scalar, &s, &order_curve, &mut ctx)?;
 
             self.pairs[matched_idx].scalar = ps_sum_s;
 
-
         } else {
             self.pairs.push(Pair::new(pt, s));
         }
 
-
+        Ok(())
     }
     
-    pub fn evaluate(&mut self) -> EcPoint {
+    /// Moves the pairs of other into self, merging those of the known bases.
+    pub fn merge(&mut self, other: MultiMult) -> Result<()> {
+        for pair in other.pairs {
+            self.insert(pair.pt, pair.scalar)?;
+        }
+        Ok(())
+    }
+
+    /// Sum of scalar * pt over all pairs, with Pippenger's bucket method for
+    /// PIPPENGER_THRESHOLD pairs or more and Bos–Coster below.
+    pub fn evaluate(&mut self) -> Result<EcPoint> {
+        if self.pairs.len() >= PIPPENGER_THRESHOLD {
+            self.evaluate_pippenger()
+        } else {
+            self.evaluate_bos_coster()
+        }
+    }
+
+    /// Bos–Coster: repeatedly replaces the two largest scalars a >= b by
+    /// (a - b, a.pt) and (b, a.pt + b.pt). Consumes the pairs.
+    pub fn evaluate_bos_coster(&mut self) -> Result<EcPoint> {
         
-        let mut ctx = BigNumContext::new().unwrap();
+        let mut ctx = BigNumContext::new()?;
          
         if self.pairs.is_empty() {
-            return EcPoint::new(&self.group).unwrap();
+            return Ok(EcPoint::new(self.group)?);
         }
 
         if self.pairs.len() == 1 {
             let a = &self.pairs[0];
 
             // Multiplies a.pt by a.scalar
-            let mut apt_times_as = EcPoint::new(&self.group).unwrap();
-            apt_times_as.mul(&self.group, &a.pt, &a.scalar, &mut ctx).unwrap();
+            let mut apt_times_as = EcPoint::new(self.group)?;
+            apt_times_as.mul(self.group, &a.pt, &a.scalar, &ctx)?;
 
-            return apt_times_as;
+            return Ok(apt_times_as);
         }
 
         heapify(&mut self.pairs);
@@ -109,44 +139,44 @@ impl<'a> MultiMult<'a> {
                 let a = &self.pairs[0];
     
                 // Multiplies a.pt by a.scalar
-                let mut apt_times_as = EcPoint::new(&self.group).unwrap();
-                apt_times_as.mul(&self.group, &a.pt, &a.scalar, &mut ctx).unwrap();
+                let mut apt_times_as = EcPoint::new(self.group)?;
+                apt_times_as.mul(self.group, &a.pt, &a.scalar, &ctx)?;
 
-                return apt_times_as;
+                return Ok(apt_times_as);
             }
 
-            let a = extract_max(&mut self.pairs);
+            let a = extract_max(&mut self.pairs)?;
             
             let b = &self.pairs[0];
 
             
             // if b == 0
-            if b.scalar.ucmp(&BigNum::from_u32(0).unwrap()) == Ordering::Equal {
+            if b.scalar.ucmp(BigNum::from_u32(0)?.as_ref()) == Ordering::Equal {
                  
                 // Multiplies a.pt by a.scalar
-                let mut apt_times_as = EcPoint::new(&self.group).unwrap();
-                apt_times_as.mul(&self.group, &a.pt, &a.scalar, &mut ctx).unwrap();
+                let mut apt_times_as = EcPoint::new(self.group)?;
+                apt_times_as.mul(self.group, &a.pt, &a.scalar, &ctx)?;
              
-                return apt_times_as;
+                return Ok(apt_times_as);
                 
             }
             
              
             // c_scalar = a.s - b.s
-            let mut c_scalar = BigNum::new().unwrap();
-            //c_scalar.checked_sub(&a.scalar, &b.scalar).unwrap();
+            let mut c_scalar = BigNum::new()?;
+            //c_scalar.checked_sub(&a.scalar, &b.scalar)?;
             // Take group order
-            let mut order_curve = BigNum::new().unwrap();
-            self.group.order(&mut order_curve, &mut ctx).unwrap();
-            c_scalar.mod_sub(&a.scalar, &b.scalar, &order_curve, &mut ctx).unwrap();
-            let c = Pair::new(a.pt.to_owned(&self.group).unwrap(), c_scalar); 
+            let mut order_curve = BigNum::new()?;
+            self.group.order(&mut order_curve, &mut ctx)?;
+            c_scalar.mod_sub(&a.scalar, &b.scalar, &order_curve, &mut ctx)?;
+            let c = Pair::new(a.pt.to_owned(self.group)?, c_scalar); 
 
             // d_pt = b.pt + a.pt
-            let mut d_pt = EcPoint::new(&self.group).unwrap();
-            d_pt.add(&self.group, &b.pt, &a.pt, &mut ctx).unwrap();
+            let mut d_pt = EcPoint::new(self.group)?;
+            d_pt.add(self.group, &b.pt, &a.pt, &mut ctx)?;
             self.pairs[0].pt = d_pt;
             
-            if c.scalar.ucmp(&BigNum::from_u32(0).unwrap()) != Ordering::Equal {
+            if c.scalar.ucmp(BigNum::from_u32(0)?.as_ref()) != Ordering::Equal {
                 self.pairs.push(c);
                 let l = self.pairs.len();
                 bubble_up(&mut self.pairs, l);
@@ -157,26 +187,103 @@ impl<'a> MultiMult<'a> {
 
     }
 
+    /// Pippenger: cuts the scalars in windows of c bits and, window by window
+    /// from the top, adds every point into the bucket of its digit and sums
+    /// the buckets weighted by their digit.
+    pub fn evaluate_pippenger(&self) -> Result<EcPoint> {
+
+        let mut ctx = BigNumContext::new()?;
+
+        let mut acc = EcPoint::new(self.group)?;
+        if self.pairs.is_empty() {
+            return Ok(acc);
+        }
+
+        let bits = self.pairs.iter().map(|p| p.scalar.num_bits()).max().unwrap_or(0) as usize;
+        let c = window_bits(self.pairs.len(), bits);
+        let windows = bits.div_ceil(c);
+
+        for w in (0..windows).rev() {
+            // acc = 2^c * acc
+            for _ in 0..c {
+                let mut dbl = EcPoint::new(self.group)?;
+                dbl.add(self.group, &acc, &acc, &mut ctx)?;
+                acc = dbl;
+            }
+
+            let mut buckets = Vec::with_capacity((1 << c) - 1);
+            for _ in 0..(1 << c) - 1 {
+                buckets.push(EcPoint::new(self.group)?);
+            }
+            for p in &self.pairs {
+                let d = digit(&p.scalar, w * c, c);
+                if d != 0 {
+                    let mut sum = EcPoint::new(self.group)?;
+                    sum.add(self.group, &buckets[d - 1], &p.pt, &mut ctx)?;
+                    buckets[d - 1] = sum;
+                }
+            }
+
+            // sum_d d * buckets[d - 1] as the sum of the running suffix sums
+            let mut running = EcPoint::new(self.group)?;
+            let mut window = EcPoint::new(self.group)?;
+            for b in buckets.iter().rev() {
+                let mut r = EcPoint::new(self.group)?;
+                r.add(self.group, &running, b, &mut ctx)?;
+                running = r;
+                let mut s = EcPoint::new(self.group)?;
+                s.add(self.group, &window, &running, &mut ctx)?;
+                window = s;
+            }
+
+            let mut sum = EcPoint::new(self.group)?;
+            sum.add(self.group, &acc, &window, &mut ctx)?;
+            acc = sum;
+        }
+
+        Ok(acc)
+    }
+
+}
 
 
+// Compressed encoding of pt, which identifies it in MultiMult::known
+fn known_key(pt: &EcPointRef, group: &EcGroupRef, ctx: &mut BigNumContext) -> Result<Vec<u8>> {
+    Ok(pt.to_bytes(group, PointConversionForm::COMPRESSED, ctx)?)
 }
 
+// Window size minimising the additions: each of the bits / c windows adds the
+// n points into buckets and then takes 2 * 2^c additions to sum the buckets
+fn window_bits(n: usize, bits: usize) -> usize {
+    (1..=16)
+        .min_by_key(|&c| bits.div_ceil(c) * (n + (2 << c)))
+        .unwrap_or(1)
+}
 
-fn extract_max<'a>(arr: &'a mut Vec<Pair>) -> Pair {
-    // We shrink the heap
-    
-    let l = arr.len();
-    arr.swap(0, l - 1);
-   
-    let max = arr.pop();
-    match max {
-        Some(max) => {
-            push_down(arr, 1);
-            max
+// The c bits of s starting at bit offset
+fn digit(s: &BigNum, offset: usize, c: usize) -> usize {
+    let mut d = 0;
+    for i in (0..c).rev() {
+        d <<= 1;
+        if s.is_bit_set((offset + i) as i32) {
+            d |= 1;
         }
-        None => panic!("heap underflow"),
     }
+    d
+}
+
+
+fn extract_max(arr: &mut Vec<Pair>) -> Result<Pair> {
+    // We shrink the heap
     
+    if arr.is_empty() {
+        return Err(ZkError::InvalidInput("heap underflow"));
+    }
+   
+    // moves the last leaf to the root
+    let max = arr.swap_remove(0);
+    push_down(arr, 1);
+    Ok(max)
 }
 
 
@@ -227,6 +334,186 @@ fn push_down(arr: &mut Vec<Pair>, parent: usize) {
 
 
 
+/// Relations of many independent proofs, each drained into its own MultiMults
+/// (one per curve), which are checked together in one MultiMult per curve.
+/// Relation::drain weighs every relation with its own random scalar, so the sum
+/// vanishes only if every proof does, except with negligible probability. When
+/// it does not, the batch is bisected to find the proofs that do not verify.
+///
+/// The pairs of each proof are reduced once when it is pushed: the scalars of
+/// the known bases are kept apart and equal bases are merged. Checking a subset
+/// then only adds up the scalars of the known bases and concatenates the other
+/// pairs, without inserting them again.
+pub struct Batch<'a> {
+    pub groups: Vec<&'a EcGroupRef>,
+    known: Vec<Vec<EcPoint>>,
+    // None if the proof was rejected before its relations were checked
+    proofs: Vec<Option<Vec<Reduced>>>,
+}
+
+// The pairs of one proof on one curve: the scalars of the known bases of the
+// curve, in order, and the other bases, pairwise distinct
+struct Reduced {
+    known: Vec<BigNum>,
+    pairs: Vec<Pair>,
+}
+
+impl<'a> Batch<'a> {
+    pub fn new(groups: Vec<&'a EcGroupRef>) -> Self {
+        let known = groups.iter().map(|_| vec![]).collect();
+        Batch {
+            groups,
+            known,
+            proofs: vec![],
+        }
+    }
+
+    /// Registers pt as a base shared by the proofs on the curve-th curve of
+    /// groups. Known bases are registered before the first proof is pushed.
+    pub fn add_known(&mut self, curve: usize, pt: EcPoint) -> Result<()> {
+        if !self.proofs.is_empty() {
+            return Err(ZkError::InvalidInput("known bases are registered before the proofs"));
+        }
+        let group = self.groups[curve];
+        is_compat_point(&pt, group)?;
+
+        let mut ctx = BigNumContext::new()?;
+        for known in &self.known[curve] {
+            if known.eq(group, &pt, &mut ctx)? {
+                return Ok(());
+            }
+        }
+        self.known[curve].push(pt);
+        Ok(())
+    }
+
+    /// Adds the next proof: aggregate drains its relations into one MultiMult
+    /// per curve, in the order of groups, as the aggregate_* functions do.
+    /// A proof for which aggregate returns false or a MalformedProof error is
+    /// invalid; any other error is returned.
+    pub fn push(&mut self, aggregate: impl FnOnce(&mut [MultiMult<'a>]) -> Result<bool>) -> Result<()> {
+        let mut multis = Vec::with_capacity(self.groups.len());
+        for (curve, group) in self.groups.iter().enumerate() {
+            let mut multi = MultiMult::new(group);
+            for pt in &self.known[curve] {
+                multi.add_known(pt.as_ref().to_owned(group)?)?;
+            }
+            multis.push(multi);
+        }
+
+        let proof = match aggregate(&mut multis) {
+            Ok(true) => Some(
+                multis.into_iter().enumerate()
+                    .map(|(curve, multi)| self.reduce(curve, multi))
+                    .collect::<Result<Vec<_>>>()?
+            ),
+            Ok(false) | Err(ZkError::MalformedProof(_)) => None,
+            Err(e) => return Err(e),
+        };
+        self.proofs.push(proof);
+        Ok(())
+    }
+
+    // Splits off the scalars of the known bases, which the MultiMult of the
+    // proof holds in its first pairs, and merges the other equal bases
+    fn reduce(&self, curve: usize, multi: MultiMult) -> Result<Reduced> {
+        let group = self.groups[curve];
+        let mut ctx = BigNumContext::new()?;
+        let mut order_curve = BigNum::new()?;
+        group.order(&mut order_curve, &mut ctx)?;
+
+        let mut pairs = multi.pairs;
+        let others = pairs.split_off(self.known[curve].len());
+        let known = pairs.into_iter().map(|pair| pair.scalar).collect();
+
+        let mut index: HashMap<Vec<u8>, usize> = HashMap::new();
+        let mut merged: Vec<Pair> = Vec::with_capacity(others.len());
+        for pair in others {
+            if pair.scalar.num_bits() == 0 {
+                continue;
+            }
+            let key = known_key(&pair.pt, group, &mut ctx)?;
+            if let Some(&i) = index.get(&key) {
+                let mut sum = BigNum::new()?;
+                sum.mod_add(&merged[i].scalar, &pair.scalar, &order_curve, &mut ctx)?;
+                merged[i].scalar = sum;
+            } else {
+                index.insert(key, merged.len());
+                merged.push(pair);
+            }
+        }
+
+        Ok(Reduced { known, pairs: merged })
+    }
+
+    /// Indices of the proofs that do not verify, in increasing order.
+    pub fn invalid(&self) -> Result<Vec<usize>> {
+        let mut invalid = vec![];
+        let mut candidates = vec![];
+        for (i, proof) in self.proofs.iter().enumerate() {
+            match proof {
+                Some(_) => candidates.push(i),
+                None => invalid.push(i),
+            }
+        }
+
+        self.bisect(&candidates, &mut invalid)?;
+        invalid.sort_unstable();
+        Ok(invalid)
+    }
+
+    // Adds to invalid the proofs of candidates that do not verify, checking
+    // halves of the candidates only when all of them together do not verify
+    fn bisect(&self, candidates: &[usize], invalid: &mut Vec<usize>) -> Result<()> {
+        if candidates.is_empty() || self.verifies(candidates)? {
+            return Ok(());
+        }
+        if candidates.len() == 1 {
+            invalid.push(candidates[0]);
+            return Ok(());
+        }
+        let (left, right) = candidates.split_at(candidates.len() / 2);
+        self.bisect(left, invalid)?;
+        self.bisect(right, invalid)
+    }
+
+    // Whether the relations of all the proofs of subset vanish together
+    fn verifies(&self, subset: &[usize]) -> Result<bool> {
+        let mut ctx = BigNumContext::new()?;
+
+        for (curve, group) in self.groups.iter().enumerate() {
+            let mut order_curve = BigNum::new()?;
+            group.order(&mut order_curve, &mut ctx)?;
+
+            let mut known = self.known[curve].iter().map(|_| BigNum::new()).collect::<std::result::Result<Vec<_>, _>>()?;
+            let mut pairs = vec![];
+            for &i in subset {
+                if let Some(reduced) = &self.proofs[i] {
+                    for (sum, s) in known.iter_mut().zip(&reduced[curve].known) {
+                        let mut next = BigNum::new()?;
+                        next.mod_add(sum, s, &order_curve, &mut ctx)?;
+                        *sum = next;
+                    }
+                    for pair in &reduced[curve].pairs {
+                        pairs.push(Pair::new(pair.pt.to_owned(group)?, pair.scalar.to_owned()?));
+                    }
+                }
+            }
+            for (pt, s) in self.known[curve].iter().zip(known) {
+                pairs.push(Pair::new(pt.as_ref().to_owned(group)?, s));
+            }
+
+            let mut multi = MultiMult { group, pairs, known: HashMap::new() };
+            if !multi.evaluate()?.is_infinity(group) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+
+
 pub struct Relation<'a> {
     pub group: &'a EcGroupRef,
     pairs: Vec<Pair>,
@@ -243,69 +530,81 @@ impl<'a> Relation<'a> {
     }
 
     
-    pub fn insert_m(&mut self, pts: &[EcPoint], scalars: &[BigNum]) {
+    pub fn insert_m(&mut self, pts: &[EcPoint], scalars: &[BigNum]) -> Result<()> {
         if pts.len() != scalars.len() {
-            panic!("arrays are not the same length");
+            return Err(ZkError::InvalidInput("arrays are not the same length"));
         }
         for i in 0..pts.len() {
-            self.insert(pts[i].to_owned(&self.group).unwrap(), scalars[i].to_owned().unwrap());
+            self.insert(pts[i].to_owned(self.group)?, scalars[i].to_owned()?)?;
         }
+
+        Ok(())
     }
     
 
-    pub fn insert(&mut self, pt: EcPoint, s: BigNum) {
+    pub fn insert(&mut self, pt: EcPoint, s: BigNum) -> Result<()> {
         
-        is_compat_point(&pt, self.group);
-        is_compat_scalar(&s, self.group);
+        is_compat_point(&pt, self.group)?;
+        is_compat_scalar(&s, self.group)?;
 
         self.pairs.push(Pair { pt, scalar: s });
 
+        Ok(())
     }
      
-    pub fn drain(&self, m: &mut MultiMult) {
+    pub fn drain(&self, m: &mut MultiMult) -> Result<()> {
         
-        let mut ctx = BigNumContext::new().unwrap();
+        let mut ctx = BigNumContext::new()?;
 
-        let mut order_curve = BigNum::new().unwrap();
-        self.group.order(&mut order_curve, &mut ctx).unwrap();
-        let r = generate_random(&order_curve).unwrap();
+        let mut order_curve = BigNum::new()?;
+        self.group.order(&mut order_curve, &mut ctx)?;
+        let r = generate_random(&order_curve)?;
 
         let l = self.pairs.len();
         
         for i in 0..l {
 
-            let rel_pairs_i_pt = self.pairs[i].pt.to_owned(&self.group).unwrap();
+            let rel_pairs_i_pt = self.pairs[i].pt.to_owned(self.group)?;
 
-            let mut rel_pairs_i_s_times_r = BigNum::new().unwrap();
-            rel_pairs_i_s_times_r.mod_mul(&self.pairs[i].scalar, &r, &order_curve, &mut ctx).unwrap();
+            let mut rel_pairs_i_s_times_r = BigNum::new()?;
+            rel_pairs_i_s_times_r.mod_mul(&self.pairs[i].scalar, &r, &order_curve, &mut ctx)?;
 
-            m.insert( rel_pairs_i_pt, rel_pairs_i_s_times_r);
+            m.insert( rel_pairs_i_pt, rel_pairs_i_s_times_r)?;
         }
+
+        Ok(())
     }
     
 }
 
 
 
-pub fn is_compat_point(pt: &EcPoint, g: &EcGroupRef) {
+pub fn is_compat_point(pt: &EcPoint, g: &EcGroupRef) -> Result<()> {
     // is compatible point
 
-    let mut ctx = BigNumContext::new().unwrap();
+    let mut ctx = BigNumContext::new()?;
 
-    let compatible_point = pt.is_on_curve(g, &mut ctx).unwrap();
-    assert!(compatible_point, "point not compatible");
-        
+    let compatible_point = pt.is_on_curve(g, &mut ctx)?;
+    if !compatible_point {
+        return Err(ZkError::MalformedProof("point not compatible"));
+    }
+
+    Ok(())
 }
 
-pub fn is_compat_scalar(s: &BigNum, g: &EcGroupRef) {
+pub fn is_compat_scalar(s: &BigNum, g: &EcGroupRef) -> Result<()> {
     // is compatible scalar
 
-    let mut ctx = BigNumContext::new().unwrap();
+    let mut ctx = BigNumContext::new()?;
 
-    let mut order_curve = BigNum::new().unwrap();
-    g.order(&mut order_curve, &mut ctx).unwrap();
+    let mut order_curve = BigNum::new()?;
+    g.order(&mut order_curve, &mut ctx)?;
     let compatible_scalar = s <= &order_curve;
-    assert!(compatible_scalar, "scalar not compatible");
+    if !compatible_scalar {
+        return Err(ZkError::MalformedProof("scalar not compatible"));
+    }
+
+    Ok(())
 }
 
 
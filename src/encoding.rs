@@ -0,0 +1,206 @@
+//! Versioned binary encoding of the proofs.
+//!
+//! Every encoded proof starts with version || tag. Only the top-level
+//! SignatureProof (and the SignatureListProof around it) also records the id
+//! of the HashFunction its challenges were drawn with, and verify_signature
+//! rejects it without recomputing any challenge when that is not the hash of
+//! the SystemParameters. The standalone sub-proofs (equality, mult, pointAdd,
+//! exp and membership) carry no hash id: the caller has to know the hash they
+//! were proven with, and under another hash they decode fine and then fail to
+//! verify (Ok(false)).
+
+use openssl::ec::{EcGroupRef, EcPoint, PointConversionForm};
+use openssl::bn::{BigNum, BigNumContext};
+
+use crate::error::{Result, ZkError};
+
+
+/// Version of the binary proof encoding, first byte of every encoded proof.
+pub const VERSION: u8 = 2;
+
+/// Type tags, second byte of every encoded proof.
+pub const TAG_EQUALITY: u8 = 1;
+pub const TAG_MULT: u8 = 2;
+pub const TAG_POINT_ADD: u8 = 3;
+pub const TAG_EXP: u8 = 4;
+pub const TAG_EXP_LIST: u8 = 5;
+pub const TAG_SIGNATURE: u8 = 6;
+pub const TAG_MEMBERSHIP: u8 = 7;
+pub const TAG_SIGNATURE_LIST: u8 = 8;
+
+
+/*
+
+        UTIL::sizes
+
+*/
+
+// Compressed points take 1 + ceil(log2(p) / 8) bytes
+fn point_len(group: &EcGroupRef) -> usize {
+    1 + (group.degree() as usize).div_ceil(8)
+}
+
+// Scalars take ceil(log2(n) / 8) bytes
+fn scalar_len(group: &EcGroupRef, ctx: &mut BigNumContext) -> Result<usize> {
+    let mut order_curve = BigNum::new()?;
+    group.order(&mut order_curve, ctx)?;
+    Ok(order_curve.num_bytes() as usize)
+}
+
+
+/// Canonical encoder: compressed points, big-endian scalars reduced mod the
+/// group order and padded to the byte length of the order, u32 lengths.
+pub struct Writer {
+    buf: Vec<u8>,
+    ctx: BigNumContext,
+}
+
+impl Writer {
+
+    /// Starts an encoding with the version and the type tag.
+    pub fn new(tag: u8) -> Result<Self> {
+        Ok(Writer {
+            buf: vec![VERSION, tag],
+            ctx: BigNumContext::new()?,
+        })
+    }
+
+    /// Starts an encoding of bare values, without the header.
+    pub fn bare() -> Result<Self> {
+        Ok(Writer {
+            buf: Vec::new(),
+            ctx: BigNumContext::new()?,
+        })
+    }
+
+    pub fn length(&mut self, len: usize) -> Result<()> {
+        let len = u32::try_from(len).map_err(|_| ZkError::InvalidInput("length too large"))?;
+        self.buf.extend_from_slice(&len.to_be_bytes());
+        Ok(())
+    }
+
+    pub fn flag(&mut self, flag: u8) {
+        self.buf.push(flag);
+    }
+
+    pub fn point(&mut self, group: &EcGroupRef, pt: &EcPoint) -> Result<()> {
+        if pt.is_infinity(group) {
+            return Err(ZkError::InvalidInput("point at infinity"));
+        }
+        let bytes = pt.to_bytes(group, PointConversionForm::COMPRESSED, &mut self.ctx)?;
+        self.buf.extend_from_slice(&bytes);
+        Ok(())
+    }
+
+    pub fn scalar(&mut self, group: &EcGroupRef, s: &BigNum) -> Result<()> {
+        let mut order_curve = BigNum::new()?;
+        group.order(&mut order_curve, &mut self.ctx)?;
+        let mut ss = BigNum::new()?;
+        ss.nnmod(s, &order_curve, &mut self.ctx)?;
+
+        let width = order_curve.num_bytes();
+        self.buf.extend_from_slice(&ss.to_vec_padded(width)?);
+        Ok(())
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+
+/// Decoder for the encoding produced by Writer. Rejects points that are not
+/// on the curve, scalars that are not reduced and trailing bytes.
+pub struct Reader<'b> {
+    bytes: &'b [u8],
+    pos: usize,
+    ctx: BigNumContext,
+}
+
+impl<'b> Reader<'b> {
+
+    /// Checks the version and the type tag.
+    pub fn new(bytes: &'b [u8], tag: u8) -> Result<Self> {
+        let mut r = Reader {
+            bytes,
+            pos: 0,
+            ctx: BigNumContext::new()?,
+        };
+        if r.take(1)?[0] != VERSION {
+            return Err(ZkError::MalformedProof("unsupported version"));
+        }
+        if r.take(1)?[0] != tag {
+            return Err(ZkError::MalformedProof("unexpected proof type"));
+        }
+        Ok(r)
+    }
+
+    /// Reads bare values, without the header.
+    pub fn bare(bytes: &'b [u8]) -> Result<Self> {
+        Ok(Reader {
+            bytes,
+            pos: 0,
+            ctx: BigNumContext::new()?,
+        })
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'b [u8]> {
+        if self.bytes.len() - self.pos < n {
+            return Err(ZkError::MalformedProof("unexpected end of input"));
+        }
+        let ret = &self.bytes[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(ret)
+    }
+
+    /// Reads a length, bounded by the number of items of item_len bytes left.
+    pub fn length(&mut self, item_len: usize) -> Result<usize> {
+        let mut len = [0u8; 4];
+        len.copy_from_slice(self.take(4)?);
+        let len = u32::from_be_bytes(len) as usize;
+        if len.saturating_mul(item_len.max(1)) > self.bytes.len() - self.pos {
+            return Err(ZkError::MalformedProof("length out of range"));
+        }
+        Ok(len)
+    }
+
+    pub fn flag(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn point(&mut self, group: &EcGroupRef) -> Result<EcPoint> {
+        let bytes = self.take(point_len(group))?;
+        if bytes[0] != 2 && bytes[0] != 3 {
+            return Err(ZkError::MalformedProof("point not compressed"));
+        }
+        EcPoint::from_bytes(group, bytes, &mut self.ctx)
+            .map_err(|_| ZkError::MalformedProof("point not compatible"))
+    }
+
+    pub fn scalar(&mut self, group: &EcGroupRef) -> Result<BigNum> {
+        let mut order_curve = BigNum::new()?;
+        group.order(&mut order_curve, &mut self.ctx)?;
+        let s = BigNum::from_slice(self.take(order_curve.num_bytes() as usize)?)?;
+        if s >= order_curve {
+            return Err(ZkError::MalformedProof("scalar not compatible"));
+        }
+        Ok(s)
+    }
+
+    /// Size in bytes of a point of group, to bound lengths of point lists.
+    pub fn point_len(&self, group: &EcGroupRef) -> usize {
+        point_len(group)
+    }
+
+    /// Size in bytes of a scalar of group, to bound lengths of scalar lists.
+    pub fn scalar_len(&mut self, group: &EcGroupRef) -> Result<usize> {
+        scalar_len(group, &mut self.ctx)
+    }
+
+    pub fn finish(self) -> Result<()> {
+        if self.pos != self.bytes.len() {
+            return Err(ZkError::MalformedProof("trailing bytes"));
+        }
+        Ok(())
+    }
+}
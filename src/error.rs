@@ -0,0 +1,49 @@
+use std::fmt;
+
+use openssl::error::ErrorStack;
+
+
+/// Errors returned by the prove and verify functions.
+///
+/// Verifiers return Ok(false) when a well-formed proof does not verify and
+/// an error when the proof or the parameters cannot be processed at all.
+#[derive(Debug)]
+pub enum ZkError {
+    /// An OpenSSL operation failed
+    OpenSsl(ErrorStack),
+    /// The curves are invalid or are not a supported pair
+    InvalidCurve(&'static str),
+    /// The prover was given inconsistent inputs (e.g. an invalid signature)
+    InvalidInput(&'static str),
+    /// The proof or its public inputs are malformed
+    MalformedProof(&'static str),
+}
+
+pub type Result<T> = std::result::Result<T, ZkError>;
+
+
+impl fmt::Display for ZkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ZkError::OpenSsl(e) => write!(f, "openssl error: {}", e),
+            ZkError::InvalidCurve(msg) => write!(f, "invalid curve: {}", msg),
+            ZkError::InvalidInput(msg) => write!(f, "invalid input: {}", msg),
+            ZkError::MalformedProof(msg) => write!(f, "malformed proof: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ZkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ZkError::OpenSsl(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ErrorStack> for ZkError {
+    fn from(e: ErrorStack) -> Self {
+        ZkError::OpenSsl(e)
+    }
+}
@@ -1,2 +1,3 @@
-pub mod pointAdd;
+pub mod point_add;
+#[allow(clippy::module_inception)]
 pub mod exp;
\ No newline at end of file
@@ -1,14 +1,23 @@
-use rand::{seq::SliceRandom, RngCore};
+use rand::seq::SliceRandom;
 use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
-use openssl::hash::{hash, MessageDigest};
-use openssl::ec::{EcGroup, EcGroupRef, EcPoint, PointConversionForm};
+use openssl::ec::{EcGroupRef, EcPoint, EcPointRef};
 use openssl::bn::{BigNum, BigNumContext};
 
-use crate::commit::pedersen::{Commitment, PedersenParams, generate_random};
-use crate::exp::pointAdd::{PointAddProof, prove_point_add, aggregate_point_add};
-use crate::equality::hash_points;
-use crate::curves::multimult::{MultiMult, Relation};
+use crate::commit::pedersen::{Commitment, PedersenParams, generate_random_with_rng};
+use crate::exp::point_add::{PointAddProof, prove_point_add_with_rng, aggregate_point_add};
+use crate::curves::instances::is_companion;
+use crate::curves::multimult::{Batch, MultiMult, Relation};
+use crate::encoding::{Reader, Writer, TAG_EXP, TAG_EXP_LIST};
+use crate::error::{Result, ZkError};
+use crate::transcript::Transcript;
+
+/// Fiat-Shamir label of the exp proof.
+pub const LABEL_EXP: &[u8] = b"zkattest/exp";
 
 
 
@@ -33,100 +42,184 @@ pub struct ExpProof<'a> {
 
 
 impl<'a> ExpProof<'a> {
+    #[allow(clippy::should_implement_trait)]
     pub fn eq(&self, other: &Self) -> bool {
         
-        let mut ctx = BigNumContext::new().unwrap();
+        let Ok(mut ctx) = BigNumContext::new() else { return false };
         //  define c0
-        let c0 = self.a.eq(self.groupNIST, &other.a, &mut ctx).unwrap() &&
-                self.t_x.eq(self.groupWario, &other.t_x, &mut ctx).unwrap() &&
-                self.t_y.eq(self.groupWario, &other.t_y, &mut ctx).unwrap();
+        let c0 = self.a.eq(self.groupNIST, &other.a, &mut ctx).unwrap_or(false) &&
+                self.t_x.eq(self.groupWario, &other.t_x, &mut ctx).unwrap_or(false) &&
+                self.t_y.eq(self.groupWario, &other.t_y, &mut ctx).unwrap_or(false);
         
         // define r0
-        let cmp_alpha = if self.alpha.is_some() && other.alpha.is_some() {
-                            self.alpha.as_ref().unwrap() == other.alpha.as_ref().unwrap()
-                        } else {
-                            false
-                        };
-
-        let cmp_beta1 = if self.beta1.is_some() && other.beta1.is_some() {
-                            self.beta1.as_ref().unwrap() == other.beta1.as_ref().unwrap()
-                        } else {
-                            false
-                        };
-
-        let cmp_beta2 = if self.beta2.is_some() && other.beta2.is_some() {
-                            self.beta2.as_ref().unwrap() == other.beta2.as_ref().unwrap()
-                        } else {
-                            false
-                        };
-
-        let cmp_beta3 = if self.beta3.is_some() && other.beta3.is_some() {
-                            self.beta3.as_ref().unwrap() == other.beta3.as_ref().unwrap()
-                        } else {
-                            false
-                        };
+        let cmp_alpha = matches!((&self.alpha, &other.alpha), (Some(a), Some(b)) if a == b);
+        let cmp_beta1 = matches!((&self.beta1, &other.beta1), (Some(a), Some(b)) if a == b);
+        let cmp_beta2 = matches!((&self.beta2, &other.beta2), (Some(a), Some(b)) if a == b);
+        let cmp_beta3 = matches!((&self.beta3, &other.beta3), (Some(a), Some(b)) if a == b);
 
         let r0 = cmp_alpha && cmp_beta1 && cmp_beta2 && cmp_beta3;
 
         // define r1
-        let cmp_z = if self.z.is_some() && other.z.is_some() {
-                            self.z.as_ref().unwrap() == other.z.as_ref().unwrap()
-                        } else {
-                            false
-                        };
-
-        let cmp_z2 = if self.z2.is_some() && other.z2.is_some() {
-                            self.z2.as_ref().unwrap() == other.z2.as_ref().unwrap()
-                        } else {
-                            false
-                        };
-
-
-        let cmp_proof = if self.proof.is_some() && other.proof.is_some() {
-                            self.proof.as_ref().unwrap().eq(&other.proof.as_ref().unwrap())
-                        } else {
-                            false
-                        };
-
-        let cmp_r1 = if self.r1.is_some() && other.r1.is_some() {
-                            self.r1.as_ref().unwrap() == other.r1.as_ref().unwrap()
-                        } else {
-                            false
-                        };
-        
-        let cmp_r2 = if self.r2.is_some() && other.r2.is_some() {
-                            self.r2.as_ref().unwrap() == other.r2.as_ref().unwrap()
-                        } else {
-                            false
-                        };
+        let cmp_z = matches!((&self.z, &other.z), (Some(a), Some(b)) if a == b);
+        let cmp_z2 = matches!((&self.z2, &other.z2), (Some(a), Some(b)) if a == b);
+        let cmp_proof = matches!((&self.proof, &other.proof), (Some(a), Some(b)) if a.eq(b));
+        let cmp_r1 = matches!((&self.r1, &other.r1), (Some(a), Some(b)) if a == b);
+        let cmp_r2 = matches!((&self.r2, &other.r2), (Some(a), Some(b)) if a == b);
 
         let r1 = cmp_z && cmp_z2 && cmp_proof && cmp_r1 && cmp_r2;
 
 
-        r0 && (r0 || r1)
+        c0 && (r0 || r1)
+    }
+
+    pub(crate) fn write(&self, w: &mut Writer) -> Result<()> {
+        w.point(self.groupNIST, &self.a)?;
+        w.point(self.groupWario, &self.t_x)?;
+        w.point(self.groupWario, &self.t_y)?;
+
+        match (&self.alpha, &self.beta1, &self.beta2, &self.beta3, &self.z, &self.z2, &self.proof, &self.r1, &self.r2) {
+            (Some(alpha), Some(beta1), Some(beta2), Some(beta3), None, None, None, None, None) => {
+                w.flag(0);
+                w.scalar(self.groupNIST, alpha)?;
+                w.scalar(self.groupNIST, beta1)?;
+                w.scalar(self.groupWario, beta2)?;
+                w.scalar(self.groupWario, beta3)
+            },
+            (None, None, None, None, Some(z), Some(z2), Some(proof), Some(r1), Some(r2)) => {
+                w.flag(1);
+                w.scalar(self.groupNIST, z)?;
+                w.scalar(self.groupNIST, z2)?;
+                w.scalar(self.groupWario, r1)?;
+                w.scalar(self.groupWario, r2)?;
+                proof.write(w)
+            },
+            _ => Err(ZkError::MalformedProof("params not found")),
+        }
+    }
+
+    pub(crate) fn read(r: &mut Reader, groupNIST: &'a EcGroupRef, groupWario: &'a EcGroupRef) -> Result<Self> {
+        let a = r.point(groupNIST)?;
+        let t_x = r.point(groupWario)?;
+        let t_y = r.point(groupWario)?;
+
+        let mut pi = ExpProof {
+            groupNIST,
+            groupWario,
+            a,
+            t_x,
+            t_y,
+            alpha: None,
+            beta1: None,
+            beta2: None,
+            beta3: None,
+            z: None,
+            z2: None,
+            proof: None,
+            r1: None,
+            r2: None,
+        };
+
+        match r.flag()? {
+            0 => {
+                pi.alpha = Some(r.scalar(groupNIST)?);
+                pi.beta1 = Some(r.scalar(groupNIST)?);
+                pi.beta2 = Some(r.scalar(groupWario)?);
+                pi.beta3 = Some(r.scalar(groupWario)?);
+            },
+            1 => {
+                pi.z = Some(r.scalar(groupNIST)?);
+                pi.z2 = Some(r.scalar(groupNIST)?);
+                pi.r1 = Some(r.scalar(groupWario)?);
+                pi.r2 = Some(r.scalar(groupWario)?);
+                pi.proof = Some(PointAddProof::read(r, groupWario)?);
+            },
+            _ => return Err(ZkError::MalformedProof("unknown response type")),
+        }
+
+        Ok(pi)
+    }
+
+    /// Encodes one repetition as version || tag (no hash id, see encoding) || A || Tx || Ty || flag || responses,
+    /// flag 0 for (alpha, beta1, beta2, beta3) and 1 for (z, z2, r1, r2, pointAdd proof)
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut w = Writer::new(TAG_EXP)?;
+        self.write(&mut w)?;
+        Ok(w.finish())
+    }
+
+    pub fn from_bytes(groupNIST: &'a EcGroupRef, groupWario: &'a EcGroupRef, bytes: &[u8]) -> Result<Self> {
+        let mut r = Reader::new(bytes, TAG_EXP)?;
+        let pi = Self::read(&mut r, groupNIST, groupWario)?;
+        r.finish()?;
+        Ok(pi)
     }
 }
 
 
+pub(crate) fn write_exp(pi: &[ExpProof], w: &mut Writer) -> Result<()> {
+    w.length(pi.len())?;
+    pi.iter().try_for_each(|pi_i| pi_i.write(w))
+}
+
+pub(crate) fn read_exp<'a>(r: &mut Reader, groupNIST: &'a EcGroupRef, groupWario: &'a EcGroupRef) -> Result<Vec<ExpProof<'a>>> {
+    let len = r.length(1)?;
+    (0..len).map(|_| ExpProof::read(r, groupNIST, groupWario)).collect()
+}
+
+/// Encodes all the repetitions returned by prov_exp as version || tag (no hash id, see encoding) || length || repetitions
+pub fn exp_to_bytes(pi: &[ExpProof]) -> Result<Vec<u8>> {
+    let mut w = Writer::new(TAG_EXP_LIST)?;
+    write_exp(pi, &mut w)?;
+    Ok(w.finish())
+}
 
-pub fn padded_bits(val: &BigNum, length: usize) -> Vec<bool> {
+pub fn exp_from_bytes<'a>(groupNIST: &'a EcGroupRef, groupWario: &'a EcGroupRef, bytes: &[u8]) -> Result<Vec<ExpProof<'a>>> {
+    let mut r = Reader::new(bytes, TAG_EXP_LIST)?;
+    let pi = read_exp(&mut r, groupNIST, groupWario)?;
+    r.finish()?;
+    Ok(pi)
+}
+
+
+
+pub fn padded_bits(val: &BigNum, length: usize) -> Result<Vec<bool>> {
     
     let mut ret = Vec::with_capacity(length);
 
-    let mut num = val.as_ref().to_owned().unwrap();
+    let mut num = val.as_ref().to_owned()?;
 
-    for i in 0..length {
-        let int = num.to_owned().unwrap();
+    for _ in 0..length {
+        let int = num.to_owned()?;
         let bit_set = num.is_bit_set(0);
-        num.rshift1(&int).unwrap();
-        //println!("1011 {}-th is {}: ", i, bit_set);
+        num.rshift1(&int)?;
         ret.push(bit_set)
     }
-    //println!("Ret vec is {:?}: ", ret);
-    ret
+    Ok(ret)
 } 
 
+// Each repetition takes one bit of the challenge, a scalar mod the NIST order:
+// bits beyond its bit length would always be 0 and could be answered without
+// the witness
+fn check_secparam(paramsNIST: &PedersenParams, secparam: usize) -> Result<()> {
+    let mut ctx = BigNumContext::new()?;
+    let mut order_curve = BigNum::new()?;
+    paramsNIST.c.order(&mut order_curve, &mut ctx)?;
+
+    if secparam == 0 {
+        return Err(ZkError::InvalidInput("security parameter must be positive"));
+    }
+    if secparam > order_curve.num_bits() as usize {
+        return Err(ZkError::InvalidInput("security parameter exceeds the challenge bits"));
+    }
+    Ok(())
+}
+
 pub fn generate_indices(limit: usize) -> Vec<usize> {
+    generate_indices_with_rng(limit, &mut OsRng)
+}
+
+/// generate_indices shuffling with rng.
+pub fn generate_indices_with_rng(limit: usize, rng: &mut (impl RngCore + CryptoRng)) -> Vec<usize> {
     
     /*  We are not using the Algorithm P. The algorithm below is based on 
     Durstenfeld's algorithm for the [Fisher–Yates shuffle](https://en.wikipedia.org/wiki/Fisher%E2%80%93Yates_shuffle#The_modern_algorithm)
@@ -135,19 +228,51 @@ pub fn generate_indices(limit: usize) -> Vec<usize> {
     */
 
     let mut ret = (0..limit).collect::<Vec<usize>>();
-    let mut rng = OsRng; // CSPRNG  
 
-    ret.shuffle(&mut rng);
+    ret.shuffle(rng);
 
     ret
 }
 
+// Challenge c = H(transcript, context, paramsNIST, paramsWario, Cs, Cx, Cy, Q, (A, Tx, Ty) for each repetition),
+// whose bits select the response of each repetition
+#[allow(clippy::too_many_arguments)]
+fn exp_challenge(
+    transcript: &mut Transcript,
+    context: Option<&[u8]>,
+    paramsNIST: &PedersenParams,
+    paramsWario: &PedersenParams,
+    Cs: &EcPoint,
+    Px: &EcPoint,
+    Py: &EcPoint,
+    Q: Option<&EcPoint>,
+    A_T: &[(&EcPoint, &EcPoint, &EcPoint)]
+) -> Result<BigNum> {
+    transcript.append_message(b"protocol", LABEL_EXP);
+    transcript.append_context(context);
+    transcript.append_params(b"paramsNIST", paramsNIST)?;
+    transcript.append_params(b"paramsWario", paramsWario)?;
+    transcript.append_point(b"Cs", paramsNIST.c, Cs)?;
+    transcript.append_point(b"Cx", paramsWario.c, Px)?;
+    transcript.append_point(b"Cy", paramsWario.c, Py)?;
+    if let Some(Q) = Q {
+        transcript.append_point(b"Q", paramsNIST.c, Q)?;
+    }
+    transcript.append_u64(b"repetitions", A_T.len() as u64);
+    for (A, Tx, Ty) in A_T {
+        transcript.append_point(b"A", paramsNIST.c, A)?;
+        transcript.append_point(b"Tx", paramsWario.c, Tx)?;
+        transcript.append_point(b"Ty", paramsWario.c, Ty)?;
+    }
+    transcript.challenge_scalar(b"c", paramsNIST.c)
+}
+
 /**
  * ZK(s, r, rx, ry: sR = P + Q and Cs = sR + rS and Cx = Px G + rx H and Cy = Py G + ry H) [Q is optional]
  * paramsNIST.g = R [Point R must be populated in the g field of paramsNIST]
  *
  * @param paramsNIST NIST params
- * @param paramsWario Wario params
+ * @param paramsWario Wario params, the Tom companion curve of paramsNIST (Tom256, Tom384 or Tom521)
  * @param s secret
  * @param Cs: commitment to the secret with params NIST
  * @param rx
@@ -155,11 +280,15 @@ pub fn generate_indices(limit: usize) -> Vec<usize> {
  * @param Px
  * @param Py
  * @param Q an optional public point
- * @param secparam Soundness error
+ * @param secparam number of repetitions, the soundness error is 2^-secparam;
+ *                 between 1 and the bit length of the NIST order
+ * @param context caller-supplied context (e.g. a server nonce) the proof is bound to
+ * @param transcript transcript shared with the enclosing proof
  */
+#[allow(clippy::too_many_arguments)]
 pub fn prov_exp<'a>(
-    paramsNIST: &'a PedersenParams<'a>,
-    paramsWario: &'a PedersenParams<'a>,
+    paramsNIST: &PedersenParams<'a>,
+    paramsWario: &PedersenParams<'a>,
     s: BigNum,
     Cs: Commitment,
     P: EcPoint,
@@ -167,370 +296,545 @@ pub fn prov_exp<'a>(
     Py: Commitment,
     secparam: usize,
     Q: Option<EcPoint>,
-) -> Vec<ExpProof<'a>> {
-    let mut ctx = BigNumContext::new().unwrap();
-
-    let mut alpha : Vec<BigNum> = Vec::with_capacity(secparam);
-    let mut r : Vec<BigNum> = Vec::with_capacity(secparam);
-    let mut T : Vec<EcPoint> = Vec::with_capacity(secparam);
-    let mut A : Vec<EcPoint> = Vec::with_capacity(secparam);
-    let mut Tx : Vec<Commitment> = Vec::with_capacity(secparam);
-    let mut Ty : Vec<Commitment> = Vec::with_capacity(secparam);
-
-    let mut NIST_order_curve = BigNum::new().unwrap();
-    paramsNIST.c.order(&mut NIST_order_curve, &mut ctx).unwrap();
-    
+    context: Option<&[u8]>,
+    transcript: &mut Transcript,
+) -> Result<Vec<ExpProof<'a>>> {
+    prov_exp_with_rng(paramsNIST, paramsWario, s, Cs, P, Px, Py, secparam, Q, context, transcript, &mut OsRng)
+}
 
-    for i in 0..secparam {
-        // Generate random value
-        let alpha_rand = generate_random(&NIST_order_curve).unwrap();
-        alpha.push(alpha_rand.to_owned().unwrap());
-        
-        // Generate random value
-        let r_rand = generate_random(&NIST_order_curve).unwrap();
-        r.push(r_rand.to_owned().unwrap());
-        
-        let mut g_times_alpha_rand = EcPoint::new(&paramsNIST.c).unwrap();
-        g_times_alpha_rand.mul_generator(&paramsNIST.c, &alpha_rand, &mut ctx);
-        T.push(g_times_alpha_rand.to_owned(&paramsNIST.c).unwrap());
+/// prov_exp with the randomness drawn from rng.
+#[allow(clippy::too_many_arguments)]
+pub fn prov_exp_with_rng<'a>(
+    paramsNIST: &PedersenParams<'a>,
+    paramsWario: &PedersenParams<'a>,
+    s: BigNum,
+    Cs: Commitment,
+    P: EcPoint,
+    Px: Commitment,
+    Py: Commitment,
+    secparam: usize,
+    Q: Option<EcPoint>,
+    context: Option<&[u8]>,
+    transcript: &mut Transcript,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Result<Vec<ExpProof<'a>>> {
+    // Coordinates of points of paramsNIST.c must be scalars of paramsWario.c
+    if !is_companion(paramsNIST.c, paramsWario.c)? {
+        return Err(ZkError::InvalidCurve("curves not compatible"));
+    }
+    check_secparam(paramsNIST, secparam)?;
 
-        //g_times_alpha_rand + r * h
-        let mut r_h = EcPoint::new(&paramsNIST.c).unwrap();
-        r_h.mul(&paramsNIST.c, &paramsNIST.h, &r_rand, &mut ctx);
-        let mut g_alpha_plus_r_h = EcPoint::new(&paramsNIST.c).unwrap();
-        g_alpha_plus_r_h.add(&paramsNIST.c, &g_times_alpha_rand, &r_h, &mut ctx);
-        A.push(g_alpha_plus_r_h.to_owned(&paramsNIST.c).unwrap());
+    let mut ctx = BigNumContext::new()?;
 
-        // Build Tx and Ty
-        let infinity_R = g_times_alpha_rand.is_infinity(&paramsNIST.c);
-        assert!(!infinity_R, "g.alpha is at infinity");
+    let mut NIST_order_curve = BigNum::new()?;
+    paramsNIST.c.order(&mut NIST_order_curve, &mut ctx)?;
 
-        let mut x = BigNum::new().unwrap();
-        let mut y = BigNum::new().unwrap();
-    
-        g_times_alpha_rand.affine_coordinates_gfp(&paramsNIST.c, &mut x, &mut y, &mut ctx).unwrap();
+    // Each repetition draws from its own ChaCha20 seeded from rng, so that the
+    // proof is the same whether the repetitions run in order or in parallel
+    let seeds: Vec<[u8; 32]> = (0..secparam).map(|_| {
+        let mut seed = [0u8; 32];
+        rng.fill_bytes(&mut seed);
+        seed
+    }).collect();
 
-        Tx.push(paramsWario.commit(&x));
-        Ty.push(paramsWario.commit(&y));
-    }
+    let reps = map_repetitions(seeds, |_, seed| {
+        commit_repetition(paramsNIST, paramsWario, &NIST_order_curve, ChaCha20Rng::from_seed(seed))
+    })?;
 
-    // Compute challenge c = H (Cx, Cy, A, Tx, Ty)
-    let mut arr : Vec<&EcPoint> = Vec::with_capacity(3*secparam+2);
-    let mut groups : Vec<&EcGroupRef> = Vec::with_capacity(3*secparam+2);
-    arr.push(&Px.p);
-    groups.push(&paramsWario.c);
-    arr.push(&Py.p);
-    groups.push(&paramsWario.c);
-    for i in 0..secparam {
-        arr.push(&A[i]);
-        groups.push(&paramsNIST.c);
-        arr.push(&Tx[i].p);
-        groups.push(&paramsWario.c);
-        arr.push(&Ty[i].p);
-        groups.push(&paramsWario.c);
-    }
-    let mut challenge = hash_points(MessageDigest::sha256(), &groups, &arr).unwrap();
+    // Compute challenge c = H (transcript, Cs, Cx, Cy, Q, A, Tx, Ty)
+    let A_T: Vec<_> = reps.iter().map(|rep| (&rep.A, &rep.Tx.p, &rep.Ty.p)).collect();
+    let challenge = exp_challenge(transcript, context, paramsNIST, paramsWario, &Cs.p, &Px.p, &Py.p, Q.as_ref(), &A_T)?;
+    let challenge_bits = padded_bits(&challenge, secparam)?;
 
-    let mut all_proofs : Vec<ExpProof> = Vec::with_capacity(secparam);
-    let mut proof : ExpProof;
-    for i in 0..secparam {
-        if challenge.is_bit_set(0) {
-            proof = ExpProof{
+    let transcript: &Transcript = transcript;
+    map_repetitions(reps, |i, rep| {
+        if challenge_bits[i] {
+            Ok(ExpProof{
                 groupNIST: paramsNIST.c,
                 groupWario: paramsWario.c,
-                a: A[i].to_owned(&paramsNIST.c).unwrap(),
-                t_x: Tx[i].p.to_owned(&paramsWario.c).unwrap(),
-                t_y: Ty[i].p.to_owned(&paramsWario.c).unwrap(),
+                a: rep.A,
+                t_x: rep.Tx.p,
+                t_y: rep.Ty.p,
                 // Response 1
-                alpha: Some(alpha[i].to_owned().unwrap()),
-                beta1: Some(r[i].to_owned().unwrap()),
-                beta2: Some(Tx[i].r.to_owned().unwrap()),
-                beta3: Some(Ty[i].r.to_owned().unwrap()),
+                alpha: Some(rep.alpha),
+                beta1: Some(rep.r),
+                beta2: Some(rep.Tx.r),
+                beta3: Some(rep.Ty.r),
                 // Response 2
                 z: None,
                 z2: None,
                 proof: None,
                 r1: None,
                 r2: None,
-            };
+            })
         } else {
-            // z = alpha - s
-            let mut z = BigNum::new().unwrap();
+            respond_repetition(paramsNIST, paramsWario, &s, &Cs, &P, &Px, &Py, Q.as_ref(), &NIST_order_curve, rep, &mut transcript.fork(b"repetition", i as u64))
+        }
+    })
+}
 
-            let mut order_curve_wario = BigNum::new().unwrap();
-            paramsWario.c.order(&mut order_curve_wario, &mut ctx).unwrap();
-            let mut ss = BigNum::new().unwrap();
-            ss.nnmod(&s, &order_curve_wario, &mut ctx).unwrap();
+// Randomness and commitments of one repetition, before the challenge
+struct Repetition<'p> {
+    alpha: BigNum,
+    r: BigNum,
+    T: EcPoint,
+    A: EcPoint,
+    Tx: Commitment<'p>,
+    Ty: Commitment<'p>,
+    rng: ChaCha20Rng,
+}
 
-            //z.mod_sub(&alpha[i], &s, &order_curve_wario, &mut ctx).unwrap();
-            z.checked_sub(&alpha[i], &ss).unwrap();
+// T = alpha g, A = T + r h and the commitments Tx, Ty to the coordinates of T
+fn commit_repetition<'p>(
+    paramsNIST: &PedersenParams,
+    paramsWario: &'p PedersenParams,
+    NIST_order_curve: &BigNum,
+    mut rng: ChaCha20Rng,
+) -> Result<Repetition<'p>> {
 
-            // T1 = g.z
-            let mut T1 = EcPoint::new(&paramsNIST.c).unwrap();
-            T1.mul_generator(&paramsNIST.c, &z, &mut ctx);
+    let mut ctx = BigNumContext::new()?;
 
-            if Q.is_some() {
-                let T1_int = T1.to_owned(&paramsNIST.c).unwrap();
-                T1.add(&paramsNIST.c, &T1_int, &Q.as_ref().unwrap(), &mut ctx);
-            }
+    // Generate random values
+    let alpha = generate_random_with_rng(NIST_order_curve, &mut rng)?;
+    let r = generate_random_with_rng(NIST_order_curve, &mut rng)?;
 
-            let infinity_T1 = T1.is_infinity(&paramsNIST.c);
-            assert!(!infinity_T1, "T1 is at infinity");
-    
-            let mut x = BigNum::new().unwrap();
-            let mut y = BigNum::new().unwrap();
-        
-            T1.affine_coordinates_gfp(&paramsNIST.c, &mut x, &mut y, &mut ctx).unwrap();
-
-            let T1x = paramsWario.commit(&x);
-            let T1y = paramsWario.commit(&y);
-            // alpha R - s R = z R => T1 + P = T
-            let pointAddProof = prove_point_add(
-                paramsNIST,
-                paramsWario,
-                T1.to_owned(&paramsNIST.c).unwrap(),
-                P.to_owned(&paramsNIST.c).unwrap(),
-                T[i].to_owned(&paramsNIST.c).unwrap(),
-                T1x.to_owned(),
-                T1y.to_owned(),
-                Px.to_owned(),
-                Py.to_owned(),
-                Tx[i].to_owned(),
-                Ty[i].to_owned()
-            );
-
-            // z2 = r[i as number].sub(Cs.r)
-            let mut z2 = BigNum::new().unwrap();
-            //z2.mod_sub(&r[i], &Cs.r, &order_curve_wario, &mut ctx).unwrap();
-            z2.checked_sub(&r[i], &Cs.r).unwrap();
-
-            proof = ExpProof{
-                groupNIST: paramsNIST.c,
-                groupWario: paramsWario.c,
-                a: A[i].to_owned(&paramsNIST.c).unwrap(),
-                t_x: Tx[i].p.to_owned(&paramsWario.c).unwrap(),
-                t_y: Ty[i].p.to_owned(&paramsWario.c).unwrap(),
-                // Response 1
-                alpha: None,
-                beta1: None,
-                beta2: None,
-                beta3: None,
-                // Response 2
-                z: Some(z),
-                z2: Some(z2),
-                proof: Some(pointAddProof),
-                r1: Some(T1x.r),
-                r2: Some(T1y.r)
-            };
+    let T = paramsNIST.mul_g(&alpha)?;
 
-        }
+    //g_times_alpha_rand + r * h
+    let r_h = paramsNIST.mul_h(&r)?;
+    let mut A = EcPoint::new(paramsNIST.c)?;
+    A.add(paramsNIST.c, &T, &r_h, &mut ctx)?;
+
+    // Build Tx and Ty
+    if T.is_infinity(paramsNIST.c) {
+        return Err(ZkError::InvalidInput("g.alpha is at infinity"));
+    }
+
+    let mut x = BigNum::new()?;
+    let mut y = BigNum::new()?;
 
-        all_proofs.push(proof);
-        let challenge_int = challenge.to_owned().unwrap();
-        challenge.rshift1(&challenge_int).unwrap();
+    T.affine_coordinates_gfp(paramsNIST.c, &mut x, &mut y, &mut ctx)?;
+
+    let Tx = paramsWario.commit_with_rng(&x, &mut rng)?;
+    let Ty = paramsWario.commit_with_rng(&y, &mut rng)?;
+
+    Ok(Repetition { alpha, r, T, A, Tx, Ty, rng })
+}
+
+// Response 2: z = alpha - s and a proof that T1 + P = T for T1 = z g (+ Q)
+#[allow(clippy::too_many_arguments)]
+fn respond_repetition<'a>(
+    paramsNIST: &PedersenParams<'a>,
+    paramsWario: &PedersenParams<'a>,
+    s: &BigNum,
+    Cs: &Commitment,
+    P: &EcPoint,
+    Px: &Commitment,
+    Py: &Commitment,
+    Q: Option<&EcPoint>,
+    NIST_order_curve: &BigNum,
+    mut rep: Repetition,
+    transcript: &mut Transcript,
+) -> Result<ExpProof<'a>> {
+
+    let mut ctx = BigNumContext::new()?;
+
+    // z = alpha - s, reduced mod the NIST order so that it has a canonical encoding
+    let mut z = BigNum::new()?;
+    z.mod_sub(&rep.alpha, s, NIST_order_curve, &mut ctx)?;
+
+    // T1 = g.z
+    let mut T1 = paramsNIST.mul_g(&z)?;
+
+    if let Some(Q) = Q {
+        let T1_int = T1.to_owned(paramsNIST.c)?;
+        T1.add(paramsNIST.c, &T1_int, Q, &mut ctx)?;
     }
 
-    all_proofs
+    if T1.is_infinity(paramsNIST.c) {
+        return Err(ZkError::InvalidInput("T1 is at infinity"));
+    }
+
+    let mut x = BigNum::new()?;
+    let mut y = BigNum::new()?;
+
+    T1.affine_coordinates_gfp(paramsNIST.c, &mut x, &mut y, &mut ctx)?;
+
+    let T1x = paramsWario.commit_with_rng(&x, &mut rep.rng)?;
+    let T1y = paramsWario.commit_with_rng(&y, &mut rep.rng)?;
+    // alpha R - s R = z R => T1 + P = T
+    let pointAddProof = prove_point_add_with_rng(
+        paramsNIST,
+        paramsWario,
+        T1.to_owned(paramsNIST.c)?,
+        P.as_ref().to_owned(paramsNIST.c)?,
+        rep.T,
+        T1x.to_owned()?,
+        T1y.to_owned()?,
+        Px.to_owned()?,
+        Py.to_owned()?,
+        rep.Tx.to_owned()?,
+        rep.Ty.to_owned()?,
+        transcript,
+        &mut rep.rng
+    )?;
+
+    // z2 = r[i as number].sub(Cs.r)
+    let mut z2 = BigNum::new()?;
+    z2.mod_sub(&rep.r, &Cs.r, NIST_order_curve, &mut ctx)?;
+
+    Ok(ExpProof{
+        groupNIST: paramsNIST.c,
+        groupWario: paramsWario.c,
+        a: rep.A,
+        t_x: rep.Tx.p,
+        t_y: rep.Ty.p,
+        // Response 1
+        alpha: None,
+        beta1: None,
+        beta2: None,
+        beta3: None,
+        // Response 2
+        z: Some(z),
+        z2: Some(z2),
+        proof: Some(pointAddProof),
+        r1: Some(T1x.r),
+        r2: Some(T1y.r)
+    })
+}
+
+// Maps f over the repetitions with their indices, on the rayon thread pool
+// with the rayon feature
+#[cfg(feature = "rayon")]
+fn map_repetitions<I: Send, T: Send>(
+    reps: Vec<I>,
+    f: impl Fn(usize, I) -> Result<T> + Send + Sync,
+) -> Result<Vec<T>> {
+    reps.into_par_iter().enumerate().map(|(i, rep)| f(i, rep)).collect()
+}
+
+#[cfg(not(feature = "rayon"))]
+fn map_repetitions<I, T>(
+    reps: Vec<I>,
+    f: impl Fn(usize, I) -> Result<T>,
+) -> Result<Vec<T>> {
+    reps.into_iter().enumerate().map(|(i, rep)| f(i, rep)).collect()
 }
 
 
+/**
+ * Verifies every repetition of pi, in order, batched in one MultiMult per curve.
+ * The proof must have exactly secparam repetitions, with secparam between 1
+ * and the bit length of the NIST order.
+ *
+ * @param paramsNIST NIST params
+ * @param paramsWario Wario params
+ * @param Clambda commitment to the secret with params NIST
+ * @param Px commitment to P.x with params Wario
+ * @param Py commitment to P.y with params Wario
+ * @param pi repetitions returned by prov_exp
+ * @param secparam Soundness error
+ * @param Q an optional public point
+ * @param context context the proof was made for
+ * @param transcript transcript shared with the enclosing proof
+ */
+#[allow(clippy::too_many_arguments)]
 pub fn verify_exp<'a>(
     paramsNIST: &'a PedersenParams<'a>,
     paramsWario: &'a PedersenParams<'a>,
     Clambda : EcPoint,
     Px: EcPoint,
     Py: EcPoint,
-    pi: &'a Vec<ExpProof<'a>>,
+    pi: &'a [ExpProof<'a>],
     secparam: usize,
     Q: Option<EcPoint>,
-) -> bool {
-    let mut ctx = BigNumContext::new().unwrap();
-    
-    assert!(!(secparam > pi.len()), "security level not achieved");
-
-    let mut multiW = MultiMult::new(&paramsWario.c);
-    let mut multiN = MultiMult::new(&paramsNIST.c);
-
-    multiW.add_known(paramsWario.g.to_owned(&paramsWario.c).unwrap());
-    multiW.add_known(paramsWario.h.to_owned(&paramsWario.c).unwrap());
-    multiN.add_known(paramsNIST.g.to_owned(&paramsNIST.c).unwrap());
-    multiN.add_known(paramsNIST.h.to_owned(&paramsNIST.c).unwrap());
-    multiN.add_known(Clambda.to_owned(&paramsNIST.c).unwrap());
-
-    // Compute challenge c = H (Cx, Cy, A, Tx, Ty)
-    let mut arr : Vec<&EcPoint> = Vec::with_capacity(3*secparam+2);
-    let mut groups : Vec<&EcGroupRef> = Vec::with_capacity(3*secparam+2);
-    arr.push(&Px);
-    groups.push(&paramsWario.c);
-    arr.push(&Py);
-    groups.push(&paramsWario.c);
-    for i in 0..secparam {
-        arr.push(&pi[i].a);
-        groups.push(&paramsNIST.c);
-        arr.push(&pi[i].t_x);
-        groups.push(&paramsWario.c);
-        arr.push(&pi[i].t_y);
-        groups.push(&paramsWario.c);
+    context: Option<&[u8]>,
+    transcript: &mut Transcript,
+) -> Result<bool> {
+    let mut multiN = MultiMult::new(paramsNIST.c);
+    let mut multiW = MultiMult::new(paramsWario.c);
+
+    let ok = aggregate_exp(paramsNIST, paramsWario, Clambda, Px, Py, pi, secparam, Q, context, &mut multiN, &mut multiW, transcript)?;
+
+    if !ok {
+        return Ok(false)
     }
-    let mut challenge = hash_points(MessageDigest::sha256(), &groups, &arr).unwrap();
 
-    let indices = generate_indices(pi.len());
-    let challenge_bits = padded_bits(&challenge, pi.len());
+    Ok(multiW.evaluate()?.is_infinity(paramsWario.c) && multiN.evaluate()?.is_infinity(paramsNIST.c))
+}
 
-    for j in 0..secparam {
-        let i = indices[j];
+#[allow(clippy::too_many_arguments)]
+pub fn aggregate_exp<'a>(
+    paramsNIST: &'a PedersenParams<'a>,
+    paramsWario: &'a PedersenParams<'a>,
+    Clambda : EcPoint,
+    Px: EcPoint,
+    Py: EcPoint,
+    pi: &'a [ExpProof<'a>],
+    secparam: usize,
+    Q: Option<EcPoint>,
+    context: Option<&[u8]>,
+    multiN: &mut MultiMult,
+    multiW: &mut MultiMult,
+    transcript: &mut Transcript,
+) -> Result<bool> {
+    if !is_companion(paramsNIST.c, paramsWario.c)? {
+        return Err(ZkError::InvalidCurve("curves not compatible"));
+    }
+    check_secparam(paramsNIST, secparam)?;
 
-        if challenge_bits[i] {
-            // Check the corresponding params are not set to None
-
-            let params_not_found =  !(pi[i].alpha.is_some() &&
-                pi[i].beta1.is_some() &&
-                pi[i].beta2.is_some() &&
-                pi[i].beta3.is_some());
-            
-            assert!(!params_not_found, "params not found");
-
-            let alpha = pi[i].alpha.as_deref().unwrap();
-            let beta1 = pi[i].beta1.as_deref().unwrap();
-            let beta2 = pi[i].beta2.as_deref().unwrap();
-            let beta3 = pi[i].beta3.as_deref().unwrap();
-            let a = pi[i].a.as_ref();
-            let t_x = pi[i].t_x.as_ref();
-            let t_y = pi[i].t_y.as_ref();
-
-            let mut T = EcPoint::new(&paramsNIST.c).unwrap();
-            T.mul_generator(&paramsNIST.c, &alpha, &mut ctx);
-
-            let mut relA = Relation::new(paramsNIST.c);
-
-            // Compute -A
-            let minus_1 = BigNum::from_dec_str("-1").unwrap(); 
-            let mut minus_a = EcPoint::new(&paramsNIST.c).unwrap();
-            minus_a.mul(&paramsNIST.c, a, &minus_1, &mut ctx).unwrap();
-            relA.insert_m(
-                &[T.to_owned(&paramsNIST.c).unwrap(),
-                paramsNIST.h.to_owned(&paramsNIST.c).unwrap(),
-                minus_a],
-                &[BigNum::from_u32(1).unwrap(),
-                beta1.to_owned().unwrap(),
-                BigNum::from_u32(1).unwrap()]);
-
-            relA.drain(&mut multiN);
-
-            // Build Tx and Ty
-            let infinity_T = T.is_infinity(&paramsNIST.c);
-            assert!(!infinity_T, "T is at infinity");
-
-            let mut x = BigNum::new().unwrap();
-            let mut y = BigNum::new().unwrap();
-        
-            T.affine_coordinates_gfp(&paramsNIST.c, &mut x, &mut y, &mut ctx).unwrap();
-
-            let mut relTx = Relation::new(paramsWario.c); 
-            let mut relTy = Relation::new(paramsWario.c); 
-
-            // Compute -Tx
-            let minus_1 = BigNum::from_dec_str("-1").unwrap(); 
-            let mut minus_t_x = EcPoint::new(&paramsWario.c).unwrap();
-            minus_t_x.mul(&paramsWario.c, t_x, &minus_1, &mut ctx).unwrap();
-            relTx.insert_m(
-                &[paramsWario.g.to_owned(&paramsWario.c).unwrap(),
-                paramsWario.h.to_owned(&paramsWario.c).unwrap(),
-                minus_t_x],
-                &[x,
-                beta2.to_owned().unwrap(),
-                BigNum::from_u32(1).unwrap()]);
-
-            // Compute -Ty
-            let minus_1 = BigNum::from_dec_str("-1").unwrap(); 
-            let mut minus_t_y = EcPoint::new(&paramsWario.c).unwrap();
-            minus_t_y.mul(&paramsWario.c, t_y, &minus_1, &mut ctx).unwrap();
-            relTx.insert_m(
-                &[paramsWario.g.to_owned(&paramsWario.c).unwrap(),
-                paramsWario.h.to_owned(&paramsWario.c).unwrap(),
-                minus_t_y],
-                &[y,
-                beta3.to_owned().unwrap(),
-                BigNum::from_u32(1).unwrap()]);
-
-            relTx.drain(&mut multiW);
-            relTy.drain(&mut multiW);
-        } else {
-            // Check the corresponding params are not set to NoneQ
-
-            let params_not_found =  !(pi[i].z.is_some() &&
-            pi[i].z2.is_some() &&
-            pi[i].proof.is_some() &&
-            pi[i].r1.is_some() &&
-            pi[i].r2.is_some());
-            
-            assert!(!params_not_found, "params not found");
-
-            let z = pi[i].z.as_deref().unwrap();
-            let z2 = pi[i].z2.as_deref().unwrap();
-            let r1 = pi[i].r1.as_deref().unwrap();
-            let r2 = pi[i].r2.as_deref().unwrap();
-            let a = pi[i].a.as_ref();
-            let t_x = pi[i].t_x.as_ref();
-            let t_y = pi[i].t_y.as_ref();
-
-            let mut T1 = EcPoint::new(&paramsNIST.c).unwrap();
-            T1.mul_generator(&paramsNIST.c, &z, &mut ctx);
-
-            let mut relA = Relation::new(paramsNIST.c);
-
-            // Compute -A
-            let minus_1 = BigNum::from_dec_str("-1").unwrap(); 
-            let mut minus_a = EcPoint::new(&paramsNIST.c).unwrap();
-            minus_a.mul(&paramsNIST.c, a, &minus_1, &mut ctx).unwrap();
-            relA.insert_m(
-                &[T1.to_owned(&paramsNIST.c).unwrap(),
-                Clambda.to_owned(&paramsNIST.c).unwrap(),
-                minus_a,
-                paramsNIST.h.to_owned(&paramsNIST.c).unwrap()],
-                &[BigNum::from_u32(1).unwrap(),
-                BigNum::from_u32(1).unwrap(),
-                BigNum::from_u32(1).unwrap(),
-                z2.to_owned().unwrap()]);
-
-            relA.drain(&mut multiN);
-
-            if Q.is_some() {
-                let T1_int = T1.to_owned(&paramsNIST.c).unwrap();
-                T1.add(&paramsNIST.c, &T1_int, &Q.as_ref().unwrap(), &mut ctx);
-            }
-
-            let infinity_T1 = T1.is_infinity(&paramsNIST.c);
-            assert!(!infinity_T1, "T1 is at infinity");
+    if pi.len() != secparam {
+        return Err(ZkError::MalformedProof("number of repetitions does not match the security level"));
+    }
+
+    multiW.add_known(paramsWario.g.to_owned(paramsWario.c)?)?;
+    multiW.add_known(paramsWario.h.to_owned(paramsWario.c)?)?;
+    multiN.add_known(paramsNIST.g.to_owned(paramsNIST.c)?)?;
+    multiN.add_known(paramsNIST.h.to_owned(paramsNIST.c)?)?;
+    multiN.add_known(Clambda.to_owned(paramsNIST.c)?)?;
+    multiW.add_known(Px.to_owned(paramsWario.c)?)?;
+    multiW.add_known(Py.to_owned(paramsWario.c)?)?;
+
+    // Compute challenge c = H (transcript, Cs, Cx, Cy, Q, A, Tx, Ty)
+    let A_T: Vec<_> = pi.iter().map(|pi_i| (&pi_i.a, &pi_i.t_x, &pi_i.t_y)).collect();
+    let challenge = exp_challenge(transcript, context, paramsNIST, paramsWario, &Clambda, &Px, &Py, Q.as_ref(), &A_T)?;
+
+    let challenge_bits = padded_bits(&challenge, pi.len())?;
+
+    #[cfg(not(feature = "rayon"))]
+    for i in 0..pi.len() {
+        let ok = verify_repetition(paramsNIST, paramsWario, &Clambda, &Px, &Py, Q.as_deref(), &pi[i], challenge_bits[i], multiN, multiW, &mut transcript.fork(b"repetition", i as u64))?;
+        if !ok {
+            return Ok(false)
+        }
+    }
+
+    // Each thread aggregates its repetitions in its own MultiMults, which are
+    // merged into multiN and multiW at the end
+    #[cfg(feature = "rayon")]
+    {
+        let transcript: &Transcript = transcript;
+        let parts = (0..pi.len()).into_par_iter()
+            .try_fold(
+                || Some((MultiMult::new(paramsNIST.c), MultiMult::new(paramsWario.c))),
+                |part, i| -> Result<_> {
+                    let Some((mut partN, mut partW)) = part else { return Ok(None) };
+                    let ok = verify_repetition(paramsNIST, paramsWario, &Clambda, &Px, &Py, Q.as_deref(), &pi[i], challenge_bits[i], &mut partN, &mut partW, &mut transcript.fork(b"repetition", i as u64))?;
+                    Ok(ok.then_some((partN, partW)))
+                })
+            .collect::<Result<Vec<_>>>()?;
+
+        for part in parts {
+            let Some((partN, partW)) = part else { return Ok(false) };
+            multiN.merge(partN)?;
+            multiW.merge(partW)?;
+        }
+    }
+
+    Ok(true)
+}
+
+// Aggregates the relations of one repetition of the exp proof, answering the
+// challenge bit
+#[allow(clippy::too_many_arguments)]
+fn verify_repetition<'a>(
+    paramsNIST: &'a PedersenParams<'a>,
+    paramsWario: &'a PedersenParams<'a>,
+    Clambda: &EcPointRef,
+    Px: &EcPointRef,
+    Py: &EcPointRef,
+    Q: Option<&EcPointRef>,
+    pi: &'a ExpProof<'a>,
+    bit: bool,
+    multiN: &mut MultiMult,
+    multiW: &mut MultiMult,
+    transcript: &mut Transcript,
+) -> Result<bool> {
+
+    let mut ctx = BigNumContext::new()?;
+
+
+    if bit {
+        // The proof answers another challenge if the params are set to None
+        let (Some(alpha), Some(beta1), Some(beta2), Some(beta3)) =
+            (&pi.alpha, &pi.beta1, &pi.beta2, &pi.beta3) else {
+            return Ok(false);
+        };
+        let a = pi.a.as_ref();
+        let t_x = pi.t_x.as_ref();
+        let t_y = pi.t_y.as_ref();
+
+        let mut T = EcPoint::new(paramsNIST.c)?;
+        T.mul(paramsNIST.c, &paramsNIST.g, alpha, &ctx)?;
+
+        let mut relA = Relation::new(paramsNIST.c);
+
+        // Compute -A
+        let minus_1 = BigNum::from_dec_str("-1")?; 
+        let mut minus_a = EcPoint::new(paramsNIST.c)?;
+        minus_a.mul(paramsNIST.c, a, &minus_1, &ctx)?;
+        relA.insert_m(
+            &[T.to_owned(paramsNIST.c)?,
+            paramsNIST.h.to_owned(paramsNIST.c)?,
+            minus_a],
+            &[BigNum::from_u32(1)?,
+            beta1.as_ref().to_owned()?,
+            BigNum::from_u32(1)?])?;
+
+        relA.drain(multiN)?;
+
+        // Build Tx and Ty
+        if T.is_infinity(paramsNIST.c) {
+            return Ok(false)
+        }
+
+        let mut x = BigNum::new()?;
+        let mut y = BigNum::new()?;
     
-            let mut sx = BigNum::new().unwrap();
-            let mut sy = BigNum::new().unwrap();
-        
-            T1.affine_coordinates_gfp(&paramsNIST.c, &mut sx, &mut sy, &mut ctx).unwrap();
-
-            let mut T1x = EcPoint::new(&paramsWario.c).unwrap();
-            let mut T1y = EcPoint::new(&paramsWario.c).unwrap();
-
-            T1x.mul_full(&paramsWario.c, &sx, &paramsWario.h, &r1, &mut ctx);
-            T1y.mul_full(&paramsWario.c, &sy, &paramsWario.h, &r2, &mut ctx);
-
-            let ok = aggregate_point_add(
-                paramsWario, 
-                T1x, 
-                T1y, 
-                Px.to_owned(&paramsWario.c).unwrap(), 
-                Py.to_owned(&paramsWario.c).unwrap(), 
-                t_x.to_owned(&paramsWario.c).unwrap(), 
-                t_y.to_owned(&paramsWario.c).unwrap(), 
-                pi[i].proof.as_ref().unwrap(), 
-                &mut multiW);
-
-            if !ok {
-                return false
-            }
+        T.affine_coordinates_gfp(paramsNIST.c, &mut x, &mut y, &mut ctx)?;
+
+        let mut relTx = Relation::new(paramsWario.c); 
+        let relTy = Relation::new(paramsWario.c); 
+
+        // Compute -Tx
+        let minus_1 = BigNum::from_dec_str("-1")?; 
+        let mut minus_t_x = EcPoint::new(paramsWario.c)?;
+        minus_t_x.mul(paramsWario.c, t_x, &minus_1, &ctx)?;
+        relTx.insert_m(
+            &[paramsWario.g.to_owned(paramsWario.c)?,
+            paramsWario.h.to_owned(paramsWario.c)?,
+            minus_t_x],
+            &[x,
+            beta2.as_ref().to_owned()?,
+            BigNum::from_u32(1)?])?;
+
+        // Compute -Ty
+        let minus_1 = BigNum::from_dec_str("-1")?; 
+        let mut minus_t_y = EcPoint::new(paramsWario.c)?;
+        minus_t_y.mul(paramsWario.c, t_y, &minus_1, &ctx)?;
+        relTx.insert_m(
+            &[paramsWario.g.to_owned(paramsWario.c)?,
+            paramsWario.h.to_owned(paramsWario.c)?,
+            minus_t_y],
+            &[y,
+            beta3.as_ref().to_owned()?,
+            BigNum::from_u32(1)?])?;
+
+        relTx.drain(multiW)?;
+        relTy.drain(multiW)?;
+    } else {
+        // The proof answers another challenge if the params are set to None
+        let (Some(z), Some(z2), Some(proof), Some(r1), Some(r2)) =
+            (&pi.z, &pi.z2, &pi.proof, &pi.r1, &pi.r2) else {
+            return Ok(false);
+        };
+        let a = pi.a.as_ref();
+        let t_x = pi.t_x.as_ref();
+        let t_y = pi.t_y.as_ref();
+
+        let mut T1 = EcPoint::new(paramsNIST.c)?;
+        T1.mul(paramsNIST.c, &paramsNIST.g, z, &ctx)?;
+
+        let mut relA = Relation::new(paramsNIST.c);
+
+        // Compute -A
+        let minus_1 = BigNum::from_dec_str("-1")?; 
+        let mut minus_a = EcPoint::new(paramsNIST.c)?;
+        minus_a.mul(paramsNIST.c, a, &minus_1, &ctx)?;
+        relA.insert_m(
+            &[T1.to_owned(paramsNIST.c)?,
+            Clambda.to_owned(paramsNIST.c)?,
+            minus_a,
+            paramsNIST.h.to_owned(paramsNIST.c)?],
+            &[BigNum::from_u32(1)?,
+            BigNum::from_u32(1)?,
+            BigNum::from_u32(1)?,
+            z2.as_ref().to_owned()?])?;
+
+        relA.drain(multiN)?;
+
+        if let Some(Q) = Q {
+            let T1_int = T1.to_owned(paramsNIST.c)?;
+            T1.add(paramsNIST.c, &T1_int, Q, &mut ctx)?;
+        }
+
+        if T1.is_infinity(paramsNIST.c) {
+            return Ok(false)
+        }
+
+        let mut sx = BigNum::new()?;
+        let mut sy = BigNum::new()?;
+    
+        T1.affine_coordinates_gfp(paramsNIST.c, &mut sx, &mut sy, &mut ctx)?;
+
+        let T1x = paramsWario.commit_with_randomness(&sx, r1)?.p;
+        let T1y = paramsWario.commit_with_randomness(&sy, r2)?.p;
+
+        let ok = aggregate_point_add(
+            paramsWario, 
+            T1x, 
+            T1y, 
+            Px.to_owned(paramsWario.c)?, 
+            Py.to_owned(paramsWario.c)?, 
+            t_x.to_owned(paramsWario.c)?, 
+            t_y.to_owned(paramsWario.c)?, 
+            proof, 
+            multiW,
+            transcript)?;
+
+        if !ok {
+            return Ok(false)
         }
     }
 
-    return  multiW.evaluate().is_infinity(&paramsWario.c) && multiN.evaluate().is_infinity(&paramsNIST.c)
+    Ok(true)
 }
 
+
+/// An exp proof of a batch, with its statement and the transcript it was
+/// made with.
+pub struct ExpBatchItem<'a> {
+    pub Clambda: EcPoint,
+    pub Px: EcPoint,
+    pub Py: EcPoint,
+    pub pi: &'a [ExpProof<'a>],
+    pub Q: Option<EcPoint>,
+    pub context: Option<&'a [u8]>,
+    pub transcript: Transcript,
+}
+
+/**
+ * Verifies many exp proofs under the same params with one multi-scalar
+ * multiplication per curve, and bisects the batch if it fails.
+ * Returns the indices of the proofs that do not verify, empty if all do.
+ *
+ * @param paramsNIST NIST params
+ * @param paramsWario Wario params
+ * @param items proofs with their statements and transcripts
+ * @param secparam Soundness error
+ */
+pub fn batch_verify_exp<'a>(
+    paramsNIST: &'a PedersenParams<'a>,
+    paramsWario: &'a PedersenParams<'a>,
+    items: Vec<ExpBatchItem<'a>>,
+    secparam: usize,
+) -> Result<Vec<usize>> {
+
+    let mut batch = Batch::new(vec![paramsNIST.c, paramsWario.c]);
+    batch.add_known(0, paramsNIST.g.to_owned(paramsNIST.c)?)?;
+    batch.add_known(0, paramsNIST.h.to_owned(paramsNIST.c)?)?;
+    batch.add_known(1, paramsWario.g.to_owned(paramsWario.c)?)?;
+    batch.add_known(1, paramsWario.h.to_owned(paramsWario.c)?)?;
+
+    for item in items {
+        let ExpBatchItem { Clambda, Px, Py, pi, Q, context, mut transcript } = item;
+        batch.push(|multi| {
+            let [multiN, multiW] = multi else {
+                return Err(ZkError::InvalidInput("one MultiMult per curve"));
+            };
+            aggregate_exp(paramsNIST, paramsWario, Clambda, Px, Py, pi, secparam, Q, context, multiN, multiW, &mut transcript)
+        })?;
+    }
+
+    batch.invalid()
+}
@@ -0,0 +1,533 @@
+use openssl::ec::{EcGroupRef, EcPoint};
+use openssl::bn::{BigNum, BigNumContext};
+use rand::{CryptoRng, RngCore};
+use rand::rngs::OsRng;
+
+use crate::commit::pedersen::{Commitment, PedersenParams};
+use crate::commit::mult::{MultProof, prov_mult_with_rng, aggregate_mult};
+use crate::commit::equality::{EqualityProof, prove_equality_with_rng, aggregate_equality};
+use crate::curves::multimult::{Batch, MultiMult};
+use crate::encoding::{Reader, Writer, TAG_POINT_ADD};
+use crate::error::{Result, ZkError};
+use crate::transcript::Transcript;
+
+/// Fiat-Shamir label of the pointAdd proof.
+pub const LABEL_POINT_ADD: &[u8] = b"zkattest/point-add";
+
+
+
+
+
+//#[derive(Serialize, Deserialize)]
+pub struct PointAddProof<'a> {
+    pub group: &'a EcGroupRef,
+    pub c_8: EcPoint,
+    pub c_10: EcPoint,
+    pub c_11: EcPoint,
+    pub c_13: EcPoint,
+    pub pi_8: MultProof<'a>,
+    pub pi_10: MultProof<'a>,
+    pub pi_11: MultProof<'a>,
+    pub pi_13: MultProof<'a>,
+    pub pi_x: EqualityProof<'a>,
+    pub pi_y: EqualityProof<'a>,
+}
+
+impl<'a> PointAddProof<'a> {
+    #[allow(clippy::should_implement_trait)]
+    pub fn eq(&self, other: &Self) -> bool {
+        
+        let Ok(mut ctx) = BigNumContext::new() else { return false };
+
+        self.c_8.eq(self.group, &other.c_8, &mut ctx).unwrap_or(false) &&
+        self.c_10.eq(self.group, &other.c_10, &mut ctx).unwrap_or(false) &&
+        self.c_11.eq(self.group, &other.c_11, &mut ctx).unwrap_or(false) &&
+        self.c_13.eq(self.group, &other.c_13, &mut ctx).unwrap_or(false) &&
+        self.pi_8.eq(&other.pi_8) &&
+        self.pi_10.eq(&other.pi_10) &&
+        self.pi_11.eq(&other.pi_11) &&
+        self.pi_13.eq(&other.pi_13) &&
+        self.pi_x.eq(&other.pi_x) &&
+        self.pi_y.eq(&other.pi_y) 
+    }
+
+    pub(crate) fn write(&self, w: &mut Writer) -> Result<()> {
+        for p in [&self.c_8, &self.c_10, &self.c_11, &self.c_13] {
+            w.point(self.group, p)?;
+        }
+        for pi in [&self.pi_8, &self.pi_10, &self.pi_11, &self.pi_13] {
+            pi.write(w)?;
+        }
+        self.pi_x.write(w)?;
+        self.pi_y.write(w)
+    }
+
+    pub(crate) fn read(r: &mut Reader, group: &'a EcGroupRef) -> Result<Self> {
+        Ok(PointAddProof {
+            group,
+            c_8: r.point(group)?,
+            c_10: r.point(group)?,
+            c_11: r.point(group)?,
+            c_13: r.point(group)?,
+            pi_8: MultProof::read(r, group)?,
+            pi_10: MultProof::read(r, group)?,
+            pi_11: MultProof::read(r, group)?,
+            pi_13: MultProof::read(r, group)?,
+            pi_x: EqualityProof::read(r, group)?,
+            pi_y: EqualityProof::read(r, group)?,
+        })
+    }
+
+    /// Encodes the proof as version || tag (no hash id, see encoding) || C8 || C10 || C11 || C13 || pi8 || pi10 || pi11 || pi13 || pix || piy
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut w = Writer::new(TAG_POINT_ADD)?;
+        self.write(&mut w)?;
+        Ok(w.finish())
+    }
+
+    pub fn from_bytes(group: &'a EcGroupRef, bytes: &[u8]) -> Result<Self> {
+        let mut r = Reader::new(bytes, TAG_POINT_ADD)?;
+        let pi = Self::read(&mut r, group)?;
+        r.finish()?;
+        Ok(pi)
+    }
+}
+
+
+
+
+// Appends params, the commitments C1, ..., C6 to the coordinates and C8, C10, C11, C13
+fn point_add_transcript(
+    transcript: &mut Transcript,
+    params: &PedersenParams,
+    coordinates: [&EcPoint; 6],
+    intermediate: [&EcPoint; 4]
+) -> Result<()> {
+    const COORDINATES: [&[u8]; 6] = [b"C1", b"C2", b"C3", b"C4", b"C5", b"C6"];
+    const INTERMEDIATE: [&[u8]; 4] = [b"C8", b"C10", b"C11", b"C13"];
+
+    transcript.append_message(b"protocol", LABEL_POINT_ADD);
+    transcript.append_params(b"params", params)?;
+    for (label, p) in COORDINATES.iter().zip(coordinates) {
+        transcript.append_point(label, params.c, p)?;
+    }
+    for (label, p) in INTERMEDIATE.iter().zip(intermediate) {
+        transcript.append_point(label, params.c, p)?;
+    }
+    Ok(())
+}
+
+
+/**
+ * ZK(P, Q, R: R = P + Q)
+ *
+ * @param params
+ * @param P (x1, y1)
+ * @param Q (x2, y2)
+ * @param R (x3, y3)
+ * @param C1 x1 = PX
+ * @param C2 x2 = QX
+ * @param C3 x3 = RX
+ * @param C4 y1 = PY
+ * @param C5 y2 = QY
+ * @param C6 y3 = RY
+ * @param transcript transcript shared with the enclosing proof
+ */ 
+#[allow(clippy::too_many_arguments)]
+pub fn prove_point_add<'a>(
+    paramsNIST: &PedersenParams<'a>,
+    paramsWario: &PedersenParams<'a>,
+    P: EcPoint,
+    Q: EcPoint,
+    R: EcPoint,
+    PX: Commitment,
+    PY: Commitment,
+    QX: Commitment,
+    QY: Commitment,
+    RX: Commitment,
+    RY: Commitment,
+    transcript: &mut Transcript,
+) -> Result<PointAddProof<'a>> {
+    prove_point_add_with_rng(paramsNIST, paramsWario, P, Q, R, PX, PY, QX, QY, RX, RY, transcript, &mut OsRng)
+}
+
+/// prove_point_add with the randomness drawn from rng.
+#[allow(clippy::too_many_arguments)]
+pub fn prove_point_add_with_rng<'a>(
+    paramsNIST: &PedersenParams<'a>,
+    paramsWario: &PedersenParams<'a>,
+    P: EcPoint,
+    Q: EcPoint,
+    R: EcPoint,
+    PX: Commitment,
+    PY: Commitment,
+    QX: Commitment,
+    QY: Commitment,
+    RX: Commitment,
+    RY: Commitment,
+    transcript: &mut Transcript,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Result<PointAddProof<'a>> {
+
+    let mut ctx = BigNumContext::new()?;
+
+    // Check P + Q = R
+    let mut check_r = EcPoint::new(paramsNIST.c)?;
+    check_r.add(paramsNIST.c, &P, &Q, &mut ctx)?;
+    let equality =  R.eq(paramsNIST.c, &check_r, &mut ctx)?;
+    if !equality {
+        return Err(ZkError::InvalidInput("Points don't add up!"));
+    }
+
+    // Checks if points are at infinity
+    if P.is_infinity(paramsNIST.c) {
+        return Err(ZkError::InvalidInput("P is at infinity"));
+    }
+    if Q.is_infinity(paramsNIST.c) {
+        return Err(ZkError::InvalidInput("Q is at infinity"));
+    }
+    if R.is_infinity(paramsNIST.c) {
+        return Err(ZkError::InvalidInput("R is at infinity"));
+    }
+
+    let mut x1 = BigNum::new()?;
+    let mut y1 = BigNum::new()?;
+    let mut x2 = BigNum::new()?;
+    let mut y2 = BigNum::new()?;
+    let mut x3 = BigNum::new()?;
+    let mut _y3 = BigNum::new()?;
+
+    P.affine_coordinates_gfp(paramsNIST.c, &mut x1, &mut y1, &mut ctx)?;
+    Q.affine_coordinates_gfp(paramsNIST.c, &mut x2, &mut y2, &mut ctx)?;
+    R.affine_coordinates_gfp(paramsNIST.c, &mut x3, &mut _y3, &mut ctx)?;
+
+    let C1 = PX.to_owned()?;
+    let C2 = QX.to_owned()?;
+    let C3 = RX.to_owned()?;
+    let C4 = PY.to_owned()?;
+    let C5 = QY.to_owned()?;
+    let C6 = RY.to_owned()?;
+
+
+    let mut order_curve = BigNum::new()?;
+    paramsWario.c.order(&mut order_curve, &mut ctx)?;
+
+
+    let mut i_7 = BigNum::new()?;
+    i_7.mod_sub(&x2, &x1, &order_curve, &mut ctx)?;         // i7  = x2 - x1
+    
+    let mut i_8 = BigNum::new()?;
+    i_8.mod_inverse(&i_7, &order_curve, &mut ctx)?;         // i8  = (x2 - x1)^-1
+    
+    let mut i_9 = BigNum::new()?;
+    i_9.mod_sub(&y2, &y1, &order_curve, &mut ctx)?;         // i9  = y2 - y1
+    
+    let mut i_10 = BigNum::new()?;
+    i_10.mod_mul(&i_8, &i_9, &order_curve, &mut ctx)?;      // i10 = i8 * i9 =  (y2 - y1) / (x2 - x1)
+    
+    let mut i_11 = BigNum::new()?;
+    i_11.mod_mul(&i_10, &i_10, &order_curve, &mut ctx)?;    // i11 = (i10)^2
+    
+    let mut i_12 = BigNum::new()?;
+    i_12.mod_sub(&x1, &x3, &order_curve, &mut ctx)?;        // i12 = x1 - x3
+    
+    let mut i_13 = BigNum::new()?;
+    i_13.mod_mul(&i_10, &i_12, &order_curve, &mut ctx)?;    // i13 = i10 * i12
+
+
+    let C7 = C2.sub(&C1)?;
+    let C8 = paramsWario.commit_with_rng(&i_8, rng)?;
+    let C9 = C5.sub(&C4)?;
+    let C10 = paramsWario.commit_with_rng(&i_10, rng)?;
+    let C11 = paramsWario.commit_with_rng(&i_11, rng)?;
+    let C12 = C1.sub(&C3)?;
+    let C13 = paramsWario.commit_with_rng(&i_13, rng)?;
+    let C14 = Commitment::new(paramsWario.c, paramsWario.g.to_owned(paramsWario.c)?, BigNum::from_u32(0)?);
+
+    // Bind the sub-proofs to each other and to the enclosing proof
+    point_add_transcript(transcript, paramsWario, [&C1.p, &C2.p, &C3.p, &C4.p, &C5.p, &C6.p], [&C8.p, &C10.p, &C11.p, &C13.p])?;
+
+    let pi_8 = prov_mult_with_rng(paramsWario, 
+        i_7, 
+        i_8.to_owned()?,
+        BigNum::from_u32(1)?,
+        C7,
+        C8.to_owned()?,
+        C14,
+        transcript,
+        rng
+    )?;
+
+    // pi10 => i10 = i8 * i9
+    let pi_10 = prov_mult_with_rng(paramsWario, 
+        i_8, 
+        i_9,
+        i_10.to_owned()?,
+        C8.to_owned()?,
+        C9,
+        C10.to_owned()?,
+        transcript,
+        rng
+    )?;
+
+    // pi11 => i11 = i10 * i10
+    let pi_11 = prov_mult_with_rng(paramsWario, 
+        i_10.to_owned()?, 
+        i_10.to_owned()?,
+        i_11.to_owned()?,
+        C10.to_owned()?,
+        C10.to_owned()?,
+        C11.to_owned()?,
+        transcript,
+        rng
+    )?;
+
+    //  Cint = Commitment(C3.p.add(C1.p).add(C2.p), C3.r.add(C1.r).add(C2.r))
+    let mut cint_p = EcPoint::new(paramsWario.c)?;
+    let mut cint_p_int = EcPoint::new(paramsWario.c)?;
+    cint_p_int.add(paramsWario.c, &C1.p, &C2.p, &mut ctx)?;
+    cint_p.add(paramsWario.c, &cint_p_int, &C3.p, &mut ctx)?;
+
+    let mut cint_r = BigNum::new()?;
+    let mut cint_r_int = BigNum::new()?;
+    cint_r_int.mod_add(&C1.r, &C2.r, &order_curve, &mut ctx)?;
+    cint_r.mod_add(&cint_r_int, &C3.r, &order_curve, &mut ctx)?;
+
+    let Cint = Commitment::new(paramsWario.c, cint_p, cint_r);
+
+    // pix => x3 = i11 - x1 - x2
+    let pi_x = prove_equality_with_rng(paramsWario,
+        i_11,
+        C11.to_owned()?,
+        Cint,
+        transcript,
+        rng
+    )?; 
+
+    // pi12 => i12 = x1 - x3
+    // pi13 => i13 = i10 * i12
+    let pi_13 = prov_mult_with_rng(paramsWario,
+        i_10,
+        i_12,
+        i_13.to_owned()?,
+        C10.to_owned()?,
+        C12,
+        C13.to_owned()?,
+        transcript,
+        rng
+    )?;
+
+    //  Cint = new Commitment(C6.p.add(C4.p), C6.r.add(C4.r))
+    let mut cint_p = EcPoint::new(paramsWario.c)?;
+    cint_p.add(paramsWario.c, &C6.p, &C4.p, &mut ctx)?;
+
+    let mut cint_r = BigNum::new()?;
+    cint_r.mod_add(&C6.r, &C4.r, &order_curve, &mut ctx)?;
+
+    let Cint = Commitment::new(paramsWario.c, cint_p, cint_r); 
+
+    // piy => y3 = i13 - y1
+    let pi_y = prove_equality_with_rng(paramsWario,
+        i_13,
+        C13.to_owned()?,
+        Cint,
+        transcript,
+        rng
+    )?;
+
+    Ok(PointAddProof {
+        group: paramsWario.c,
+        c_8: C8.p,
+        c_10: C10.p,
+        c_11: C11.p,
+        c_13: C13.p,
+        pi_8,
+        pi_10,
+        pi_11,
+        pi_13,
+        pi_x,
+        pi_y
+    })
+}
+
+/**
+ * ZKP Verification ZK(P, Q, R: P + Q = R)
+ * P (x1, y1)
+ * Q (x2, y2)
+ * R (x3, y3)
+ *
+ * @param params
+ * @param C1 x1
+ * @param C2 x2
+ * @param C3 x3
+ * @param C4 y1
+ * @param C5 y2
+ * @param C6 y3
+ * @param pi
+ * @param transcript transcript shared with the enclosing proof
+ */
+ #[allow(clippy::too_many_arguments)]
+ pub fn verify_point_add<'a>(
+    params: &'a PedersenParams<'a>,
+    PX: EcPoint,
+    PY: EcPoint,
+    QX: EcPoint,
+    QY: EcPoint,
+    RX: EcPoint,
+    RY: EcPoint,
+    pi: &'a PointAddProof<'a>,
+    transcript: &mut Transcript
+) -> Result<bool> {
+    
+    let mut multi = MultiMult::new(params.c);
+    let ok = aggregate_point_add(params, PX, PY, QX, QY, RX, RY, pi, &mut multi, transcript)?;
+
+    if !ok {
+        return Ok(false)
+    }
+    
+    Ok(multi.evaluate()?.is_infinity(params.c))
+}
+
+
+#[allow(clippy::too_many_arguments)]
+pub fn aggregate_point_add<'a> (
+    params: &'a PedersenParams<'a>,
+    PX: EcPoint,
+    PY: EcPoint,
+    QX: EcPoint,
+    QY: EcPoint,
+    RX: EcPoint,
+    RY: EcPoint,
+    pi: &'a PointAddProof<'a>,
+    multi: &mut MultiMult,
+    transcript: &mut Transcript
+) -> Result<bool> {
+
+    let mut ctx = BigNumContext::new()?;
+
+    point_add_transcript(transcript, params, [&PX, &QX, &RX, &PY, &QY, &RY], [&pi.c_8, &pi.c_10, &pi.c_11, &pi.c_13])?;
+
+    let C1 = PX.to_owned(params.c)?;
+    let C2 = QX.to_owned(params.c)?;
+    let C3 = RX.to_owned(params.c)?;
+    let C4 = PY.to_owned(params.c)?;
+    let C5 = QY.to_owned(params.c)?;
+    let C6 = RY.to_owned(params.c)?;
+    
+    // let C7 = C2.sub(&C1)?;
+    let mut C7 = EcPoint::new(params.c)?;
+    let mut minus_C1 = C1.to_owned(params.c)?;
+    minus_C1.invert(params.c, &ctx)?;
+    C7.add(params.c, &C2, &minus_C1, &mut ctx)?;
+    
+    // let C9 = C5.sub(&C4)?;
+    let mut C9 = EcPoint::new(params.c)?;
+    let mut minus_C4 = C4.to_owned(params.c)?;
+    minus_C4.invert(params.c, &ctx)?;
+    C9.add(params.c, &C5, &minus_C4, &mut ctx)?;
+
+    // let C12 = C1.sub(&C3)?;
+    let mut C12 = EcPoint::new(params.c)?;
+    let mut minus_C3 = C3.to_owned(params.c)?;
+    minus_C3.invert(params.c, &ctx)?;
+    C12.add(params.c, &C1, &minus_C3, &mut ctx)?;
+
+
+    // pi8 => C8 * C7 = C14 and C14 == 1
+    let c_14 = params.g.to_owned(params.c)?;
+    let c_8 = pi.c_8.to_owned(params.c)?;
+    let ver_aggmult = aggregate_mult(params, C7, c_8, c_14, &pi.pi_8, multi, transcript)?;
+    if !ver_aggmult {
+        return Ok(false);
+    }
+
+
+    // pi10 => i10 = i8 * i9
+    let c_8 = pi.c_8.to_owned(params.c)?;
+    let c_10 = pi.c_10.to_owned(params.c)?;
+    let ver_aggmult = aggregate_mult(params, c_8, C9, c_10, &pi.pi_10, multi, transcript)?;
+    if !ver_aggmult {
+        return Ok(false);
+    }
+
+
+    // pi11 => i11 = i10 * i10
+    let c_10_1 = pi.c_10.to_owned(params.c)?;
+    let c_10_2 = pi.c_10.to_owned(params.c)?;
+    let c_11 = pi.c_11.to_owned(params.c)?;
+    let ver_aggmult = aggregate_mult(params, c_10_1, c_10_2, c_11, &pi.pi_11, multi, transcript)?;
+    if !ver_aggmult {
+        return Ok(false);
+    }
+
+    // pix => x3 = i11 - x1 - x2
+    let mut cint = EcPoint::new(params.c)?;
+    let mut cint_int = EcPoint::new(params.c)?;
+    cint_int.add(params.c, &C1, &C2, &mut ctx)?;
+    cint.add(params.c, &cint_int, &C3, &mut ctx)?;
+    let c_11 = pi.c_11.to_owned(params.c)?;
+    let ver_aggeq = aggregate_equality(params, c_11, cint, &pi.pi_x, multi, transcript)?;
+    if !ver_aggeq {
+        return Ok(false);
+    }
+
+    // pi13 => i13 = i10 * i12
+    let c_10 = pi.c_10.to_owned(params.c)?;
+    let c_13 = pi.c_13.to_owned(params.c)?;
+    let ver_aggmult = aggregate_mult(params, c_10, C12, c_13, &pi.pi_13, multi, transcript)?;
+    if !ver_aggmult {
+        return Ok(false);
+    }
+
+    // piy => y3 = i13 - y1
+    let mut cint = EcPoint::new(params.c)?;
+    cint.add(params.c, &C4, &C6, &mut ctx)?;
+    let c_13 = pi.c_13.to_owned(params.c)?;
+    let ver_aggeq = aggregate_equality(params, c_13, cint, &pi.pi_y, multi, transcript)?;
+    if !ver_aggeq {
+        return Ok(false);
+    } 
+
+    Ok(true)
+
+}
+
+
+/// A point addition proof of a batch, with the commitments it is about and
+/// the transcript it was made with.
+pub struct PointAddBatchItem<'a> {
+    pub PX: EcPoint,
+    pub PY: EcPoint,
+    pub QX: EcPoint,
+    pub QY: EcPoint,
+    pub RX: EcPoint,
+    pub RY: EcPoint,
+    pub pi: &'a PointAddProof<'a>,
+    pub transcript: Transcript,
+}
+
+/**
+ * Verifies many point addition proofs under the same params with one
+ * multi-scalar multiplication, and bisects the batch if it fails.
+ * Returns the indices of the proofs that do not verify, empty if all do.
+ *
+ * @param params
+ * @param items proofs with their commitments and transcripts
+ */
+pub fn batch_verify_point_add<'a>(
+    params: &'a PedersenParams<'a>,
+    items: Vec<PointAddBatchItem<'a>>,
+) -> Result<Vec<usize>> {
+
+    let mut batch = Batch::new(vec![params.c]);
+    batch.add_known(0, params.g.to_owned(params.c)?)?;
+    batch.add_known(0, params.h.to_owned(params.c)?)?;
+
+    for item in items {
+        let PointAddBatchItem { PX, PY, QX, QY, RX, RY, pi, mut transcript } = item;
+        batch.push(|multi| aggregate_point_add(params, PX, PY, QX, QY, RX, RY, pi, &mut multi[0], &mut transcript))?;
+    }
+
+    batch.invalid()
+}
@@ -0,0 +1,56 @@
+//! Rust implementation of the zkattest proof system: zero-knowledge proofs of
+//! knowledge of an ECDSA signature over P-256 under a committed (or listed)
+//! public key, built on Pedersen commitments over the Tom256 curve.
+//!
+//! # Compatibility with Cloudflare zkattest
+//!
+//! The proofs follow the structure of the Cloudflare zkattest TypeScript
+//! prover but are not byte-compatible with it, so proofs from TypeScript
+//! clients cannot be verified here and the other way around:
+//!
+//! - challenges come from a [`Transcript`] shared by all sub-proofs, with
+//!   domain separation, an optional caller context and a configurable hash;
+//! - the Pedersen generator h is derived with RFC 9380 hash_to_curve;
+//! - proofs use the versioned binary [`encoding`] of this crate (and the
+//!   optional serde representation built on it).
+//!
+//! There are no TypeScript test vectors in the repository to check against.
+
+// Naming follows the zkattest TypeScript implementation (paramsNIST, Clambda, ...)
+#![allow(non_snake_case)]
+// openssl >= 0.10.73 deprecates the EcPointRef arithmetic used throughout
+#![allow(deprecated)]
+
+pub mod commit;
+pub mod curves;
+pub mod encoding;
+pub mod error;
+pub mod exp;
+#[cfg(feature = "serde")]
+pub mod serialization;
+pub mod signature;
+pub mod transcript;
+
+pub use crate::commit::{pedersen, equality, mult, membership};
+
+// Errors
+pub use crate::error::{Result, ZkError};
+
+// Transcripts
+pub use crate::transcript::{HashFunction, Transcript};
+
+// Commitments
+pub use crate::commit::pedersen::{Commitment, PedersenParams, FixedBaseTable, generate_pedersen_params, generate_pedersen_params_from_seed, verify_pedersen_params, generate_random, generate_random_with_rng};
+
+// Proofs
+pub use crate::commit::equality::{EqualityProof, EqualityBatchItem, prove_equality, prove_equality_with_rng, verify_equality, batch_verify_equality};
+pub use crate::commit::mult::{MultProof, MultBatchItem, prov_mult, prov_mult_with_rng, verify_mult, batch_verify_mult};
+pub use crate::commit::membership::{MembershipProof, prove_membership, prove_membership_with_rng, verify_membership};
+pub use crate::exp::point_add::{PointAddProof, PointAddBatchItem, prove_point_add, prove_point_add_with_rng, verify_point_add, batch_verify_point_add};
+pub use crate::exp::exp::{ExpProof, ExpBatchItem, prov_exp, prov_exp_with_rng, verify_exp, batch_verify_exp, exp_to_bytes, exp_from_bytes};
+pub use crate::signature::signature::{SignatureProof, SystemParameters, prove_signature, prove_signature_with_rng, prove_signature_deterministic, verify_signature};
+pub use crate::signature::signaturelist::{SignatureListProof, prove_signature_list, prove_signature_list_with_rng, prove_signature_list_deterministic, verify_signature_list};
+
+// Serde
+#[cfg(feature = "serde")]
+pub use crate::serialization::{BindCurves, Bound, deserialize_with};
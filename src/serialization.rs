@@ -0,0 +1,686 @@
+//! Serde support for commitments, Pedersen parameters and proofs, behind the
+//! `serde` feature.
+//!
+//! Points and scalars are written as the hex of their binary encoding (see
+//! encoding): compressed SEC1 points and big-endian scalars, reduced mod the
+//! group order and padded to its byte length, checked by the same Reader on
+//! the way back. The structs hold the curves they live on, so they only
+//! implement Serialize; deserialization goes through deserialize_with (or the
+//! Bound seed), which takes the curve (or the NIST and Wario pair) the value
+//! is bound to:
+//!
+//! ```ignore
+//! let json = serde_json::to_string(&proof)?;
+//! let mut de = serde_json::Deserializer::from_str(&json);
+//! let proof: SignatureProof = deserialize_with((&groupNIST, &groupWario), &mut de)?;
+//! ```
+
+use std::marker::PhantomData;
+
+use openssl::bn::BigNum;
+use openssl::ec::{EcGroupRef, EcPoint};
+use serde::de::{DeserializeOwned, DeserializeSeed, Deserializer, Error as _};
+use serde::ser::{Error as _, Serialize, Serializer};
+use serde::{Deserialize as DeriveDeserialize, Serialize as DeriveSerialize};
+
+use crate::commit::equality::EqualityProof;
+use crate::commit::membership::MembershipProof;
+use crate::commit::mult::MultProof;
+use crate::commit::pedersen::{Commitment, PedersenParams};
+use crate::encoding::{Reader, Writer};
+use crate::error::{Result, ZkError};
+use crate::exp::exp::ExpProof;
+use crate::exp::point_add::PointAddProof;
+use crate::signature::signature::SignatureProof;
+use crate::signature::signaturelist::SignatureListProof;
+use crate::transcript::HashFunction;
+
+
+/*
+
+        UTIL::hex
+
+*/
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(s: &str) -> Result<Vec<u8>> {
+    if !s.len().is_multiple_of(2) || !s.is_ascii() {
+        return Err(ZkError::MalformedProof("invalid hex"));
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).map_err(|_| ZkError::MalformedProof("invalid hex")))
+        .collect()
+}
+
+// Converts points and scalars of a group to and from their hex strings,
+// using the binary encoding of each value
+struct Hex;
+
+impl Hex {
+
+    fn point(&mut self, group: &EcGroupRef, pt: &EcPoint) -> Result<String> {
+        let mut w = Writer::bare()?;
+        w.point(group, pt)?;
+        Ok(to_hex(&w.finish()))
+    }
+
+    fn scalar(&mut self, group: &EcGroupRef, s: &BigNum) -> Result<String> {
+        let mut w = Writer::bare()?;
+        w.scalar(group, s)?;
+        Ok(to_hex(&w.finish()))
+    }
+
+    fn read_point(&mut self, group: &EcGroupRef, s: &str) -> Result<EcPoint> {
+        let bytes = from_hex(s)?;
+        let mut r = Reader::bare(&bytes)?;
+        let pt = r.point(group)?;
+        r.finish()?;
+        Ok(pt)
+    }
+
+    fn read_scalar(&mut self, group: &EcGroupRef, s: &str) -> Result<BigNum> {
+        let bytes = from_hex(s)?;
+        let mut r = Reader::bare(&bytes)?;
+        let ss = r.scalar(group)?;
+        r.finish()?;
+        Ok(ss)
+    }
+
+    fn points(&mut self, group: &EcGroupRef, pts: &[EcPoint]) -> Result<Vec<String>> {
+        pts.iter().map(|pt| self.point(group, pt)).collect()
+    }
+
+    fn scalars(&mut self, group: &EcGroupRef, ss: &[BigNum]) -> Result<Vec<String>> {
+        ss.iter().map(|s| self.scalar(group, s)).collect()
+    }
+
+    fn read_points(&mut self, group: &EcGroupRef, v: &[String]) -> Result<Vec<EcPoint>> {
+        v.iter().map(|s| self.read_point(group, s)).collect()
+    }
+
+    fn read_scalars(&mut self, group: &EcGroupRef, v: &[String]) -> Result<Vec<BigNum>> {
+        v.iter().map(|s| self.read_scalar(group, s)).collect()
+    }
+}
+
+
+/*
+
+        Context-aware deserialization
+
+*/
+
+/// Values that are deserialized from Repr once bound to their curves:
+/// a single group, or the (groupNIST, groupWario) pair.
+pub trait BindCurves<'a>: Sized {
+    type Curves: Copy;
+    type Repr: DeserializeOwned;
+
+    fn bind(repr: Self::Repr, curves: Self::Curves) -> Result<Self>;
+}
+
+/// DeserializeSeed binding the deserialized value to curves.
+pub struct Bound<'a, T: BindCurves<'a>> {
+    curves: T::Curves,
+    _value: PhantomData<T>,
+}
+
+impl<'a, T: BindCurves<'a>> Bound<'a, T> {
+    pub fn new(curves: T::Curves) -> Self {
+        Bound { curves, _value: PhantomData }
+    }
+}
+
+impl<'de, 'a, T: BindCurves<'a>> DeserializeSeed<'de> for Bound<'a, T> {
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<T, D::Error> {
+        let repr = T::Repr::deserialize(deserializer)?;
+        T::bind(repr, self.curves).map_err(D::Error::custom)
+    }
+}
+
+/**
+ * Deserializes a value bound to curves.
+ * @param curves group for commitments, Pedersen parameters, equality, mult,
+ *               pointAdd and membership proofs; (groupNIST, groupWario) for
+ *               exp, signature and signature list proofs
+ * @param deserializer any serde deserializer
+ */
+pub fn deserialize_with<'de, 'a, T, D>(curves: T::Curves, deserializer: D) -> std::result::Result<T, D::Error>
+where
+    T: BindCurves<'a>,
+    D: Deserializer<'de>,
+{
+    Bound::<T>::new(curves).deserialize(deserializer)
+}
+
+impl<'a, T: BindCurves<'a>> BindCurves<'a> for Vec<T> {
+    type Curves = T::Curves;
+    type Repr = Vec<T::Repr>;
+
+    fn bind(repr: Self::Repr, curves: Self::Curves) -> Result<Self> {
+        repr.into_iter().map(|r| T::bind(r, curves)).collect()
+    }
+}
+
+// Serialize through the Repr of the value
+macro_rules! serialize_via_repr {
+    ($ty:ident) => {
+        impl<'a> Serialize for $ty<'a> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                let mut hex = Hex;
+                self.repr(&mut hex).map_err(S::Error::custom)?.serialize(serializer)
+            }
+        }
+    };
+}
+
+
+/*
+
+        Commitments
+
+*/
+
+#[derive(DeriveSerialize, DeriveDeserialize)]
+pub struct CommitmentRepr {
+    pub p: String,
+    pub r: String,
+}
+
+impl<'a> Commitment<'a> {
+    fn repr(&self, hex: &mut Hex) -> Result<CommitmentRepr> {
+        Ok(CommitmentRepr {
+            p: hex.point(self.group, &self.p)?,
+            r: hex.scalar(self.group, &self.r)?,
+        })
+    }
+}
+
+impl<'a> BindCurves<'a> for Commitment<'a> {
+    type Curves = &'a EcGroupRef;
+    type Repr = CommitmentRepr;
+
+    fn bind(repr: CommitmentRepr, group: &'a EcGroupRef) -> Result<Self> {
+        let mut hex = Hex;
+        Ok(Commitment::new(group, hex.read_point(group, &repr.p)?, hex.read_scalar(group, &repr.r)?))
+    }
+}
+
+serialize_via_repr!(Commitment);
+
+
+#[derive(DeriveSerialize, DeriveDeserialize)]
+pub struct PedersenParamsRepr {
+    pub g: String,
+    pub h: String,
+}
+
+impl<'a> PedersenParams<'a> {
+    fn repr(&self, hex: &mut Hex) -> Result<PedersenParamsRepr> {
+        Ok(PedersenParamsRepr {
+            g: hex.point(self.c, &self.g)?,
+            h: hex.point(self.c, &self.h)?,
+        })
+    }
+}
+
+impl<'a> BindCurves<'a> for PedersenParams<'a> {
+    type Curves = &'a EcGroupRef;
+    type Repr = PedersenParamsRepr;
+
+    fn bind(repr: PedersenParamsRepr, c: &'a EcGroupRef) -> Result<Self> {
+        let mut hex = Hex;
+        Ok(PedersenParams::new(c, hex.read_point(c, &repr.g)?, hex.read_point(c, &repr.h)?))
+    }
+}
+
+serialize_via_repr!(PedersenParams);
+
+
+/*
+
+        Equality, mult and pointAdd proofs
+
+*/
+
+#[derive(DeriveSerialize, DeriveDeserialize)]
+pub struct EqualityProofRepr {
+    pub a_1: String,
+    pub a_2: String,
+    pub t_x: String,
+    pub t_r1: String,
+    pub t_r2: String,
+}
+
+impl<'a> EqualityProof<'a> {
+    fn repr(&self, hex: &mut Hex) -> Result<EqualityProofRepr> {
+        let group = self.group;
+        Ok(EqualityProofRepr {
+            a_1: hex.point(group, &self.a_1)?,
+            a_2: hex.point(group, &self.a_2)?,
+            t_x: hex.scalar(group, &self.t_x)?,
+            t_r1: hex.scalar(group, &self.t_r1)?,
+            t_r2: hex.scalar(group, &self.t_r2)?,
+        })
+    }
+
+    fn from_repr(repr: &EqualityProofRepr, group: &'a EcGroupRef, hex: &mut Hex) -> Result<Self> {
+        Ok(EqualityProof {
+            group,
+            a_1: hex.read_point(group, &repr.a_1)?,
+            a_2: hex.read_point(group, &repr.a_2)?,
+            t_x: hex.read_scalar(group, &repr.t_x)?,
+            t_r1: hex.read_scalar(group, &repr.t_r1)?,
+            t_r2: hex.read_scalar(group, &repr.t_r2)?,
+        })
+    }
+}
+
+impl<'a> BindCurves<'a> for EqualityProof<'a> {
+    type Curves = &'a EcGroupRef;
+    type Repr = EqualityProofRepr;
+
+    fn bind(repr: EqualityProofRepr, group: &'a EcGroupRef) -> Result<Self> {
+        Self::from_repr(&repr, group, &mut Hex)
+    }
+}
+
+serialize_via_repr!(EqualityProof);
+
+
+#[derive(DeriveSerialize, DeriveDeserialize)]
+pub struct MultProofRepr {
+    pub c_4: String,
+    pub a_x: String,
+    pub a_y: String,
+    pub a_z: String,
+    pub a_4_1: String,
+    pub a_4_2: String,
+    pub t_x: String,
+    pub t_y: String,
+    pub t_z: String,
+    pub t_rx: String,
+    pub t_ry: String,
+    pub t_rz: String,
+    pub t_r4: String,
+}
+
+impl<'a> MultProof<'a> {
+    fn repr(&self, hex: &mut Hex) -> Result<MultProofRepr> {
+        let group = self.group;
+        Ok(MultProofRepr {
+            c_4: hex.point(group, &self.c_4)?,
+            a_x: hex.point(group, &self.a_x)?,
+            a_y: hex.point(group, &self.a_y)?,
+            a_z: hex.point(group, &self.a_z)?,
+            a_4_1: hex.point(group, &self.a_4_1)?,
+            a_4_2: hex.point(group, &self.a_4_2)?,
+            t_x: hex.scalar(group, &self.t_x)?,
+            t_y: hex.scalar(group, &self.t_y)?,
+            t_z: hex.scalar(group, &self.t_z)?,
+            t_rx: hex.scalar(group, &self.t_rx)?,
+            t_ry: hex.scalar(group, &self.t_ry)?,
+            t_rz: hex.scalar(group, &self.t_rz)?,
+            t_r4: hex.scalar(group, &self.t_r4)?,
+        })
+    }
+
+    fn from_repr(repr: &MultProofRepr, group: &'a EcGroupRef, hex: &mut Hex) -> Result<Self> {
+        Ok(MultProof {
+            group,
+            c_4: hex.read_point(group, &repr.c_4)?,
+            a_x: hex.read_point(group, &repr.a_x)?,
+            a_y: hex.read_point(group, &repr.a_y)?,
+            a_z: hex.read_point(group, &repr.a_z)?,
+            a_4_1: hex.read_point(group, &repr.a_4_1)?,
+            a_4_2: hex.read_point(group, &repr.a_4_2)?,
+            t_x: hex.read_scalar(group, &repr.t_x)?,
+            t_y: hex.read_scalar(group, &repr.t_y)?,
+            t_z: hex.read_scalar(group, &repr.t_z)?,
+            t_rx: hex.read_scalar(group, &repr.t_rx)?,
+            t_ry: hex.read_scalar(group, &repr.t_ry)?,
+            t_rz: hex.read_scalar(group, &repr.t_rz)?,
+            t_r4: hex.read_scalar(group, &repr.t_r4)?,
+        })
+    }
+}
+
+impl<'a> BindCurves<'a> for MultProof<'a> {
+    type Curves = &'a EcGroupRef;
+    type Repr = MultProofRepr;
+
+    fn bind(repr: MultProofRepr, group: &'a EcGroupRef) -> Result<Self> {
+        Self::from_repr(&repr, group, &mut Hex)
+    }
+}
+
+serialize_via_repr!(MultProof);
+
+
+#[derive(DeriveSerialize, DeriveDeserialize)]
+pub struct PointAddProofRepr {
+    pub c_8: String,
+    pub c_10: String,
+    pub c_11: String,
+    pub c_13: String,
+    pub pi_8: MultProofRepr,
+    pub pi_10: MultProofRepr,
+    pub pi_11: MultProofRepr,
+    pub pi_13: MultProofRepr,
+    pub pi_x: EqualityProofRepr,
+    pub pi_y: EqualityProofRepr,
+}
+
+impl<'a> PointAddProof<'a> {
+    fn repr(&self, hex: &mut Hex) -> Result<PointAddProofRepr> {
+        let group = self.group;
+        Ok(PointAddProofRepr {
+            c_8: hex.point(group, &self.c_8)?,
+            c_10: hex.point(group, &self.c_10)?,
+            c_11: hex.point(group, &self.c_11)?,
+            c_13: hex.point(group, &self.c_13)?,
+            pi_8: self.pi_8.repr(hex)?,
+            pi_10: self.pi_10.repr(hex)?,
+            pi_11: self.pi_11.repr(hex)?,
+            pi_13: self.pi_13.repr(hex)?,
+            pi_x: self.pi_x.repr(hex)?,
+            pi_y: self.pi_y.repr(hex)?,
+        })
+    }
+
+    fn from_repr(repr: &PointAddProofRepr, group: &'a EcGroupRef, hex: &mut Hex) -> Result<Self> {
+        Ok(PointAddProof {
+            group,
+            c_8: hex.read_point(group, &repr.c_8)?,
+            c_10: hex.read_point(group, &repr.c_10)?,
+            c_11: hex.read_point(group, &repr.c_11)?,
+            c_13: hex.read_point(group, &repr.c_13)?,
+            pi_8: MultProof::from_repr(&repr.pi_8, group, hex)?,
+            pi_10: MultProof::from_repr(&repr.pi_10, group, hex)?,
+            pi_11: MultProof::from_repr(&repr.pi_11, group, hex)?,
+            pi_13: MultProof::from_repr(&repr.pi_13, group, hex)?,
+            pi_x: EqualityProof::from_repr(&repr.pi_x, group, hex)?,
+            pi_y: EqualityProof::from_repr(&repr.pi_y, group, hex)?,
+        })
+    }
+}
+
+impl<'a> BindCurves<'a> for PointAddProof<'a> {
+    type Curves = &'a EcGroupRef;
+    type Repr = PointAddProofRepr;
+
+    fn bind(repr: PointAddProofRepr, group: &'a EcGroupRef) -> Result<Self> {
+        Self::from_repr(&repr, group, &mut Hex)
+    }
+}
+
+serialize_via_repr!(PointAddProof);
+
+
+/*
+
+        Exp proofs
+
+*/
+
+/// One repetition of the exp proof: either the (alpha, beta1, beta2, beta3)
+/// or the (z, z2, r1, r2, proof) response is set.
+#[derive(DeriveSerialize, DeriveDeserialize)]
+pub struct ExpProofRepr {
+    pub a: String,
+    pub t_x: String,
+    pub t_y: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alpha: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub beta1: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub beta2: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub beta3: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub z: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub z2: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof: Option<PointAddProofRepr>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r1: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r2: Option<String>,
+}
+
+impl<'a> ExpProof<'a> {
+    fn repr(&self, hex: &mut Hex) -> Result<ExpProofRepr> {
+        let (groupNIST, groupWario) = (self.groupNIST, self.groupWario);
+        let mut repr = ExpProofRepr {
+            a: hex.point(groupNIST, &self.a)?,
+            t_x: hex.point(groupWario, &self.t_x)?,
+            t_y: hex.point(groupWario, &self.t_y)?,
+            alpha: None,
+            beta1: None,
+            beta2: None,
+            beta3: None,
+            z: None,
+            z2: None,
+            proof: None,
+            r1: None,
+            r2: None,
+        };
+
+        match (&self.alpha, &self.beta1, &self.beta2, &self.beta3, &self.z, &self.z2, &self.proof, &self.r1, &self.r2) {
+            (Some(alpha), Some(beta1), Some(beta2), Some(beta3), None, None, None, None, None) => {
+                repr.alpha = Some(hex.scalar(groupNIST, alpha)?);
+                repr.beta1 = Some(hex.scalar(groupNIST, beta1)?);
+                repr.beta2 = Some(hex.scalar(groupWario, beta2)?);
+                repr.beta3 = Some(hex.scalar(groupWario, beta3)?);
+            },
+            (None, None, None, None, Some(z), Some(z2), Some(proof), Some(r1), Some(r2)) => {
+                repr.z = Some(hex.scalar(groupNIST, z)?);
+                repr.z2 = Some(hex.scalar(groupNIST, z2)?);
+                repr.proof = Some(proof.repr(hex)?);
+                repr.r1 = Some(hex.scalar(groupWario, r1)?);
+                repr.r2 = Some(hex.scalar(groupWario, r2)?);
+            },
+            _ => return Err(ZkError::MalformedProof("params not found")),
+        }
+
+        Ok(repr)
+    }
+
+    fn from_repr(repr: &ExpProofRepr, groupNIST: &'a EcGroupRef, groupWario: &'a EcGroupRef, hex: &mut Hex) -> Result<Self> {
+        let mut pi = ExpProof {
+            groupNIST,
+            groupWario,
+            a: hex.read_point(groupNIST, &repr.a)?,
+            t_x: hex.read_point(groupWario, &repr.t_x)?,
+            t_y: hex.read_point(groupWario, &repr.t_y)?,
+            alpha: None,
+            beta1: None,
+            beta2: None,
+            beta3: None,
+            z: None,
+            z2: None,
+            proof: None,
+            r1: None,
+            r2: None,
+        };
+
+        match (&repr.alpha, &repr.beta1, &repr.beta2, &repr.beta3, &repr.z, &repr.z2, &repr.proof, &repr.r1, &repr.r2) {
+            (Some(alpha), Some(beta1), Some(beta2), Some(beta3), None, None, None, None, None) => {
+                pi.alpha = Some(hex.read_scalar(groupNIST, alpha)?);
+                pi.beta1 = Some(hex.read_scalar(groupNIST, beta1)?);
+                pi.beta2 = Some(hex.read_scalar(groupWario, beta2)?);
+                pi.beta3 = Some(hex.read_scalar(groupWario, beta3)?);
+            },
+            (None, None, None, None, Some(z), Some(z2), Some(proof), Some(r1), Some(r2)) => {
+                pi.z = Some(hex.read_scalar(groupNIST, z)?);
+                pi.z2 = Some(hex.read_scalar(groupNIST, z2)?);
+                pi.proof = Some(PointAddProof::from_repr(proof, groupWario, hex)?);
+                pi.r1 = Some(hex.read_scalar(groupWario, r1)?);
+                pi.r2 = Some(hex.read_scalar(groupWario, r2)?);
+            },
+            _ => return Err(ZkError::MalformedProof("unknown response type")),
+        }
+
+        Ok(pi)
+    }
+}
+
+impl<'a> BindCurves<'a> for ExpProof<'a> {
+    type Curves = (&'a EcGroupRef, &'a EcGroupRef);
+    type Repr = ExpProofRepr;
+
+    fn bind(repr: ExpProofRepr, (groupNIST, groupWario): Self::Curves) -> Result<Self> {
+        Self::from_repr(&repr, groupNIST, groupWario, &mut Hex)
+    }
+}
+
+serialize_via_repr!(ExpProof);
+
+
+/*
+
+        Signature, membership and signature list proofs
+
+*/
+
+#[derive(DeriveSerialize, DeriveDeserialize)]
+pub struct SignatureProofRepr {
+    pub hash: String,
+    pub R: String,
+    pub comS1: String,
+    pub keyXcom: String,
+    pub keyYcom: String,
+    pub expProof: Vec<ExpProofRepr>,
+}
+
+impl<'a> SignatureProof<'a> {
+    fn repr(&self, hex: &mut Hex) -> Result<SignatureProofRepr> {
+        Ok(SignatureProofRepr {
+            hash: self.hash.name().to_string(),
+            R: hex.point(self.groupNIST, &self.R)?,
+            comS1: hex.point(self.groupNIST, &self.comS1)?,
+            keyXcom: hex.point(self.groupWario, &self.keyXcom)?,
+            keyYcom: hex.point(self.groupWario, &self.keyYcom)?,
+            expProof: self.expProof.iter().map(|pi| pi.repr(hex)).collect::<Result<_>>()?,
+        })
+    }
+
+    fn from_repr(repr: &SignatureProofRepr, groupNIST: &'a EcGroupRef, groupWario: &'a EcGroupRef, hex: &mut Hex) -> Result<Self> {
+        Ok(SignatureProof {
+            groupNIST,
+            groupWario,
+            hash: HashFunction::from_name(&repr.hash)?,
+            R: hex.read_point(groupNIST, &repr.R)?,
+            comS1: hex.read_point(groupNIST, &repr.comS1)?,
+            keyXcom: hex.read_point(groupWario, &repr.keyXcom)?,
+            keyYcom: hex.read_point(groupWario, &repr.keyYcom)?,
+            expProof: repr.expProof.iter()
+                .map(|pi| ExpProof::from_repr(pi, groupNIST, groupWario, hex))
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+impl<'a> BindCurves<'a> for SignatureProof<'a> {
+    type Curves = (&'a EcGroupRef, &'a EcGroupRef);
+    type Repr = SignatureProofRepr;
+
+    fn bind(repr: SignatureProofRepr, (groupNIST, groupWario): Self::Curves) -> Result<Self> {
+        Self::from_repr(&repr, groupNIST, groupWario, &mut Hex)
+    }
+}
+
+serialize_via_repr!(SignatureProof);
+
+
+#[derive(DeriveSerialize, DeriveDeserialize)]
+pub struct MembershipProofRepr {
+    pub c_l: Vec<String>,
+    pub c_a: Vec<String>,
+    pub c_b: Vec<String>,
+    pub c_d: Vec<String>,
+    pub f: Vec<String>,
+    pub z_a: Vec<String>,
+    pub z_b: Vec<String>,
+    pub z_d: String,
+}
+
+impl<'a> MembershipProof<'a> {
+    fn repr(&self, hex: &mut Hex) -> Result<MembershipProofRepr> {
+        let group = self.group;
+        Ok(MembershipProofRepr {
+            c_l: hex.points(group, &self.c_l)?,
+            c_a: hex.points(group, &self.c_a)?,
+            c_b: hex.points(group, &self.c_b)?,
+            c_d: hex.points(group, &self.c_d)?,
+            f: hex.scalars(group, &self.f)?,
+            z_a: hex.scalars(group, &self.z_a)?,
+            z_b: hex.scalars(group, &self.z_b)?,
+            z_d: hex.scalar(group, &self.z_d)?,
+        })
+    }
+
+    fn from_repr(repr: &MembershipProofRepr, group: &'a EcGroupRef, hex: &mut Hex) -> Result<Self> {
+        Ok(MembershipProof {
+            group,
+            c_l: hex.read_points(group, &repr.c_l)?,
+            c_a: hex.read_points(group, &repr.c_a)?,
+            c_b: hex.read_points(group, &repr.c_b)?,
+            c_d: hex.read_points(group, &repr.c_d)?,
+            f: hex.read_scalars(group, &repr.f)?,
+            z_a: hex.read_scalars(group, &repr.z_a)?,
+            z_b: hex.read_scalars(group, &repr.z_b)?,
+            z_d: hex.read_scalar(group, &repr.z_d)?,
+        })
+    }
+}
+
+impl<'a> BindCurves<'a> for MembershipProof<'a> {
+    type Curves = &'a EcGroupRef;
+    type Repr = MembershipProofRepr;
+
+    fn bind(repr: MembershipProofRepr, group: &'a EcGroupRef) -> Result<Self> {
+        Self::from_repr(&repr, group, &mut Hex)
+    }
+}
+
+serialize_via_repr!(MembershipProof);
+
+
+#[derive(DeriveSerialize, DeriveDeserialize)]
+pub struct SignatureListProofRepr {
+    pub signatureProof: SignatureProofRepr,
+    pub membershipProof: MembershipProofRepr,
+}
+
+impl<'a> SignatureListProof<'a> {
+    fn repr(&self, hex: &mut Hex) -> Result<SignatureListProofRepr> {
+        Ok(SignatureListProofRepr {
+            signatureProof: self.signatureProof.repr(hex)?,
+            membershipProof: self.membershipProof.repr(hex)?,
+        })
+    }
+}
+
+impl<'a> BindCurves<'a> for SignatureListProof<'a> {
+    type Curves = (&'a EcGroupRef, &'a EcGroupRef);
+    type Repr = SignatureListProofRepr;
+
+    fn bind(repr: SignatureListProofRepr, (groupNIST, groupWario): Self::Curves) -> Result<Self> {
+        let mut hex = Hex;
+        Ok(SignatureListProof {
+            signatureProof: SignatureProof::from_repr(&repr.signatureProof, groupNIST, groupWario, &mut hex)?,
+            membershipProof: MembershipProof::from_repr(&repr.membershipProof, groupWario, &mut hex)?,
+        })
+    }
+}
+
+serialize_via_repr!(SignatureListProof);
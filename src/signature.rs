@@ -1,2 +1,3 @@
+#[allow(clippy::module_inception)]
 pub mod signature;
 pub mod signaturelist;
\ No newline at end of file
@@ -1,56 +1,98 @@
-use openssl::ec::{EcGroup, EcGroupRef, EcPoint, EcPointRef, PointConversionForm};
-use openssl::bn::{BigNum, BigNumRef, BigNumContext, MsbOption};
-use openssl::error::ErrorStack;
-use openssl::hash::{hash, MessageDigest};
+use openssl::ec::{EcGroupRef, EcPoint};
+use openssl::bn::{BigNum, BigNumRef, BigNumContext};
+use rand::{CryptoRng, RngCore};
+use rand::rngs::OsRng;
 
-use crate::commit::pedersen::{Commitment, PedersenParams, generate_random};
-use crate::commit::mult::{MultProof, prov_mult, aggregate_mult};
-use crate::commit::equality::{EqualityProof, prove_equality, aggregate_equality};
-use crate::curves::multimult::{MultiMult, Relation};
-use crate::equality::hash_points;
-use crate::exp::exp::ExpProof;
+use crate::commit::pedersen::{Commitment, PedersenParams, generate_random_with_rng};
+use crate::curves::instances::CurvePair;
+use crate::exp::exp::{ExpProof, prov_exp_with_rng, verify_exp, write_exp, read_exp};
+use crate::encoding::{Reader, Writer, TAG_SIGNATURE};
+use crate::error::{Result, ZkError};
+use crate::transcript::{HashFunction, Transcript};
+
+/// Fiat-Shamir label of the signature proof.
+pub const LABEL_SIGNATURE: &[u8] = b"zkattest/signature";
+
+/// Label of the statement the derandomized provers seed their RNG with.
+pub const LABEL_DERANDOMIZE: &[u8] = b"zkattest/derandomize";
 
 
 
 pub struct SignatureProof<'a> {
     pub groupNIST: &'a EcGroupRef,
     pub groupWario: &'a EcGroupRef,
+    /// Hash of the Fiat-Shamir challenges
+    pub hash: HashFunction,
     pub R: EcPoint,
     pub comS1: EcPoint,
     pub keyXcom: EcPoint,
     pub keyYcom: EcPoint,
-    pub expProof: ExpProof<'a>,
+    pub expProof: Vec<ExpProof<'a>>,
 }
 
 impl<'a> SignatureProof<'a> {
+    #[allow(clippy::should_implement_trait)]
     pub fn eq(&self, other: &Self) -> bool {
 
-        let mut ctx = BigNumContext::new().unwrap();
+        let Ok(mut ctx) = BigNumContext::new() else { return false };
+
+        self.hash == other.hash &&
+        self.R.eq(self.groupNIST, &other.R, &mut ctx).unwrap_or(false) &&
+        self.comS1.eq(self.groupNIST, &other.comS1, &mut ctx).unwrap_or(false) &&
+        self.keyXcom.eq(self.groupWario, &other.keyXcom, &mut ctx).unwrap_or(false) &&
+        self.keyYcom.eq(self.groupWario, &other.keyYcom, &mut ctx).unwrap_or(false) &&
+        self.expProof.len() == other.expProof.len() &&
+        self.expProof.iter().zip(other.expProof.iter()).all(|(a, b)| a.eq(b))
+    }
+
+    pub(crate) fn write(&self, w: &mut Writer) -> Result<()> {
+        w.flag(self.hash.id());
+        w.point(self.groupNIST, &self.R)?;
+        w.point(self.groupNIST, &self.comS1)?;
+        w.point(self.groupWario, &self.keyXcom)?;
+        w.point(self.groupWario, &self.keyYcom)?;
+        write_exp(&self.expProof, w)
+    }
+
+    pub(crate) fn read(r: &mut Reader, groupNIST: &'a EcGroupRef, groupWario: &'a EcGroupRef) -> Result<Self> {
+        Ok(SignatureProof {
+            groupNIST,
+            groupWario,
+            hash: HashFunction::from_id(r.flag()?)?,
+            R: r.point(groupNIST)?,
+            comS1: r.point(groupNIST)?,
+            keyXcom: r.point(groupWario)?,
+            keyYcom: r.point(groupWario)?,
+            expProof: read_exp(r, groupNIST, groupWario)?,
+        })
+    }
+
+    /// Encodes the proof as version || tag || hash id || R || comS1 || keyXcom || keyYcom || exp proofs
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut w = Writer::new(TAG_SIGNATURE)?;
+        self.write(&mut w)?;
+        Ok(w.finish())
+    }
 
-        self.R.eq(self.groupNIST, &other.R, &mut ctx).unwrap() &&
-        self.comS1.eq(self.groupWario, &other.comS1, &mut ctx).unwrap() &&
-        self.keyXcom.eq(self.groupWario, &other.keyXcom, &mut ctx).unwrap() &&
-        self.keyYcom.eq(self.groupWario, &other.keyYcom, &mut ctx).unwrap() &&
-        self.expProof.eq(&other.expProof)
+    pub fn from_bytes(groupNIST: &'a EcGroupRef, groupWario: &'a EcGroupRef, bytes: &[u8]) -> Result<Self> {
+        let mut r = Reader::new(bytes, TAG_SIGNATURE)?;
+        let pi = Self::read(&mut r, groupNIST, groupWario)?;
+        r.finish()?;
+        Ok(pi)
     }
 }
 
 
 pub struct SystemParameters<'a> {
+    pub pair: CurvePair,
     pub groupNIST: &'a EcGroupRef,
     pub groupWario: &'a EcGroupRef,
-    pub secLevel: usize
+    /// Repetitions of the exp proof, from 1 to the bit length of the NIST order
+    pub secLevel: usize,
+    /// Hash of the Fiat-Shamir challenges
+    pub hash: HashFunction,
 }
 
-fn shr(self, n: i32) -> BigNum {
-    let mut r = BigNum::new().unwrap();
-    let delta = self.num_bits() - n.num_bits();
-    if delta > 0 {
-        r.rshift(self, n).unwrap();
-    }
-    
-    r
-}
 
 /* function truncateToN(msg: bigint, n: bigint): bigint {
     const delta = bitLen(msg) - bitLen(n)
@@ -58,4 +100,356 @@ fn shr(self, n: i32) -> BigNum {
         msg >>= BigInt(delta)
     }
     return msg
-} */
\ No newline at end of file
+} */
+pub fn truncate_to_n(msg: &BigNum, n: &BigNum) -> Result<BigNum> {
+    let mut r = msg.as_ref().to_owned()?;
+    let delta = msg.num_bits() - n.num_bits();
+    if delta > 0 {
+        r.rshift(msg, delta)?;
+    }
+
+    Ok(r)
+}
+
+
+// Statement of a proof for the derandomized provers: everything its challenges
+// depend on, except what the prover draws from the RNG. The caller appends the
+// remaining public inputs (key commitments or key list).
+pub(crate) fn derandomize_transcript(
+    label: &[u8],
+    params: &SystemParameters,
+    paramsNIST: &PedersenParams,
+    paramsWario: &PedersenParams,
+    msg_hash: &BigNum,
+    context: Option<&[u8]>
+) -> Result<Transcript> {
+    let mut transcript = Transcript::with_hash(LABEL_DERANDOMIZE, params.hash);
+    transcript.append_message(b"protocol", label);
+    transcript.append_context(context);
+    transcript.append_message(b"pair", params.pair.name().as_bytes());
+    transcript.append_u64(b"secLevel", params.secLevel as u64);
+    transcript.append_message(b"hash", params.hash.name().as_bytes());
+    transcript.append_params(b"paramsNIST", paramsNIST)?;
+    transcript.append_params(b"paramsWario", paramsWario)?;
+    transcript.append_scalar(b"msg_hash", msg_hash);
+    Ok(transcript)
+}
+
+
+// Appends the curve pair, the security level, the truncated message hash z, R and the key commitments
+fn signature_transcript(
+    transcript: &mut Transcript,
+    params: &SystemParameters,
+    z: &BigNum,
+    R: &EcPoint,
+    keyXcom: &EcPoint,
+    keyYcom: &EcPoint
+) -> Result<()> {
+    transcript.append_message(b"protocol", LABEL_SIGNATURE);
+    transcript.append_message(b"pair", params.pair.name().as_bytes());
+    transcript.append_u64(b"secLevel", params.secLevel as u64);
+    transcript.append_scalar(b"z", z);
+    transcript.append_point(b"R", params.groupNIST, R)?;
+    transcript.append_point(b"keyXcom", params.groupWario, keyXcom)?;
+    transcript.append_point(b"keyYcom", params.groupWario, keyYcom)
+}
+
+/**
+ * ZK(s1, Pk: s1 R = Pk + z1 G and keyXcom, keyYcom commit to Pk)
+ *
+ * params.pair selects the NIST curve of the signature and its Tom companion.
+ *
+ * The ECDSA verification equation R = (z/s) G + (r/s) Pk is rewritten as
+ * (s/r) R = Pk + (z/r) G, so knowledge of a valid signature under the
+ * committed key is an exp proof with base R and public point Q = (z/r) G.
+ *
+ * @param params system parameters
+ * @param paramsNIST NIST params (only h is used, g is replaced by R)
+ * @param paramsWario Wario params
+ * @param msg_hash hash of the signed message
+ * @param r signature r
+ * @param s signature s
+ * @param public_key signer's public key Pk
+ * @param keyX commitment to Pk.x with paramsWario
+ * @param keyY commitment to Pk.y with paramsWario
+ * @param context caller-supplied context (e.g. a server nonce) the proof is bound to
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn prove_signature<'a>(
+    params: &SystemParameters<'a>,
+    paramsNIST: &PedersenParams<'a>,
+    paramsWario: &PedersenParams<'a>,
+    msg_hash: &BigNum,
+    r: &BigNumRef,
+    s: &BigNumRef,
+    public_key: &EcPoint,
+    keyX: Commitment,
+    keyY: Commitment,
+    context: Option<&[u8]>,
+) -> Result<SignatureProof<'a>> {
+    prove_signature_with_rng(params, paramsNIST, paramsWario, msg_hash, r, s, public_key, keyX, keyY, context, &mut OsRng)
+}
+
+/// prove_signature with the randomness drawn from rng.
+#[allow(clippy::too_many_arguments)]
+pub fn prove_signature_with_rng<'a>(
+    params: &SystemParameters<'a>,
+    paramsNIST: &PedersenParams<'a>,
+    paramsWario: &PedersenParams<'a>,
+    msg_hash: &BigNum,
+    r: &BigNumRef,
+    s: &BigNumRef,
+    public_key: &EcPoint,
+    keyX: Commitment,
+    keyY: Commitment,
+    context: Option<&[u8]>,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Result<SignatureProof<'a>> {
+    let mut transcript = Transcript::with_hash(LABEL_SIGNATURE, params.hash);
+    transcript.append_context(context);
+    prove_signature_transcript(params, paramsNIST, paramsWario, msg_hash, r, s, public_key, keyX, keyY, &mut transcript, rng)
+}
+
+/**
+ * Derandomized prove_signature: the randomness is derived, RFC6979-style, from
+ * the signature, the randomness of keyX and keyY and the statement, so the same
+ * inputs always give the same proof.
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn prove_signature_deterministic<'a>(
+    params: &SystemParameters<'a>,
+    paramsNIST: &PedersenParams<'a>,
+    paramsWario: &PedersenParams<'a>,
+    msg_hash: &BigNum,
+    r: &BigNumRef,
+    s: &BigNumRef,
+    public_key: &EcPoint,
+    keyX: Commitment,
+    keyY: Commitment,
+    context: Option<&[u8]>,
+) -> Result<SignatureProof<'a>> {
+    let mut statement = derandomize_transcript(LABEL_SIGNATURE, params, paramsNIST, paramsWario, msg_hash, context)?;
+    statement.append_point(b"keyXcom", params.groupWario, &keyX.p)?;
+    statement.append_point(b"keyYcom", params.groupWario, &keyY.p)?;
+    let mut rng = statement.witness_rng(&[&r.to_vec(), &s.to_vec(), &keyX.r.to_vec(), &keyY.r.to_vec()])?;
+
+    prove_signature_with_rng(params, paramsNIST, paramsWario, msg_hash, r, s, public_key, keyX, keyY, context, &mut rng)
+}
+
+// prove_signature continuing transcript, which the signature list proof shares with its membership proof
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn prove_signature_transcript<'a>(
+    params: &SystemParameters<'a>,
+    paramsNIST: &PedersenParams<'a>,
+    paramsWario: &PedersenParams<'a>,
+    msg_hash: &BigNum,
+    r: &BigNumRef,
+    s: &BigNumRef,
+    public_key: &EcPoint,
+    keyX: Commitment,
+    keyY: Commitment,
+    transcript: &mut Transcript,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Result<SignatureProof<'a>> {
+
+    if !params.pair.matches(params.groupNIST, params.groupWario)? {
+        return Err(ZkError::InvalidCurve("curves not compatible"));
+    }
+
+    let mut ctx = BigNumContext::new()?;
+
+    let mut order_curve = BigNum::new()?;
+    params.groupNIST.order(&mut order_curve, &mut ctx)?;
+
+    let zero = BigNum::from_u32(0)?;
+    if !(r > &*zero && r < &*order_curve) {
+        return Err(ZkError::InvalidInput("r out of range"));
+    }
+    if !(s > &*zero && s < &*order_curve) {
+        return Err(ZkError::InvalidInput("s out of range"));
+    }
+
+    let z = truncate_to_n(msg_hash, &order_curve)?;
+
+    // R = u1 G + u2 Pk with u1 = z / s and u2 = r / s
+    let mut s_inv = BigNum::new()?;
+    s_inv.mod_inverse(s, &order_curve, &mut ctx)?;
+    let mut u1 = BigNum::new()?;
+    u1.mod_mul(&z, &s_inv, &order_curve, &mut ctx)?;
+    let mut u2 = BigNum::new()?;
+    u2.mod_mul(r, &s_inv, &order_curve, &mut ctx)?;
+
+    let mut R = EcPoint::new(params.groupNIST)?;
+    R.mul_full(params.groupNIST, &u1, public_key, &u2, &mut ctx)?;
+
+    if R.is_infinity(params.groupNIST) {
+        return Err(ZkError::InvalidInput("R is at infinity"));
+    }
+
+    let mut rx = BigNum::new()?;
+    let mut ry = BigNum::new()?;
+    R.affine_coordinates_gfp(params.groupNIST, &mut rx, &mut ry, &mut ctx)?;
+    let mut rx_mod_n = BigNum::new()?;
+    rx_mod_n.nnmod(&rx, &order_curve, &mut ctx)?;
+    if &*rx_mod_n != r {
+        return Err(ZkError::InvalidInput("signature not valid"));
+    }
+
+    // s1 = s / r and z1 = z / r
+    let mut r_inv = BigNum::new()?;
+    r_inv.mod_inverse(r, &order_curve, &mut ctx)?;
+    let mut s1 = BigNum::new()?;
+    s1.mod_mul(s, &r_inv, &order_curve, &mut ctx)?;
+    let mut z1 = BigNum::new()?;
+    z1.mod_mul(&z, &r_inv, &order_curve, &mut ctx)?;
+
+    // Q = z1 G
+    let mut Q = EcPoint::new(params.groupNIST)?;
+    Q.mul_generator(params.groupNIST, &z1, &ctx)?;
+
+    // paramsNIST.g = R. R is new for every proof, so only the table of h is
+    // reused; a table of R would cost more than it saves
+    let mut paramsR = PedersenParams::new(
+        params.groupNIST,
+        R.to_owned(params.groupNIST)?,
+        paramsNIST.h.to_owned(params.groupNIST)?);
+    paramsR.table_h = paramsNIST.table_h.clone();
+
+    // comS1 = s1 R + rs1 h
+    let rs1 = generate_random_with_rng(&order_curve, rng)?;
+    let mut s1_R = EcPoint::new(params.groupNIST)?;
+    s1_R.mul(params.groupNIST, &R, &s1, &ctx)?;
+    let rs1_h = paramsR.mul_h(&rs1)?;
+    let mut com_s1_p = EcPoint::new(params.groupNIST)?;
+    com_s1_p.add(params.groupNIST, &s1_R, &rs1_h, &mut ctx)?;
+    let comS1 = Commitment::new(params.groupNIST, com_s1_p, rs1);
+
+    let keyXcom = keyX.p.to_owned(params.groupWario)?;
+    let keyYcom = keyY.p.to_owned(params.groupWario)?;
+
+    signature_transcript(transcript, params, &z, &R, &keyXcom, &keyYcom)?;
+
+    let expProof = prov_exp_with_rng(
+        &paramsR,
+        paramsWario,
+        s1,
+        comS1.to_owned()?,
+        public_key.as_ref().to_owned(params.groupNIST)?,
+        keyX,
+        keyY,
+        params.secLevel,
+        Some(Q),
+        // the context is already in the transcript
+        None,
+        transcript,
+        rng
+    )?;
+
+    Ok(SignatureProof {
+        groupNIST: params.groupNIST,
+        groupWario: params.groupWario,
+        hash: params.hash,
+        R,
+        comS1: comS1.p,
+        keyXcom,
+        keyYcom,
+        expProof,
+    })
+}
+
+
+/**
+ * ZKP Verification ZK(s1, Pk: s1 R = Pk + z1 G)
+ *
+ * The caller is responsible for checking that pi.keyXcom and pi.keyYcom
+ * are the commitments to the expected key.
+ *
+ * @param params system parameters
+ * @param paramsNIST NIST params (only h is used, g is replaced by R)
+ * @param paramsWario Wario params
+ * @param msg_hash hash of the signed message
+ * @param pi signature proof
+ * @param context context the proof was made for
+ */
+pub fn verify_signature<'a>(
+    params: &SystemParameters<'a>,
+    paramsNIST: &PedersenParams<'a>,
+    paramsWario: &PedersenParams<'a>,
+    msg_hash: &BigNum,
+    pi: &SignatureProof<'a>,
+    context: Option<&[u8]>,
+) -> Result<bool> {
+    let mut transcript = Transcript::with_hash(LABEL_SIGNATURE, params.hash);
+    transcript.append_context(context);
+    verify_signature_transcript(params, paramsNIST, paramsWario, msg_hash, pi, &mut transcript)
+}
+
+// verify_signature replaying transcript
+pub(crate) fn verify_signature_transcript<'a>(
+    params: &SystemParameters<'a>,
+    paramsNIST: &PedersenParams<'a>,
+    paramsWario: &PedersenParams<'a>,
+    msg_hash: &BigNum,
+    pi: &SignatureProof<'a>,
+    transcript: &mut Transcript,
+) -> Result<bool> {
+
+    if !params.pair.matches(params.groupNIST, params.groupWario)? {
+        return Err(ZkError::InvalidCurve("curves not compatible"));
+    }
+
+    // The challenges of the proof were drawn with another hash function
+    if pi.hash != params.hash {
+        return Ok(false)
+    }
+
+    let mut ctx = BigNumContext::new()?;
+
+    let mut order_curve = BigNum::new()?;
+    params.groupNIST.order(&mut order_curve, &mut ctx)?;
+
+    if pi.R.is_infinity(params.groupNIST) {
+        return Ok(false)
+    }
+
+    // r = R.x mod n
+    let mut rx = BigNum::new()?;
+    let mut ry = BigNum::new()?;
+    pi.R.affine_coordinates_gfp(params.groupNIST, &mut rx, &mut ry, &mut ctx)?;
+    let mut r = BigNum::new()?;
+    r.nnmod(&rx, &order_curve, &mut ctx)?;
+    if r.num_bits() == 0 {
+        return Ok(false)
+    }
+
+    // Q = (z / r) G
+    let z = truncate_to_n(msg_hash, &order_curve)?;
+    let mut r_inv = BigNum::new()?;
+    r_inv.mod_inverse(&r, &order_curve, &mut ctx)?;
+    let mut z1 = BigNum::new()?;
+    z1.mod_mul(&z, &r_inv, &order_curve, &mut ctx)?;
+
+    let mut Q = EcPoint::new(params.groupNIST)?;
+    Q.mul_generator(params.groupNIST, &z1, &ctx)?;
+
+    signature_transcript(transcript, params, &z, &pi.R, &pi.keyXcom, &pi.keyYcom)?;
+
+    // paramsNIST.g = R
+    let paramsR = PedersenParams::new(
+        params.groupNIST,
+        pi.R.to_owned(params.groupNIST)?,
+        paramsNIST.h.to_owned(params.groupNIST)?);
+
+    verify_exp(
+        &paramsR,
+        paramsWario,
+        pi.comS1.to_owned(params.groupNIST)?,
+        pi.keyXcom.to_owned(params.groupWario)?,
+        pi.keyYcom.to_owned(params.groupWario)?,
+        &pi.expProof,
+        params.secLevel,
+        Some(Q),
+        None,
+        transcript
+    )
+}
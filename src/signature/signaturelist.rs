@@ -0,0 +1,250 @@
+use openssl::ec::{EcGroupRef, EcPoint, PointConversionForm};
+use openssl::bn::{BigNum, BigNumRef, BigNumContext};
+use rand::{CryptoRng, RngCore};
+use rand::rngs::OsRng;
+
+use crate::commit::pedersen::PedersenParams;
+use crate::commit::membership::{MembershipProof, prove_membership_with_rng, verify_membership};
+use crate::signature::signature::{SignatureProof, SystemParameters, derandomize_transcript, prove_signature_transcript, verify_signature_transcript};
+use crate::encoding::{Reader, Writer, TAG_SIGNATURE_LIST};
+use crate::error::{Result, ZkError};
+use crate::transcript::Transcript;
+
+/// Fiat-Shamir label of the signature list proof.
+pub const LABEL_SIGNATURE_LIST: &[u8] = b"zkattest/signature-list";
+
+
+
+pub struct SignatureListProof<'a> {
+    pub signatureProof: SignatureProof<'a>,
+    pub membershipProof: MembershipProof<'a>,
+}
+
+impl<'a> SignatureListProof<'a> {
+    #[allow(clippy::should_implement_trait)]
+    pub fn eq(&self, other: &Self) -> bool {
+        self.signatureProof.eq(&other.signatureProof) &&
+        self.membershipProof.eq(&other.membershipProof)
+    }
+
+    /// Encodes the proof as version || tag || signature proof || membership proof
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut w = Writer::new(TAG_SIGNATURE_LIST)?;
+        self.signatureProof.write(&mut w)?;
+        self.membershipProof.write(&mut w)?;
+        Ok(w.finish())
+    }
+
+    pub fn from_bytes(groupNIST: &'a EcGroupRef, groupWario: &'a EcGroupRef, bytes: &[u8]) -> Result<Self> {
+        let mut r = Reader::new(bytes, TAG_SIGNATURE_LIST)?;
+        let signatureProof = SignatureProof::read(&mut r, groupNIST, groupWario)?;
+        let membershipProof = MembershipProof::read(&mut r, groupWario)?;
+        r.finish()?;
+        Ok(SignatureListProof {
+            signatureProof,
+            membershipProof,
+        })
+    }
+}
+
+
+/*
+
+        UTIL::keys
+
+*/
+
+// Maps every key (x, y) of the list to x + e y, where e = H(transcript, keyXcom, keyYcom, keys)
+// binds the combination to the committed coordinates.
+fn key_values(
+    params: &SystemParameters,
+    paramsWario: &PedersenParams,
+    keyXcom: &EcPoint,
+    keyYcom: &EcPoint,
+    key_list: &[EcPoint],
+    transcript: &mut Transcript
+) -> Result<(BigNum, Vec<BigNum>)> {
+
+    let mut ctx = BigNumContext::new()?;
+
+    let mut order_curve = BigNum::new()?;
+    params.groupWario.order(&mut order_curve, &mut ctx)?;
+
+    transcript.append_params(b"paramsWario", paramsWario)?;
+    transcript.append_point(b"keyXcom", params.groupWario, keyXcom)?;
+    transcript.append_point(b"keyYcom", params.groupWario, keyYcom)?;
+    transcript.append_u64(b"keys", key_list.len() as u64);
+    for key in key_list {
+        transcript.append_point(b"key", params.groupNIST, key)?;
+    }
+    let e = transcript.challenge_scalar(b"e", params.groupWario)?;
+
+    let values = key_list.iter().map(|key| {
+        let mut x = BigNum::new()?;
+        let mut y = BigNum::new()?;
+        key.affine_coordinates_gfp(params.groupNIST, &mut x, &mut y, &mut ctx)?;
+
+        let mut e_y = BigNum::new()?;
+        e_y.mod_mul(&e, &y, &order_curve, &mut ctx)?;
+        let mut v = BigNum::new()?;
+        v.mod_add(&x, &e_y, &order_curve, &mut ctx)?;
+        Ok(v)
+    }).collect::<Result<_>>()?;
+
+    Ok((e, values))
+}
+
+
+/**
+ * ZK(Pk, s1: s1 R = Pk + z1 G and Pk in key_list)
+ *
+ * Proves knowledge of a valid signature under one of the keys of key_list
+ * without revealing which one.
+ *
+ * @param params system parameters
+ * @param paramsNIST NIST params
+ * @param paramsWario Wario params
+ * @param msg_hash hash of the signed message
+ * @param r signature r
+ * @param s signature s
+ * @param public_key signer's public key, must belong to key_list
+ * @param key_list public list of keys
+ * @param context caller-supplied context (e.g. a server nonce) the proof is bound to
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn prove_signature_list<'a>(
+    params: &SystemParameters<'a>,
+    paramsNIST: &PedersenParams<'a>,
+    paramsWario: &PedersenParams<'a>,
+    msg_hash: &BigNum,
+    r: &BigNumRef,
+    s: &BigNumRef,
+    public_key: &EcPoint,
+    key_list: &[EcPoint],
+    context: Option<&[u8]>,
+) -> Result<SignatureListProof<'a>> {
+    prove_signature_list_with_rng(params, paramsNIST, paramsWario, msg_hash, r, s, public_key, key_list, context, &mut OsRng)
+}
+
+/// prove_signature_list with the randomness drawn from rng.
+#[allow(clippy::too_many_arguments)]
+pub fn prove_signature_list_with_rng<'a>(
+    params: &SystemParameters<'a>,
+    paramsNIST: &PedersenParams<'a>,
+    paramsWario: &PedersenParams<'a>,
+    msg_hash: &BigNum,
+    r: &BigNumRef,
+    s: &BigNumRef,
+    public_key: &EcPoint,
+    key_list: &[EcPoint],
+    context: Option<&[u8]>,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Result<SignatureListProof<'a>> {
+
+    let mut ctx = BigNumContext::new()?;
+
+    let mut which = None;
+    for (i, key) in key_list.iter().enumerate() {
+        if key.eq(params.groupNIST, public_key, &mut ctx)? {
+            which = Some(i);
+            break;
+        }
+    }
+    let Some(which) = which else {
+        return Err(ZkError::InvalidInput("public key not in list"));
+    };
+
+    let mut x = BigNum::new()?;
+    let mut y = BigNum::new()?;
+    public_key.affine_coordinates_gfp(params.groupNIST, &mut x, &mut y, &mut ctx)?;
+
+    let keyX = paramsWario.commit_with_rng(&x, rng)?;
+    let mut keyY = paramsWario.commit_with_rng(&y, rng)?;
+
+    // Cv = keyX + e keyY commits to x + e y
+    let mut transcript = Transcript::with_hash(LABEL_SIGNATURE_LIST, params.hash);
+    transcript.append_context(context);
+    let (e, values) = key_values(params, paramsWario, &keyX.p, &keyY.p, key_list, &mut transcript)?;
+    let Cv = keyX.add(&keyY.mul(&e)?)?;
+
+    let membershipProof = prove_membership_with_rng(paramsWario, &values, which, Cv, &mut transcript, rng)?;
+
+    let signatureProof = prove_signature_transcript(
+        params,
+        paramsNIST,
+        paramsWario,
+        msg_hash,
+        r,
+        s,
+        public_key,
+        keyX,
+        keyY,
+        &mut transcript,
+        rng
+    )?;
+
+    Ok(SignatureListProof {
+        signatureProof,
+        membershipProof,
+    })
+}
+
+/**
+ * Derandomized prove_signature_list: the randomness is derived, RFC6979-style,
+ * from the signature, the public key and the statement, so the same inputs
+ * always give the same proof.
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn prove_signature_list_deterministic<'a>(
+    params: &SystemParameters<'a>,
+    paramsNIST: &PedersenParams<'a>,
+    paramsWario: &PedersenParams<'a>,
+    msg_hash: &BigNum,
+    r: &BigNumRef,
+    s: &BigNumRef,
+    public_key: &EcPoint,
+    key_list: &[EcPoint],
+    context: Option<&[u8]>,
+) -> Result<SignatureListProof<'a>> {
+
+    let mut ctx = BigNumContext::new()?;
+
+    let mut statement = derandomize_transcript(LABEL_SIGNATURE_LIST, params, paramsNIST, paramsWario, msg_hash, context)?;
+    statement.append_u64(b"keys", key_list.len() as u64);
+    for key in key_list {
+        statement.append_point(b"key", params.groupNIST, key)?;
+    }
+    let public_key_bytes = public_key.to_bytes(params.groupNIST, PointConversionForm::COMPRESSED, &mut ctx)?;
+    let mut rng = statement.witness_rng(&[&r.to_vec(), &s.to_vec(), &public_key_bytes])?;
+
+    prove_signature_list_with_rng(params, paramsNIST, paramsWario, msg_hash, r, s, public_key, key_list, context, &mut rng)
+}
+
+
+pub fn verify_signature_list<'a>(
+    params: &SystemParameters<'a>,
+    paramsNIST: &PedersenParams<'a>,
+    paramsWario: &PedersenParams<'a>,
+    msg_hash: &BigNum,
+    key_list: &[EcPoint],
+    pi: &SignatureListProof<'a>,
+    context: Option<&[u8]>,
+) -> Result<bool> {
+
+    let mut ctx = BigNumContext::new()?;
+
+    let sig = &pi.signatureProof;
+
+    let mut transcript = Transcript::with_hash(LABEL_SIGNATURE_LIST, params.hash);
+    transcript.append_context(context);
+    let (e, values) = key_values(params, paramsWario, &sig.keyXcom, &sig.keyYcom, key_list, &mut transcript)?;
+
+    // Cv = keyXcom + e keyYcom
+    let mut e_keyYcom = EcPoint::new(params.groupWario)?;
+    e_keyYcom.mul(params.groupWario, &sig.keyYcom, &e, &ctx)?;
+    let mut Cv = EcPoint::new(params.groupWario)?;
+    Cv.add(params.groupWario, &sig.keyXcom, &e_keyYcom, &mut ctx)?;
+
+    Ok(verify_membership(paramsWario, &values, Cv, &pi.membershipProof, &mut transcript)? &&
+       verify_signature_transcript(params, paramsNIST, paramsWario, msg_hash, sig, &mut transcript)?)
+}
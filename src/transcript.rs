@@ -0,0 +1,253 @@
+use openssl::bn::{BigNum, BigNumContext, BigNumRef};
+use openssl::ec::{EcGroupRef, EcPointRef, PointConversionForm};
+use openssl::hash::{hash, Hasher, MessageDigest};
+use openssl::pkey::PKey;
+use openssl::sign::Signer;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+use crate::commit::pedersen::PedersenParams;
+use crate::error::{Result, ZkError};
+
+
+/// Domain separator of every transcript.
+const TRANSCRIPT_DOMAIN: &[u8] = b"zkattest-transcript-v1";
+
+/// Domain separator of the derandomized prover RNG.
+const WITNESS_RNG_DOMAIN: &[u8] = b"zkattest-witness-rng-v1";
+
+
+/// Hash function of the Fiat-Shamir challenges. Its id is recorded in the
+/// header of encoded signature proofs, which only verify under the same hash.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HashFunction {
+    #[default]
+    Sha256,
+    Sha384,
+    Sha512,
+    Sha3_256,
+    /// SHAKE256 read to 64 bytes
+    Shake256,
+}
+
+impl HashFunction {
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashFunction::Sha256 => "sha256",
+            HashFunction::Sha384 => "sha384",
+            HashFunction::Sha512 => "sha512",
+            HashFunction::Sha3_256 => "sha3-256",
+            HashFunction::Shake256 => "shake256",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Self> {
+        Self::ALL.into_iter()
+            .find(|h| h.name() == name)
+            .ok_or(ZkError::MalformedProof("unknown hash function"))
+    }
+
+    /// Byte identifying the hash in encoded proofs.
+    pub fn id(&self) -> u8 {
+        match self {
+            HashFunction::Sha256 => 1,
+            HashFunction::Sha384 => 2,
+            HashFunction::Sha512 => 3,
+            HashFunction::Sha3_256 => 4,
+            HashFunction::Shake256 => 5,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Result<Self> {
+        Self::ALL.into_iter()
+            .find(|h| h.id() == id)
+            .ok_or(ZkError::MalformedProof("unknown hash function"))
+    }
+
+    const ALL: [HashFunction; 5] = [
+        HashFunction::Sha256,
+        HashFunction::Sha384,
+        HashFunction::Sha512,
+        HashFunction::Sha3_256,
+        HashFunction::Shake256,
+    ];
+
+    fn digest(&self) -> MessageDigest {
+        match self {
+            HashFunction::Sha256 => MessageDigest::sha256(),
+            HashFunction::Sha384 => MessageDigest::sha384(),
+            HashFunction::Sha512 => MessageDigest::sha512(),
+            HashFunction::Sha3_256 => MessageDigest::sha3_256(),
+            HashFunction::Shake256 => MessageDigest::shake_256(),
+        }
+    }
+
+    /// Output length in bytes.
+    pub fn size(&self) -> usize {
+        match self {
+            HashFunction::Shake256 => 64,
+            _ => self.digest().size(),
+        }
+    }
+
+    pub fn hash(&self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            // XOFs have no default output length
+            HashFunction::Shake256 => {
+                let mut h = Hasher::new(self.digest())?;
+                h.update(data)?;
+                let mut out = vec![0; self.size()];
+                h.finish_xof(&mut out)?;
+                Ok(out)
+            }
+            _ => Ok(hash(self.digest(), data)?.to_vec()),
+        }
+    }
+}
+
+
+/// Fiat-Shamir transcript shared by a proof and all of its sub-proofs.
+///
+/// The prover and the verifier append the same labeled points and scalars in
+/// the same order; every challenge depends on everything appended before it,
+/// including the earlier challenges. Sub-proofs that the verifier checks in a
+/// different order (the repetitions of the exp proof) get their own fork.
+#[derive(Clone)]
+pub struct Transcript {
+    hash: HashFunction,
+    // Digest of the previous state followed by the messages appended since
+    buf: Vec<u8>,
+}
+
+impl Transcript {
+
+    /// Starts a SHA-256 transcript for the protocol label.
+    pub fn new(label: &[u8]) -> Self {
+        Self::with_hash(label, HashFunction::Sha256)
+    }
+
+    pub fn with_hash(label: &[u8], hash: HashFunction) -> Self {
+        let mut t = Transcript { hash, buf: Vec::new() };
+        t.append_message(b"dom-sep", TRANSCRIPT_DOMAIN);
+        t.append_message(b"protocol", label);
+        t
+    }
+
+    pub fn append_message(&mut self, label: &[u8], msg: &[u8]) {
+        for bytes in [label, msg] {
+            self.buf.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+            self.buf.extend_from_slice(bytes);
+        }
+    }
+
+    pub fn append_u64(&mut self, label: &[u8], v: u64) {
+        self.append_message(label, &v.to_be_bytes());
+    }
+
+    pub fn append_scalar(&mut self, label: &[u8], s: &BigNumRef) {
+        self.append_message(label, &s.to_vec());
+    }
+
+    /// Appends the caller-supplied context (server nonce, audience, timestamp, ...)
+    /// the proof is only valid for; None is the empty context.
+    pub fn append_context(&mut self, context: Option<&[u8]>) {
+        self.append_message(b"context", context.unwrap_or_default());
+    }
+
+    /// Appends the compressed encoding of pt.
+    pub fn append_point(&mut self, label: &[u8], group: &EcGroupRef, pt: &EcPointRef) -> Result<()> {
+        let mut ctx = BigNumContext::new()?;
+        let bytes = pt.to_bytes(group, PointConversionForm::COMPRESSED, &mut ctx)?;
+        self.append_message(label, &bytes);
+        Ok(())
+    }
+
+    /// Appends the curve (p, a, b, order and generator) and the generators g, h of params.
+    pub fn append_params(&mut self, label: &[u8], params: &PedersenParams) -> Result<()> {
+        let mut ctx = BigNumContext::new()?;
+        let group = params.c;
+
+        let mut p = BigNum::new()?;
+        let mut a = BigNum::new()?;
+        let mut b = BigNum::new()?;
+        group.components_gfp(&mut p, &mut a, &mut b, &mut ctx)?;
+        let mut order_curve = BigNum::new()?;
+        group.order(&mut order_curve, &mut ctx)?;
+
+        self.append_message(label, b"curve");
+        self.append_scalar(b"p", &p);
+        self.append_scalar(b"a", &a);
+        self.append_scalar(b"b", &b);
+        self.append_scalar(b"order", &order_curve);
+        self.append_point(b"generator", group, group.generator())?;
+        self.append_point(b"g", group, &params.g)?;
+        self.append_point(b"h", group, &params.h)
+    }
+
+    /**
+     * RFC6979-style RNG of a derandomized prover: ChaCha20 seeded with
+     * HMAC-SHA256, keyed by the witness, of the transcript state. The state must
+     * already hold the whole statement: proving another statement with the same
+     * witness must not reuse the randomness.
+     * @param witness secret inputs of the prover
+     */
+    pub fn witness_rng(&self, witness: &[&[u8]]) -> Result<ChaCha20Rng> {
+        let mut key = Vec::new();
+        for w in witness {
+            key.extend_from_slice(&(w.len() as u32).to_be_bytes());
+            key.extend_from_slice(w);
+        }
+        let key = PKey::hmac(&key)?;
+
+        let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+        signer.update(WITNESS_RNG_DOMAIN)?;
+        signer.update(&self.buf)?;
+
+        let mut seed = [0u8; 32];
+        seed.copy_from_slice(&signer.sign_to_vec()?);
+        Ok(ChaCha20Rng::from_seed(seed))
+    }
+
+    /// Copy of the transcript for the index-th of several independent sub-proofs.
+    pub fn fork(&self, label: &[u8], index: u64) -> Transcript {
+        let mut t = self.clone();
+        t.append_u64(label, index);
+        t
+    }
+
+    /**
+     * Challenge scalar mod the order of group.
+     * Reduces a wide expansion of the state (the order size plus 128 bits), so
+     * the challenge is a full-order scalar, and then continues from the state
+     * that includes the challenge.
+     * @param label label of the challenge
+     * @param group curve whose order the challenge is reduced by
+     */
+    pub fn challenge_scalar(&mut self, label: &[u8], group: &EcGroupRef) -> Result<BigNum> {
+        let mut ctx = BigNumContext::new()?;
+        let mut order_curve = BigNum::new()?;
+        group.order(&mut order_curve, &mut ctx)?;
+
+        self.append_message(b"challenge", label);
+        let digest = self.hash.hash(&self.buf)?;
+
+        // H(digest || 0) || H(digest || 1) || ...
+        let wide_len = order_curve.num_bytes() as usize + 16;
+        let mut wide = Vec::with_capacity(wide_len + self.hash.size());
+        let mut counter: u32 = 0;
+        while wide.len() < wide_len {
+            let mut block = digest.clone();
+            block.extend_from_slice(&counter.to_be_bytes());
+            wide.extend_from_slice(&self.hash.hash(&block)?);
+            counter += 1;
+        }
+
+        let mut challenge = BigNum::new()?;
+        challenge.nnmod(BigNum::from_slice(&wide[..wide_len])?.as_ref(), &order_curve, &mut ctx)?;
+
+        self.buf = digest;
+        self.append_scalar(label, &challenge);
+        Ok(challenge)
+    }
+}